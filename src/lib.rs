@@ -1,6 +1,13 @@
-use std::{collections::HashMap, error::Error, ops::{Div, Mul}, str::FromStr};
-use nom::{branch::alt, bytes::complete::{escaped, is_not, tag, take_till, take_until, take_while, take_while_m_n}, character::complete::{alphanumeric1, anychar, multispace0, one_of, space0}, combinator::{opt, recognize, rest}, multi::{many0, many1, many_till, separated_list1}, sequence::{delimited, preceded, separated_pair, terminated, tuple}, IResult, Parser};
+use std::{collections::{HashMap, HashSet}, error::Error, ops::{Div, Mul}, str::FromStr, sync::{Mutex, OnceLock, PoisonError}};
+use base64::{prelude::BASE64_STANDARD, Engine};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use nom::{branch::alt, bytes::complete::{escaped, is_not, tag, take_till, take_until, take_while, take_while1, take_while_m_n}, character::complete::{alphanumeric1, anychar, line_ending, multispace0, multispace1, one_of, space0}, combinator::{consumed, eof, not, opt, peek, recognize, verify}, multi::{many0, many_till, separated_list1}, sequence::{delimited, preceded, separated_pair, terminated, tuple}, IResult, Parser};
 use nom_locate::LocatedSpan;
+use regex::{NoExpand, Regex, RegexBuilder};
+#[cfg(feature = "highlight")]
+use syntect::{html::{ClassStyle, ClassedHTMLGenerator}, parsing::SyntaxSet, util::LinesWithEndings};
+#[cfg(feature = "graphemes")]
+use unicode_segmentation::UnicodeSegmentation;
 
 ////////////////////////////////////////////////////////////////////////////////
 // Structs and types
@@ -162,10 +169,171 @@ impl FromStr for TextCase {
     }
 }
 
+/// A list of the ways in which [`Filter::Round`] can settle a value that is
+/// exactly halfway between two others.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RoundingMode {
+    /// Rounds a half away from zero.
+    ///
+    /// # Example
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter, RoundingMode};
+    ///
+    /// let filter = Filter::Round { precision: 0, mode: RoundingMode::HalfUp };
+    ///
+    /// assert_eq!(render_filter("2.5".to_string(), &filter), "3");
+    /// assert_eq!(render_filter("-2.5".to_string(), &filter), "-3");
+    /// ```
+    HalfUp,
+    /// Rounds a half towards zero.
+    ///
+    /// # Example
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter, RoundingMode};
+    ///
+    /// let filter = Filter::Round { precision: 0, mode: RoundingMode::HalfDown };
+    ///
+    /// assert_eq!(render_filter("2.5".to_string(), &filter), "2");
+    /// assert_eq!(render_filter("-2.5".to_string(), &filter), "-2");
+    /// ```
+    HalfDown,
+    /// Rounds a half to the nearest even number, also known as banker's
+    /// rounding.
+    ///
+    /// # Example
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter, RoundingMode};
+    ///
+    /// let filter = Filter::Round { precision: 0, mode: RoundingMode::HalfEven };
+    ///
+    /// assert_eq!(render_filter("1.5".to_string(), &filter), "2");
+    /// assert_eq!(render_filter("2.5".to_string(), &filter), "2");
+    /// ```
+    HalfEven,
+}
+
+impl FromStr for RoundingMode {
+    type Err = String;
+
+    /// Parse a string slice, into a `RoundingMode`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use blogs_md_easy::RoundingMode;
+    ///
+    /// assert_eq!("half_up".parse::<RoundingMode>(), Ok(RoundingMode::HalfUp));
+    /// assert_eq!("half_down".parse::<RoundingMode>(), Ok(RoundingMode::HalfDown));
+    /// assert_eq!("half_even".parse::<RoundingMode>(), Ok(RoundingMode::HalfEven));
+    /// assert_eq!("bankers".parse::<RoundingMode>(), Ok(RoundingMode::HalfEven));
+    /// assert!("sideways".parse::<RoundingMode>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "half_up" | "up" => Ok(Self::HalfUp),
+            "half_down" | "down" => Ok(Self::HalfDown),
+            "half_even" | "even" | "bankers" => Ok(Self::HalfEven),
+            _ => Err(format!("Unable to parse RoundingMode from '{}'", s)),
+        }
+    }
+}
+
 /// Predefined functions names that will be used within [`render_filter`] to
 /// convert a value.
 #[derive(Clone, Debug, PartialEq)]
 pub enum Filter {
+    // Boolean filters
+
+    /// Replace a boolean-like value with a label.
+    ///
+    /// The values `true`, `1`, `yes`, and `on` are truthy, ignoring case and
+    /// surrounding whitespace. Anything else, including an empty value, is
+    /// falsy.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let filter = Filter::Bool { truthy: "Yes".to_string(), falsy: "No".to_string() };
+    ///
+    /// assert_eq!(render_filter("true".to_string(), &filter), "Yes");
+    /// assert_eq!(render_filter("TRUE".to_string(), &filter), "Yes");
+    /// assert_eq!(render_filter("1".to_string(), &filter), "Yes");
+    /// assert_eq!(render_filter("yes".to_string(), &filter), "Yes");
+    /// assert_eq!(render_filter(" On ".to_string(), &filter), "Yes");
+    /// ```
+    ///
+    /// Every other value is falsy.
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let filter = Filter::Bool { truthy: "Yes".to_string(), falsy: "No".to_string() };
+    ///
+    /// assert_eq!(render_filter("false".to_string(), &filter), "No");
+    /// assert_eq!(render_filter("0".to_string(), &filter), "No");
+    /// assert_eq!(render_filter("off".to_string(), &filter), "No");
+    /// assert_eq!(render_filter("truthy".to_string(), &filter), "No");
+    /// assert_eq!(render_filter("".to_string(), &filter), "No");
+    /// ```
+    Bool {
+        /// The label used when the value is truthy.
+        ///
+        /// `Default: "true"`
+        ///
+        /// # Example
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, Filter, Span};
+        ///
+        /// let input = Span::new("bool = truthy: Yes, falsy: No");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert!(matches!(filter, Filter::Bool { .. }));
+        /// assert_eq!(filter, Filter::Bool { truthy: "Yes".to_string(), falsy: "No".to_string() });
+        /// ```
+        truthy: String,
+        /// The label used when the value is falsy.
+        ///
+        /// `Default: "false"`
+        ///
+        /// # Example
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, Filter, Span};
+        ///
+        /// let input = Span::new("bool");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert!(matches!(filter, Filter::Bool { .. }));
+        /// assert_eq!(filter, Filter::Bool { truthy: "true".to_string(), falsy: "false".to_string() });
+        /// ```
+        falsy: String,
+    },
+    /// Replace the value with `true` when it is empty, or only whitespace,
+    /// and `false` otherwise. Chain it with `bool` to choose the labels.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// assert_eq!(render_filter("".to_string(), &Filter::IsEmpty), "true");
+    /// assert_eq!(render_filter(" \n\t".to_string(), &Filter::IsEmpty), "true");
+    /// assert_eq!(render_filter("0".to_string(), &Filter::IsEmpty), "false");
+    /// ```
+    IsEmpty,
+    /// Replace the value with `true` when it is a finite number, ignoring
+    /// surrounding whitespace, and `false` otherwise.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// assert_eq!(render_filter("42".to_string(), &Filter::IsNumeric), "true");
+    /// assert_eq!(render_filter(" -1.5e3 ".to_string(), &Filter::IsNumeric), "true");
+    /// assert_eq!(render_filter("".to_string(), &Filter::IsNumeric), "false");
+    /// assert_eq!(render_filter("   ".to_string(), &Filter::IsNumeric), "false");
+    /// assert_eq!(render_filter("12px".to_string(), &Filter::IsNumeric), "false");
+    /// assert_eq!(render_filter("NaN".to_string(), &Filter::IsNumeric), "false");
+    /// ```
+    IsNumeric,
+
     // Maths filters
 
     /// Rounds a numeric value up to the nearest whole number.
@@ -181,6 +349,73 @@ pub enum Filter {
     /// assert_eq!(output, "2");
     /// ```
     Ceil,
+    /// Format a number of bytes as a human readable file size, to one decimal
+    /// place, using the largest unit up to terabytes.
+    ///
+    /// If the value isn't a whole number, it is returned unchanged.
+    ///
+    /// # Examples
+    /// Binary units are multiples of 1024.
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "1024".to_string();
+    /// let filter = Filter::FileSize { binary: true };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "1.0 KiB");
+    /// ```
+    ///
+    /// Decimal units are multiples of 1000.
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "1500".to_string();
+    /// let filter = Filter::FileSize { binary: false };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "1.5 KB");
+    /// ```
+    ///
+    /// A size that would round up to the next unit is shown in that unit.
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let filter = Filter::FileSize { binary: false };
+    /// assert_eq!(render_filter("999949".to_string(), &filter), "999.9 KB");
+    /// assert_eq!(render_filter("999999".to_string(), &filter), "1.0 MB");
+    ///
+    /// let filter = Filter::FileSize { binary: true };
+    /// assert_eq!(render_filter("1048524".to_string(), &filter), "1023.9 KiB");
+    /// assert_eq!(render_filter("1048575".to_string(), &filter), "1.0 MiB");
+    /// ```
+    ///
+    /// A value that isn't a whole number is left as it is.
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "large".to_string();
+    /// let output = render_filter(input, &Filter::FileSize { binary: false });
+    ///
+    /// assert_eq!(output, "large");
+    /// ```
+    FileSize {
+        /// Whether to use binary units, such as `KiB`, rather than decimal
+        /// units, such as `KB`.
+        ///
+        /// `Default: false`
+        ///
+        /// # Example
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, Filter, Span};
+        ///
+        /// let input = Span::new("filesize = binary: true");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert_eq!(filter, Filter::FileSize { binary: true });
+        /// ```
+        binary: bool,
+    },
     /// Rounds a numeric value down to the nearest whole number.
     ///
     /// # Example
@@ -194,6 +429,101 @@ pub enum Filter {
     /// assert_eq!(output, "4");
     /// ```
     Floor,
+    /// Clamp a numeric value so that it is no greater than the given value.
+    ///
+    /// If the value isn't a number, it is returned unchanged.
+    ///
+    /// `Default argument: value`
+    ///
+    /// # Example
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "150".to_string();
+    /// let filter = Filter::Max { value: 100.0 };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "100");
+    /// ```
+    Max {
+        /// The largest allowed value.
+        ///
+        /// `Default: no limit`
+        ///
+        /// # Example
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, Filter, Span};
+        ///
+        /// let input = Span::new("max = 100");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert_eq!(filter, Filter::Max { value: 100.0 });
+        /// ```
+        value: f64,
+    },
+    /// Clamp a numeric value so that it is no less than the given value.
+    ///
+    /// If the value isn't a number, it is returned unchanged.
+    ///
+    /// `Default argument: value`
+    ///
+    /// # Example
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "-5".to_string();
+    /// let filter = Filter::Min { value: 0.0 };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "0");
+    /// ```
+    Min {
+        /// The smallest allowed value.
+        ///
+        /// `Default: no limit`
+        ///
+        /// # Example
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, Filter, Span};
+        ///
+        /// let input = Span::new("min = 0");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert_eq!(filter, Filter::Min { value: 0.0 });
+        /// ```
+        value: f64,
+    },
+    /// Appends the English ordinal suffix to a whole number, such as `1st`,
+    /// `2nd`, `3rd`, and `4th`.
+    ///
+    /// If the value isn't a whole number, it is returned unchanged.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let ordinal = |input: &str| render_filter(input.to_string(), &Filter::Ordinal);
+    ///
+    /// assert_eq!(ordinal("1"), "1st");
+    /// assert_eq!(ordinal("2"), "2nd");
+    /// assert_eq!(ordinal("3"), "3rd");
+    /// assert_eq!(ordinal("4"), "4th");
+    /// assert_eq!(ordinal("11"), "11th");
+    /// assert_eq!(ordinal("12"), "12th");
+    /// assert_eq!(ordinal("13"), "13th");
+    /// assert_eq!(ordinal("21"), "21st");
+    /// ```
+    ///
+    /// A value that isn't a whole number is left as it is.
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "first".to_string();
+    /// let output = render_filter(input, &Filter::Ordinal);
+    ///
+    /// assert_eq!(output, "first");
+    /// ```
+    Ordinal,
     /// Round a number to a given precision.
     ///
     /// `Default argument: precision`
@@ -201,10 +531,10 @@ pub enum Filter {
     /// # Examples
     /// Precision of 0 to remove decimal place.
     /// ```rust
-    /// use blogs_md_easy::{render_filter, Filter};
+    /// use blogs_md_easy::{render_filter, Filter, RoundingMode};
     ///
     /// let input = "1.234".to_string();
-    /// let filter = Filter::Round { precision: 0 };
+    /// let filter = Filter::Round { precision: 0, mode: RoundingMode::HalfUp };
     /// let output = render_filter(input, &filter);
     ///
     /// assert_eq!(output, "1");
@@ -212,1226 +542,4750 @@ pub enum Filter {
     ///
     /// Precision of 3 for three decimal places.
     /// ```rust
-    /// use blogs_md_easy::{render_filter, Filter};
+    /// use blogs_md_easy::{render_filter, Filter, RoundingMode};
     ///
     /// let input = "1.23456789".to_string();
-    /// let filter = Filter::Round { precision: 3 };
+    /// let filter = Filter::Round { precision: 3, mode: RoundingMode::HalfUp };
     /// let output = render_filter(input, &filter);
     ///
     /// assert_eq!(output, "1.235");
     /// ```
+    ///
+    /// Precision of -1 to round to the nearest ten.
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter, RoundingMode};
+    ///
+    /// let input = "1234".to_string();
+    /// let filter = Filter::Round { precision: -1, mode: RoundingMode::HalfUp };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "1230");
+    /// ```
+    ///
+    /// Precision of -2 to round to the nearest hundred.
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter, RoundingMode};
+    ///
+    /// let input = "1234".to_string();
+    /// let filter = Filter::Round { precision: -2, mode: RoundingMode::HalfUp };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "1200");
+    /// ```
     Round {
         /// The number of decimal places to round to.
-        /// A half is rounded down.
+        /// A negative precision rounds to places before the decimal point,
+        /// such that `-2` rounds to the nearest hundred.
         ///
         /// `Default: 0`
         ///
         /// # Examples
         /// Providing no arguments.
         /// ```rust
-        /// use blogs_md_easy::{parse_filter, Filter, Span};
+        /// use blogs_md_easy::{parse_filter, Filter, RoundingMode, Span};
         ///
         /// let input = Span::new("round");
         /// let (_, filter) = parse_filter(input).unwrap();
         ///
         /// assert!(matches!(filter, Filter::Round { .. }));
-        /// assert_eq!(filter, Filter::Round { precision: 0 });
+        /// assert_eq!(filter, Filter::Round { precision: 0, mode: RoundingMode::HalfUp });
         /// ```
         ///
         /// Providing the default argument.
         /// ```rust
-        /// use blogs_md_easy::{parse_filter, Filter, Span};
+        /// use blogs_md_easy::{parse_filter, Filter, RoundingMode, Span};
         ///
         /// let input = Span::new("round = 3");
         /// let (_, filter) = parse_filter(input).unwrap();
         ///
         /// assert!(matches!(filter, Filter::Round { .. }));
-        /// assert_eq!(filter, Filter::Round { precision: 3 });
+        /// assert_eq!(filter, Filter::Round { precision: 3, mode: RoundingMode::HalfUp });
         /// ```
         ///
         /// Alternatively, it is possible to be more explicit.
         /// ```rust
-        /// use blogs_md_easy::{parse_filter, Filter, Span};
+        /// use blogs_md_easy::{parse_filter, Filter, RoundingMode, Span};
         ///
         /// let input = Span::new("round = precision: 42");
         /// let (_, filter) = parse_filter(input).unwrap();
         ///
         /// assert!(matches!(filter, Filter::Round { .. }));
-        /// assert_eq!(filter, Filter::Round { precision: 42 });
+        /// assert_eq!(filter, Filter::Round { precision: 42, mode: RoundingMode::HalfUp });
+        /// ```
+        ///
+        /// Negative values are also accepted.
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, Filter, RoundingMode, Span};
+        ///
+        /// let input = Span::new("round = -2");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert_eq!(filter, Filter::Round { precision: -2, mode: RoundingMode::HalfUp });
+        /// ```
+        precision: i8,
+        /// How a value exactly halfway between two others is rounded.
+        ///
+        /// `Default: half_up`
+        ///
+        /// # Examples
+        /// Providing a mode for banker's rounding.
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, Filter, RoundingMode, Span};
+        ///
+        /// let input = Span::new("round = precision: 1, mode: half_even");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert_eq!(filter, Filter::Round { precision: 1, mode: RoundingMode::HalfEven });
+        /// ```
+        ///
+        /// An unknown mode falls back to the default.
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, Filter, RoundingMode, Span};
+        ///
+        /// let input = Span::new("round = mode: sideways");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert_eq!(filter, Filter::Round { precision: 0, mode: RoundingMode::HalfUp });
         /// ```
-        precision: u8,
+        mode: RoundingMode,
     },
-
-    // String filter
-
-    /// Converts a string from Markdown into HTML.
+    /// Remove the grouping separators from a number, such as `1,234.5`
+    /// becoming `1234.5`, so that it can be used in further calculations.
     ///
-    /// # Example
-    /// ```rust
-    /// use blogs_md_easy::{render_filter, Filter};
+    /// The other maths filters already ignore commas, so this is only needed
+    /// for other separators, or for outputting the number itself.
     ///
-    /// let input = r#"# Markdown Title
-    /// First paragraph.
+    /// If the value isn't a number, it is returned unchanged.
     ///
-    /// [example.com](https://example.com)
+    /// `Default argument: separator`
     ///
-    /// * Unordered list
+    /// # Examples
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
     ///
-    /// 1. Ordered list"#.to_string();
-    /// let filter = Filter::Markdown;
+    /// let input = "1,234,567".to_string();
+    /// let filter = Filter::Unformat { separator: ",".to_string() };
     /// let output = render_filter(input, &filter);
     ///
-    /// assert_eq!(output, r#"<h1>Markdown Title</h1>
-    /// <p>First paragraph.</p>
-    /// <p><a href="https://example.com">example.com</a></p>
-    /// <ul>
-    /// <li>Unordered list</li>
-    /// </ul>
-    /// <ol>
-    /// <li>Ordered list</li>
-    /// </ol>"#);
+    /// assert_eq!(output, "1234567");
     /// ```
-    Markdown,
-    /// Replace a given substring with another. Optionally, limit the number of
-    /// replacements from the start of the string.
     ///
-    /// `Default argument: find`
-    ///
-    /// # Example
+    /// Each character of the separator is removed.
     /// ```rust
     /// use blogs_md_easy::{render_filter, Filter};
     ///
-    /// let input = "Hello, World!".to_string();
-    /// let filter = Filter::Replace {
-    ///     find: "World".to_string(),
-    ///     replacement: "Rust".to_string(),
-    ///     limit: None,
-    /// };
+    /// let input = "1 234'567.5".to_string();
+    /// let filter = Filter::Unformat { separator: " '".to_string() };
     /// let output = render_filter(input, &filter);
     ///
-    /// assert_eq!(output, "Hello, Rust!");
+    /// assert_eq!(output, "1234567.5");
     /// ```
-    Replace {
-        /// The substring that we are looking for.
-        find: String,
-        /// The substring that will replace what we `find`.
-        replacement: String,
-        /// Limit the number of replacements from the start of the string.
+    Unformat {
+        /// The grouping separator characters to remove.
         ///
-        /// `Default: None`
+        /// `Default: ,`
         ///
-        /// # Examples
-        /// Without an argument, this will default to doing nothing.
+        /// # Example
         /// ```rust
-        /// use blogs_md_easy::{parse_placeholder, render_filter, Filter, Span};
-        ///
-        /// let input = Span::new("{{ £greeting | replace }}");
-        /// let (_, placeholder) = parse_placeholder(input).unwrap();
-        ///
-        /// assert!(matches!(placeholder.filters[0], Filter::Replace { .. }));
-        /// assert_eq!(placeholder.filters[0], Filter::Replace {
-        ///     find: "".to_string(),
-        ///     replacement: "".to_string(),
-        ///     limit: None,
-        /// });
-        ///
-        /// let greeting = "Hello, World!".to_string();
-        /// // Cloning here, only so we can reuse the `greeting` variable in
-        /// // assert, to prove that they are identical.
-        /// let output = render_filter(greeting.clone(), &placeholder.filters[0]);
-        /// assert_eq!(output, greeting);
-        /// ```
-        ///
-        /// Providing the default argument.
-        /// In this case the value will be assigned to `find`, and the
-        /// `replacement` will be an empty String, essentially removing this
-        /// phrase from the string.
-        /// ```rust
-        /// use blogs_md_easy::{parse_placeholder, render_filter, Filter, Span};
-        ///
-        /// let input = Span::new("{{ £greeting | replace = World }}");
-        /// let (_, placeholder) = parse_placeholder(input).unwrap();
+        /// use blogs_md_easy::{parse_filter, Filter, Span};
         ///
-        /// assert!(matches!(placeholder.filters[0], Filter::Replace { .. }));
-        /// assert_eq!(placeholder.filters[0], Filter::Replace {
-        ///     find: "World".to_string(),
-        ///     replacement: "".to_string(),
-        ///     limit: None,
-        /// });
+        /// let input = Span::new("unformat = \"_\"");
+        /// let (_, filter) = parse_filter(input).unwrap();
         ///
-        /// let greeting = "Hello, World!".to_string();
-        /// let output = render_filter(greeting, &placeholder.filters[0]);
-        /// assert_eq!(output, "Hello, !".to_string());
+        /// assert_eq!(filter, Filter::Unformat { separator: "_".to_string() });
         /// ```
+        separator: String,
+    },
+
+    // String filter
+
+    /// Take the content after a `<!-- more -->` marker, such as for the rest of
+    /// an article after its teaser. This is the opposite of `BeforeMore`.
+    ///
+    /// `Default argument: marker`
+    ///
+    /// # Examples
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "The teaser.\n\n<!-- more -->\n\nThe rest.".to_string();
+    /// let filter = Filter::AfterMore { marker: "<!-- more -->".to_string() };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "The rest.");
+    /// ```
+    ///
+    /// Without the marker, there is nothing after it.
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "No teaser.".to_string();
+    /// let filter = Filter::AfterMore { marker: "<!-- more -->".to_string() };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "");
+    /// ```
+    AfterMore {
+        /// The marker that separates the teaser from the rest of the content.
         ///
-        /// Specify the number of replacements.
-        /// ```rust
-        /// use blogs_md_easy::{parse_placeholder, render_filter, Filter, Span};
-        ///
-        /// let input = Span::new("{{ £greeting | replace = !, limit: 2 }}");
-        /// let (_, placeholder) = parse_placeholder(input).unwrap();
-        ///
-        /// assert!(matches!(placeholder.filters[0], Filter::Replace { .. }));
-        /// assert_eq!(placeholder.filters[0], Filter::Replace {
-        ///     find: "!".to_string(),
-        ///     replacement: "".to_string(),
-        ///     limit: Some(2),
-        /// });
-        ///
-        /// let greeting = "Hello, World!!!".to_string();
-        /// let output = render_filter(greeting, &placeholder.filters[0]);
-        /// assert_eq!(output, "Hello, World!".to_string());
-        /// ```
+        /// `Default: <!-- more -->`
         ///
-        /// Setting all arguments explicitly.
+        /// # Example
         /// ```rust
-        /// use blogs_md_easy::{parse_placeholder, render_filter, Filter, Span};
-        ///
-        /// let input = Span::new("{{ £greeting | replace = find: World, replacement: Rust, limit: 1 }}");
-        /// let (_, placeholder) = parse_placeholder(input).unwrap();
+        /// use blogs_md_easy::{parse_filter, Filter, Span};
         ///
-        /// assert!(matches!(placeholder.filters[0], Filter::Replace { .. }));
-        /// assert_eq!(placeholder.filters[0], Filter::Replace {
-        ///     find: "World".to_string(),
-        ///     replacement: "Rust".to_string(),
-        ///     limit: Some(1),
-        /// });
+        /// let input = Span::new("after_more = \"<!-- cut -->\"");
+        /// let (_, filter) = parse_filter(input).unwrap();
         ///
-        /// let greeting = "Hello, World! Hello, World!".to_string();
-        /// let output = render_filter(greeting, &placeholder.filters[0]);
-        /// assert_eq!(output, "Hello, Rust! Hello, World!".to_string());
+        /// assert_eq!(filter, Filter::AfterMore { marker: "<!-- cut -->".to_string() });
         /// ```
-        limit: Option<u8>,
+        marker: String,
     },
-    /// Reverse a string, character by character.
+    /// Encode a value as standard, padded, Base64.
     ///
     /// # Example
     /// ```rust
     /// use blogs_md_easy::{render_filter, Filter};
     ///
-    /// let input = "Hello, World!".to_string();
-    /// let filter = Filter::Reverse;
+    /// let input = "hello".to_string();
+    /// let filter = Filter::Base64Encode;
     /// let output = render_filter(input, &filter);
     ///
-    /// assert_eq!(output, "!dlroW ,olleH");
+    /// assert_eq!(output, "aGVsbG8=");
     /// ```
-    Reverse,
-    /// Converts text to another format.
+    Base64Encode,
+    /// Decode a standard, padded, Base64 value.
     ///
-    /// Currently, the only argument is `case`.
+    /// If the value isn't valid Base64, or doesn't decode into valid UTF-8,
+    /// then the value is returned unchanged, rather than failing the render.
     ///
-    /// `Default argument: case`
+    /// # Examples
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
     ///
-    /// # Example
+    /// let input = "aGVsbG8=".to_string();
+    /// let filter = Filter::Base64Decode;
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "hello");
+    /// ```
+    ///
+    /// An invalid value is left as it is.
     /// ```rust
-    /// use blogs_md_easy::{render_filter, Filter, TextCase};
+    /// use blogs_md_easy::{render_filter, Filter};
     ///
-    /// let input = "Hello, World!".to_string();
-    /// let filter = Filter::Text { case: TextCase::Upper };
+    /// let input = "not base64!".to_string();
+    /// let filter = Filter::Base64Decode;
     /// let output = render_filter(input, &filter);
     ///
-    /// assert_eq!(output, "HELLO, WORLD!");
+    /// assert_eq!(output, "not base64!");
     /// ```
-    Text {
-        /// Specifies the [`TextCase`] that the font should use.
-        ///
-        /// `Default: lower`
-        ///
-        /// # Examples
-        /// Without an argument, this will default to lowercase.
-        /// ```rust
-        /// use blogs_md_easy::{parse_filter, Filter, Span, TextCase};
-        ///
-        /// let input = Span::new("text");
-        /// let (_, filter) = parse_filter(input).unwrap();
-        ///
-        /// assert!(matches!(filter, Filter::Text { .. }));
-        /// assert_eq!(filter, Filter::Text { case: TextCase::Lower });
-        /// ```
-        ///
-        /// Passing in a case, without an argument is possible too.
-        /// ```rust
-        /// use blogs_md_easy::{parse_filter, Filter, Span, TextCase};
-        ///
-        /// let input = Span::new("text = upper");
-        /// let (_, filter) = parse_filter(input).unwrap();
+    Base64Decode,
+    /// Take the content before a `<!-- more -->` marker, such as for the
+    /// teaser of an article on a listing page.
+    ///
+    /// `Default argument: marker`
+    ///
+    /// # Examples
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "The teaser.\n\n<!-- more -->\n\nThe rest.".to_string();
+    /// let filter = Filter::BeforeMore { marker: "<!-- more -->".to_string() };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "The teaser.");
+    /// ```
+    ///
+    /// Without the marker, the whole content is returned.
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "No teaser.".to_string();
+    /// let filter = Filter::BeforeMore { marker: "<!-- more -->".to_string() };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "No teaser.");
+    /// ```
+    BeforeMore {
+        /// The marker that separates the teaser from the rest of the content.
         ///
-        /// assert!(matches!(filter, Filter::Text { .. }));
-        /// assert_eq!(filter, Filter::Text { case: TextCase::Upper });
-        /// ```
+        /// `Default: <!-- more -->`
         ///
-        /// Alternatively, it is possible to be more explicit.
+        /// # Example
         /// ```rust
-        /// use blogs_md_easy::{parse_filter, Filter, Span, TextCase};
+        /// use blogs_md_easy::{parse_filter, Filter, Span};
         ///
-        /// let input = Span::new("text = case: snake");
+        /// let input = Span::new("before_more = \"<!-- cut -->\"");
         /// let (_, filter) = parse_filter(input).unwrap();
         ///
-        /// assert!(matches!(filter, Filter::Text { .. }));
-        /// assert_eq!(filter, Filter::Text { case: TextCase::Snake });
+        /// assert_eq!(filter, Filter::BeforeMore { marker: "<!-- cut -->".to_string() });
         /// ```
-        case: TextCase,
+        marker: String,
     },
-    /// Truncates a string to a given length, and applies a `trail`ing string,
-    /// if the string was truncated.
+    /// Wrap the value in a CDATA section, so that HTML can be embedded in XML,
+    /// such as the `<description>` of an RSS item, without escaping it.
     ///
-    /// `Default argument: characters`
+    /// A CDATA section ends at the first `]]>`, so any in the value are split
+    /// across two sections.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "<p>Hello, World!</p>".to_string();
+    /// let filter = Filter::Cdata;
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "<![CDATA[<p>Hello, World!</p>]]>");
+    /// ```
+    ///
+    /// An embedded `]]>` is split, so that it can't end the section early.
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "<code>a[b[0]]>c</code>".to_string();
+    /// let filter = Filter::Cdata;
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "<![CDATA[<code>a[b[0]]]]><![CDATA[>c</code>]]>");
+    /// ```
+    Cdata,
+    /// Collapse every run of whitespace, including tabs and newlines, into a
+    /// single space, and trim the ends.
+    ///
+    /// This is useful before truncating content into an excerpt.
     ///
     /// # Example
     /// ```rust
     /// use blogs_md_easy::{render_filter, Filter};
     ///
-    /// let input = "Hello, World!".to_string();
-    /// let filter = Filter::Truncate { characters: 5, trail: "...".to_string() };
+    /// let input = "  First\tline.  \n\n\nSecond  line.  ".to_string();
+    /// let filter = Filter::CollapseWhitespace;
     /// let output = render_filter(input, &filter);
     ///
-    /// assert_eq!(output, "Hello...");
+    /// assert_eq!(output, "First line. Second line.");
     /// ```
-    Truncate {
-        /// The number of characters the String will be cut to.
-        ///
-        /// If this number is greater than the String's length, then nothing
-        /// happens to the String.
+    CollapseWhitespace,
+    /// Count how many times a `needle` appears in the value.
+    ///
+    /// Matches do not overlap, and an empty `needle` counts as `0`.
+    ///
+    /// `Default argument: needle`
+    ///
+    /// # Example
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "the cat sat on the mat by the door".to_string();
+    /// let filter = Filter::Count { needle: "the".to_string() };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "3");
+    /// ```
+    Count {
+        /// The substring to count.
         ///
-        /// `Default: 100`
+        /// `Default: ""`
         ///
         /// # Example
         /// ```rust
         /// use blogs_md_easy::{parse_filter, Filter, Span};
         ///
-        /// let input = Span::new("truncate = trail: --");
+        /// let input = Span::new("count = the");
         /// let (_, filter) = parse_filter(input).unwrap();
         ///
-        /// assert!(matches!(filter, Filter::Truncate { .. }));
-        /// assert_eq!(filter, Filter::Truncate {
-        ///     characters: 100,
-        ///     trail: "--".to_string(),
-        /// });
+        /// assert!(matches!(filter, Filter::Count { .. }));
+        /// assert_eq!(filter, Filter::Count { needle: "the".to_string() });
         /// ```
-        characters: u8,
-        /// The trailing characters to be appended to a truncated String.
-        ///
-        /// Due to this being appended, that means that your string will exceed
-        /// the characters length.  \
-        /// To counter this, you will need to reduce your `characters` value.
+        needle: String,
+    },
+    /// Remove the leading whitespace that every line has in common, such as
+    /// from a block that was copied with its indentation.
+    ///
+    /// Lines that are blank, or only whitespace, don't count towards the
+    /// common indentation, and are emptied.
+    ///
+    /// # Example
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "    fn main() {\n      println!(\"Hi\");\n  \n    }".to_string();
+    /// let output = render_filter(input, &Filter::Dedent);
+    ///
+    /// assert_eq!(output, "fn main() {\n  println!(\"Hi\");\n\n}");
+    /// ```
+    Dedent,
+    /// Escape the characters that have a special meaning in XML, so that a
+    /// value can be placed in a feed, such as the `<title>` of an RSS item.
+    ///
+    /// # Example
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "Tom & Jerry's <\"Great\"> Escape".to_string();
+    /// let filter = Filter::EscapeXml;
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "Tom &amp; Jerry&apos;s &lt;&quot;Great&quot;&gt; Escape");
+    /// ```
+    EscapeXml,
+    /// Use the value of another variable when this value is empty, such as
+    /// falling back to the `title` when there is no `subtitle`.
+    ///
+    /// This filter needs every variable, so only has an effect when used with
+    /// [`render_filter_with_variables`]; [`render_filter`] leaves the value as
+    /// it is.
+    ///
+    /// `Default argument: key`
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::collections::HashMap;
+    /// use blogs_md_easy::{render_filter_with_variables, Filter};
+    ///
+    /// let variables = HashMap::from([("title".to_string(), "My Title".to_string())]);
+    /// let filter = Filter::Fallback { key: "title".to_string() };
+    ///
+    /// assert_eq!(render_filter_with_variables("".to_string(), &filter, &variables), "My Title");
+    /// assert_eq!(render_filter_with_variables("  ".to_string(), &filter, &variables), "My Title");
+    /// assert_eq!(render_filter_with_variables("My Subtitle".to_string(), &filter, &variables), "My Subtitle");
+    /// ```
+    ///
+    /// If the other variable doesn't exist either, then the value is left empty.
+    /// ```rust
+    /// use std::collections::HashMap;
+    /// use blogs_md_easy::{render_filter_with_variables, Filter};
+    ///
+    /// let filter = Filter::Fallback { key: "missing".to_string() };
+    /// assert_eq!(render_filter_with_variables("".to_string(), &filter, &HashMap::new()), "");
+    /// ```
+    Fallback {
+        /// The name of the variable to use instead, without the `£`.
         ///
-        /// `Default: "..."`
+        /// `Default: ""`
         ///
         /// # Example
         /// ```rust
         /// use blogs_md_easy::{parse_filter, Filter, Span};
         ///
-        /// let input = Span::new("truncate = characters: 42");
+        /// let input = Span::new("fallback = title");
         /// let (_, filter) = parse_filter(input).unwrap();
         ///
-        /// assert!(matches!(filter, Filter::Truncate { .. }));
-        /// assert_eq!(filter, Filter::Truncate {
-        ///     characters: 42,
-        ///     trail: "...".to_string(),
-        /// });
+        /// assert!(matches!(filter, Filter::Fallback { .. }));
+        /// assert_eq!(filter, Filter::Fallback { key: "title".to_string() });
+        ///
+        /// // The key may be written as a variable.
+        /// let input = Span::new("coalesce = key: £title");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        /// assert_eq!(filter, Filter::Fallback { key: "title".to_string() });
         /// ```
-        trail: String,
-    }
-}
+        key: String,
+    },
+    /// Get the first item from a list of values, separated by a `separator`.
+    ///
+    /// As with [`Filter::Join`], the list defaults to being newline separated,
+    /// which is what [`Filter::Split`] produces. Each item is trimmed, and empty
+    /// items are ignored.
+    ///
+    /// `Default argument: separator`
+    ///
+    /// # Examples
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "rust\ncli\nmarkdown".to_string();
+    /// let filter = Filter::First { separator: "\n".to_string() };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "rust");
+    /// ```
+    ///
+    /// A value can be split on another separator directly.
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "rust, cli, markdown".to_string();
+    /// let filter = Filter::First { separator: ",".to_string() };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "rust");
+    /// ```
+    ///
+    /// A value without the separator is a single item, so is returned as is.
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "rust".to_string();
+    /// let filter = Filter::First { separator: ",".to_string() };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "rust");
+    /// ```
+    ///
+    /// An empty string has no items, so remains empty.
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "".to_string();
+    /// let filter = Filter::First { separator: ",".to_string() };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "");
+    /// ```
+    First {
+        /// The string that separates each item.
+        ///
+        /// `Default: "\n"`
+        ///
+        /// # Example
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, Filter, Span};
+        ///
+        /// let input = Span::new("first");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        /// assert_eq!(filter, Filter::First { separator: "\n".to_string() });
+        ///
+        /// let input = Span::new("first = ';'");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert!(matches!(filter, Filter::First { .. }));
+        /// assert_eq!(filter, Filter::First { separator: ";".to_string() });
+        /// ```
+        separator: String,
+    },
+    /// Take the first paragraph of the value, up to the first blank line, such
+    /// as for the excerpt of an article.
+    ///
+    /// Apply it before `markdown`, which is where `£content` has it applied by
+    /// default. If there is no blank line, then the whole value is returned.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "The first paragraph,\nover two lines.\n\nThe second paragraph.\n\nThe third.".to_string();
+    /// let output = render_filter(input, &Filter::FirstParagraph);
+    ///
+    /// assert_eq!(output, "The first paragraph,\nover two lines.");
+    /// ```
+    ///
+    /// Leading blank lines are skipped, and a value with only one paragraph is
+    /// returned whole.
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "\n  \nOnly one paragraph.\n".to_string();
+    /// let output = render_filter(input, &Filter::FirstParagraph);
+    ///
+    /// assert_eq!(output, "Only one paragraph.");
+    /// ```
+    FirstParagraph,
+    /// Place the value into a format string, replacing the first `{}`.
+    ///
+    /// Use `{{` and `}}` for literal braces. If there is no `{}`, then the value
+    /// is appended to the end.
+    ///
+    /// `Default argument: template`
+    ///
+    /// # Examples
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "World".to_string();
+    /// let filter = Filter::Format { template: "Hello, {}!".to_string() };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "Hello, World!");
+    /// ```
+    ///
+    /// Doubled braces are literal, and only the first `{}` is replaced.
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let filter = Filter::Format { template: "{{{}}} and {}".to_string() };
+    /// assert_eq!(render_filter("value".to_string(), &filter), "{value} and {}");
+    /// ```
+    ///
+    /// Without a `{}`, the value is appended.
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let filter = Filter::Format { template: "Posted by ".to_string() };
+    /// assert_eq!(render_filter("Jane".to_string(), &filter), "Posted by Jane");
+    /// ```
+    Format {
+        /// The format string, containing a `{}` where the value is placed.
+        ///
+        /// `Default: "{}"`
+        ///
+        /// # Example
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, Filter, Span};
+        ///
+        /// let input = Span::new("fmt = \"Hello, {}!\"");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert!(matches!(filter, Filter::Format { .. }));
+        /// assert_eq!(filter, Filter::Format { template: "Hello, {}!".to_string() });
+        /// ```
+        template: String,
+    },
+    /// Wrap every occurrence of a `term` in a `tag`, ignoring case, such as to
+    /// highlight the words that were searched for.
+    ///
+    /// When the value is HTML, only the text between tags is searched, so tag
+    /// names and attributes are left alone. However, a term that spans more
+    /// than one element won't be matched, and a term may still match inside
+    /// an entity, such as `amp` in `&amp;`.
+    ///
+    /// `Default argument: term`
+    ///
+    /// # Examples
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "Rust is fast, and rust is safe.".to_string();
+    /// let filter = Filter::Highlight { term: "rust".to_string(), tag: "mark".to_string() };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "<mark>Rust</mark> is fast, and <mark>rust</mark> is safe.");
+    /// ```
+    ///
+    /// The term is matched literally, rather than as a regular expression.
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "C++ and C".to_string();
+    /// let filter = Filter::Highlight { term: "C++".to_string(), tag: "strong".to_string() };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "<strong>C++</strong> and C");
+    /// ```
+    ///
+    /// Tags and their attributes are not highlighted.
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = r#"<a href="/rust">Learn Rust</a>"#.to_string();
+    /// let filter = Filter::Highlight { term: "rust".to_string(), tag: "mark".to_string() };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, r#"<a href="/rust">Learn <mark>Rust</mark></a>"#);
+    /// ```
+    Highlight {
+        /// The text to highlight. An empty term leaves the value unchanged.
+        ///
+        /// `Default: ""`
+        ///
+        /// # Example
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, Filter, Span};
+        ///
+        /// let input = Span::new("highlight = rust");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert_eq!(filter, Filter::Highlight { term: "rust".to_string(), tag: "mark".to_string() });
+        /// ```
+        term: String,
+        /// The name of the element to wrap each occurrence in. Anything other
+        /// than a name, such as `mark class=x`, falls back to the default, both
+        /// when parsed and when rendered.
+        ///
+        /// `Default: mark`
+        ///
+        /// # Example
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, Filter, Span};
+        ///
+        /// let input = Span::new("highlight_term = term: rust, tag: strong");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert_eq!(filter, Filter::Highlight { term: "rust".to_string(), tag: "strong".to_string() });
+        /// ```
+        tag: String,
+    },
+    /// Indent each line of a value by a number of `spaces`, optionally leaving
+    /// the `first` line as it is.
+    ///
+    /// Blank lines are never indented, so that no trailing whitespace is added.
+    ///
+    /// `Default argument: spaces`
+    ///
+    /// # Examples
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "first line\nsecond line".to_string();
+    /// let filter = Filter::Indent { spaces: 4, first: true };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "    first line\n    second line");
+    /// ```
+    ///
+    /// The first line can be left alone, for when the placeholder itself is
+    /// already indented.
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "first line\n\nthird line".to_string();
+    /// let filter = Filter::Indent { spaces: 2, first: false };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "first line\n\n  third line");
+    /// ```
+    Indent {
+        /// The number of spaces to add to the start of each line.
+        ///
+        /// `Default: 4`
+        ///
+        /// # Example
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, Filter, Span};
+        ///
+        /// let input = Span::new("indent = 2");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert!(matches!(filter, Filter::Indent { .. }));
+        /// assert_eq!(filter, Filter::Indent { spaces: 2, first: true });
+        /// ```
+        spaces: u8,
+        /// Whether the first line is indented too.
+        ///
+        /// `Default: true`
+        ///
+        /// # Example
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, Filter, Span};
+        ///
+        /// let input = Span::new("indent = spaces: 4, first: false");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert!(matches!(filter, Filter::Indent { .. }));
+        /// assert_eq!(filter, Filter::Indent { spaces: 4, first: false });
+        /// ```
+        first: bool,
+    },
+    /// Escape a value so that it can be placed inside a JSON string.
+    ///
+    /// Double quotes, backslashes, and control characters are escaped, but no
+    /// surrounding quotes are added.
+    ///
+    /// # Example
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "The \"Best\" Post\nEver".to_string();
+    /// let filter = Filter::JsonEscape;
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, r#"The \"Best\" Post\nEver"#);
+    /// ```
+    JsonEscape,
+    /// Join a list of values together with a `separator`.
+    ///
+    /// Lists are stored as a newline separated String, which is what
+    /// [`Filter::Split`] produces. Therefore, each line is considered an item.
+    ///
+    /// `Default argument: separator`
+    ///
+    /// # Example
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "rust\ncli\nmarkdown".to_string();
+    /// let filter = Filter::Join { separator: " | ".to_string() };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "rust | cli | markdown");
+    /// ```
+    Join {
+        /// The string placed between each item.
+        ///
+        /// `Default: ", "`
+        ///
+        /// # Example
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, Filter, Span};
+        ///
+        /// let input = Span::new("join");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert!(matches!(filter, Filter::Join { .. }));
+        /// assert_eq!(filter, Filter::Join { separator: ", ".to_string() });
+        /// ```
+        separator: String,
+    },
+    /// Get the last item from a list of values, separated by a `separator`.
+    ///
+    /// As with [`Filter::Join`], the list defaults to being newline separated,
+    /// which is what [`Filter::Split`] produces. Each item is trimmed, and empty
+    /// items are ignored.
+    ///
+    /// `Default argument: separator`
+    ///
+    /// # Examples
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "rust\ncli\nmarkdown".to_string();
+    /// let filter = Filter::Last { separator: "\n".to_string() };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "markdown");
+    /// ```
+    ///
+    /// A value can be split on another separator directly.
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "rust, cli, markdown".to_string();
+    /// let filter = Filter::Last { separator: ",".to_string() };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "markdown");
+    /// ```
+    ///
+    /// A value without the separator is a single item, so is returned as is.
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "rust".to_string();
+    /// let filter = Filter::Last { separator: ",".to_string() };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "rust");
+    /// ```
+    ///
+    /// An empty string has no items, so remains empty.
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "".to_string();
+    /// let filter = Filter::Last { separator: ",".to_string() };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "");
+    /// ```
+    Last {
+        /// The string that separates each item.
+        ///
+        /// `Default: "\n"`
+        ///
+        /// # Example
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, Filter, Span};
+        ///
+        /// let input = Span::new("last");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        /// assert_eq!(filter, Filter::Last { separator: "\n".to_string() });
+        ///
+        /// let input = Span::new("last = separator: '|'");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert!(matches!(filter, Filter::Last { .. }));
+        /// assert_eq!(filter, Filter::Last { separator: "|".to_string() });
+        /// ```
+        separator: String,
+    },
+    /// Converts a string from Markdown into HTML.
+    ///
+    /// # Example
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = r#"# Markdown Title
+    /// First paragraph.
+    ///
+    /// [example.com](https://example.com)
+    ///
+    /// * Unordered list
+    ///
+    /// 1. Ordered list"#.to_string();
+    /// let filter = Filter::Markdown { allow_html: true, gfm: false, anchors: false, highlight: false };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, r#"<h1>Markdown Title</h1>
+    /// <p>First paragraph.</p>
+    /// <p><a href="https://example.com">example.com</a></p>
+    /// <ul>
+    /// <li>Unordered list</li>
+    /// </ul>
+    /// <ol>
+    /// <li>Ordered list</li>
+    /// </ol>"#);
+    /// ```
+    ///
+    /// Raw HTML can be escaped, rather than passed through, for untrusted content.
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "Hello, <strong>World</strong>!".to_string();
+    /// let filter = Filter::Markdown { allow_html: false, gfm: false, anchors: false, highlight: false };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "<p>Hello, &lt;strong&gt;World&lt;/strong&gt;!</p>");
+    /// ```
+    ///
+    /// GitHub Flavoured Markdown must be enabled to render tables and strikethrough.
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "~~Old~~ New".to_string();
+    /// let filter = Filter::Markdown { allow_html: true, gfm: true, anchors: false, highlight: false };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "<p><del>Old</del> New</p>");
+    /// ```
+    ///
+    /// Headings can be given an `id`, so that they can be linked to.
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "# Hello, World!".to_string();
+    /// let filter = Filter::Markdown { allow_html: true, gfm: false, anchors: true, highlight: false };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "<h1 id=\"hello-world\">Hello, World!</h1>");
+    /// ```
+    Markdown {
+        /// Whether raw HTML in the Markdown is passed through to the output.
+        ///
+        /// `Default: true`
+        ///
+        /// # Example
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, Filter, Span};
+        ///
+        /// let input = Span::new("markdown = html: false");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert!(matches!(filter, Filter::Markdown { .. }));
+        /// assert_eq!(filter, Filter::Markdown { allow_html: false, gfm: false, anchors: false, highlight: false });
+        /// ```
+        allow_html: bool,
+        /// Whether GitHub Flavoured Markdown extensions are enabled, such as
+        /// tables, strikethrough, autolinks, and task lists.
+        ///
+        /// `Default: false`
+        ///
+        /// # Example
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, Filter, Span};
+        ///
+        /// let input = Span::new("markdown = gfm: true");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert!(matches!(filter, Filter::Markdown { .. }));
+        /// assert_eq!(filter, Filter::Markdown { allow_html: true, gfm: true, anchors: false, highlight: false });
+        /// ```
+        gfm: bool,
+        /// Whether each heading is given an `id`, based on its text, so that
+        /// it can be linked to. Headings with the same text are given a
+        /// numeric suffix.
+        ///
+        /// `Default: false`
+        ///
+        /// # Example
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, Filter, Span};
+        ///
+        /// let input = Span::new("markdown = anchors: true");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert!(matches!(filter, Filter::Markdown { .. }));
+        /// assert_eq!(filter, Filter::Markdown { allow_html: true, gfm: false, anchors: true, highlight: false });
+        /// ```
+        anchors: bool,
+        /// Whether fenced code blocks are syntax highlighted, based on the
+        /// language given after the opening fence.
+        ///
+        /// This requires the `highlight` feature, otherwise code blocks are
+        /// left as they are. See [`highlight_code_blocks`].
+        ///
+        /// `Default: false`
+        ///
+        /// # Example
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, Filter, Span};
+        ///
+        /// let input = Span::new("markdown = highlight: true");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert!(matches!(filter, Filter::Markdown { .. }));
+        /// assert_eq!(filter, Filter::Markdown { allow_html: true, gfm: false, anchors: false, highlight: true });
+        /// ```
+        highlight: bool,
+    },
+    /// Replace each match of a regular expression `pattern`. Optionally, limit
+    /// the number of replacements from the start of the string.
+    ///
+    /// This is parsed from a `replace` filter with the argument `regex: true`,
+    /// so that literal replacement remains the default.  \
+    /// The `replacement` may reference capture groups, such as `$1`.
+    ///
+    /// If the `pattern` is empty, or is not a valid regular expression, then
+    /// the value is returned unchanged.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use blogs_md_easy::{parse_filter, render_filter, Filter, Span};
+    ///
+    /// let input = Span::new(r"replace = find: \d+, replacement: #, regex: true");
+    /// let (_, filter) = parse_filter(input).unwrap();
+    /// assert_eq!(filter, Filter::RegexReplace {
+    ///     pattern: r"\d+".to_string(),
+    ///     replacement: "#".to_string(),
+    ///     limit: None,
+    ///     ignore_case: false,
+    /// });
+    ///
+    /// let output = render_filter("Call 0123 or 4567".to_string(), &filter);
+    /// assert_eq!(output, "Call # or #");
+    /// ```
+    ///
+    /// Using capture groups in the replacement.
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "2024-01-31".to_string();
+    /// let filter = Filter::RegexReplace {
+    ///     pattern: r"(\d+)-(\d+)-(\d+)".to_string(),
+    ///     replacement: "$3/$2/$1".to_string(),
+    ///     limit: None,
+    ///     ignore_case: false,
+    /// };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "31/01/2024");
+    /// ```
+    RegexReplace {
+        /// The regular expression that we are looking for.
+        pattern: String,
+        /// The string that will replace each match, which may reference
+        /// capture groups.
+        replacement: String,
+        /// Limit the number of replacements from the start of the string.
+        ///
+        /// `Default: None`
+        limit: Option<u8>,
+        /// Match the `pattern` regardless of its case.
+        ///
+        /// `Default: false`
+        ///
+        /// # Example
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, render_filter, Span};
+        ///
+        /// let input = Span::new("replace = find: w(or)ld, replacement: $1, regex: true, ignore_case: true");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        /// assert_eq!(render_filter("Hello, WORLD!".to_string(), &filter), "Hello, OR!");
+        /// ```
+        ignore_case: bool,
+    },
+    /// Remove each occurrence of `text` from the value. Optionally, limit the
+    /// number of removals from the start of the string.
+    ///
+    /// This is shorthand for a [`Filter::Replace`] with an empty
+    /// `replacement`, and is rendered using the same logic.
+    ///
+    /// `Default argument: text`
+    ///
+    /// # Examples
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "Hello, World! Hello, World!".to_string();
+    /// let filter = Filter::Remove { text: "Hello, ".to_string(), limit: None };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "World! World!");
+    /// ```
+    ///
+    /// Only remove the first occurrence.
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "Hello, World! Hello, World!".to_string();
+    /// let filter = Filter::Remove { text: "Hello, ".to_string(), limit: Some(1) };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "World! Hello, World!");
+    /// ```
+    Remove {
+        /// The substring to remove.
+        ///
+        /// `Default: ""`
+        ///
+        /// # Example
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, Filter, Span};
+        ///
+        /// let input = Span::new("remove = foo");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert!(matches!(filter, Filter::Remove { .. }));
+        /// assert_eq!(filter, Filter::Remove { text: "foo".to_string(), limit: None });
+        /// ```
+        text: String,
+        /// The maximum number of removals, from the start of the string.
+        ///
+        /// `Default: None`
+        ///
+        /// # Example
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, Filter, Span};
+        ///
+        /// let input = Span::new("remove = text: foo, limit: 2");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert!(matches!(filter, Filter::Remove { .. }));
+        /// assert_eq!(filter, Filter::Remove { text: "foo".to_string(), limit: Some(2) });
+        /// ```
+        limit: Option<u8>,
+    },
+    /// Replace a given substring with another. Optionally, limit the number of
+    /// replacements from the start of the string.
+    ///
+    /// `Default argument: find`
+    ///
+    /// # Example
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "Hello, World!".to_string();
+    /// let filter = Filter::Replace {
+    ///     find: "World".to_string(),
+    ///     replacement: "Rust".to_string(),
+    ///     limit: None,
+    ///     ignore_case: false,
+    /// };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "Hello, Rust!");
+    /// ```
+    Replace {
+        /// The substring that we are looking for.
+        find: String,
+        /// The substring that will replace what we `find`.
+        replacement: String,
+        /// Limit the number of replacements from the start of the string.
+        ///
+        /// `Default: None`
+        ///
+        /// # Examples
+        /// Without an argument, this will default to doing nothing.
+        /// ```rust
+        /// use blogs_md_easy::{parse_placeholder, render_filter, Filter, Span};
+        ///
+        /// let input = Span::new("{{ £greeting | replace }}");
+        /// let (_, placeholder) = parse_placeholder(input).unwrap();
+        ///
+        /// assert!(matches!(placeholder.filters[0], Filter::Replace { .. }));
+        /// assert_eq!(placeholder.filters[0], Filter::Replace {
+        ///     find: "".to_string(),
+        ///     replacement: "".to_string(),
+        ///     limit: None,
+        ///     ignore_case: false,
+        /// });
+        ///
+        /// let greeting = "Hello, World!".to_string();
+        /// // Cloning here, only so we can reuse the `greeting` variable in
+        /// // assert, to prove that they are identical.
+        /// let output = render_filter(greeting.clone(), &placeholder.filters[0]);
+        /// assert_eq!(output, greeting);
+        /// ```
+        ///
+        /// Providing the default argument.
+        /// In this case the value will be assigned to `find`, and the
+        /// `replacement` will be an empty String, essentially removing this
+        /// phrase from the string.
+        /// ```rust
+        /// use blogs_md_easy::{parse_placeholder, render_filter, Filter, Span};
+        ///
+        /// let input = Span::new("{{ £greeting | replace = World }}");
+        /// let (_, placeholder) = parse_placeholder(input).unwrap();
+        ///
+        /// assert!(matches!(placeholder.filters[0], Filter::Replace { .. }));
+        /// assert_eq!(placeholder.filters[0], Filter::Replace {
+        ///     find: "World".to_string(),
+        ///     replacement: "".to_string(),
+        ///     limit: None,
+        ///     ignore_case: false,
+        /// });
+        ///
+        /// let greeting = "Hello, World!".to_string();
+        /// let output = render_filter(greeting, &placeholder.filters[0]);
+        /// assert_eq!(output, "Hello, !".to_string());
+        /// ```
+        ///
+        /// Specify the number of replacements.
+        /// ```rust
+        /// use blogs_md_easy::{parse_placeholder, render_filter, Filter, Span};
+        ///
+        /// let input = Span::new("{{ £greeting | replace = !, limit: 2 }}");
+        /// let (_, placeholder) = parse_placeholder(input).unwrap();
+        ///
+        /// assert!(matches!(placeholder.filters[0], Filter::Replace { .. }));
+        /// assert_eq!(placeholder.filters[0], Filter::Replace {
+        ///     find: "!".to_string(),
+        ///     replacement: "".to_string(),
+        ///     limit: Some(2),
+        ///     ignore_case: false,
+        /// });
+        ///
+        /// let greeting = "Hello, World!!!".to_string();
+        /// let output = render_filter(greeting, &placeholder.filters[0]);
+        /// assert_eq!(output, "Hello, World!".to_string());
+        /// ```
+        ///
+        /// Setting all arguments explicitly.
+        /// ```rust
+        /// use blogs_md_easy::{parse_placeholder, render_filter, Filter, Span};
+        ///
+        /// let input = Span::new("{{ £greeting | replace = find: World, replacement: Rust, limit: 1 }}");
+        /// let (_, placeholder) = parse_placeholder(input).unwrap();
+        ///
+        /// assert!(matches!(placeholder.filters[0], Filter::Replace { .. }));
+        /// assert_eq!(placeholder.filters[0], Filter::Replace {
+        ///     find: "World".to_string(),
+        ///     replacement: "Rust".to_string(),
+        ///     limit: Some(1),
+        ///     ignore_case: false,
+        /// });
+        ///
+        /// let greeting = "Hello, World! Hello, World!".to_string();
+        /// let output = render_filter(greeting, &placeholder.filters[0]);
+        /// assert_eq!(output, "Hello, Rust! Hello, World!".to_string());
+        /// ```
+        limit: Option<u8>,
+        /// Match the `find` regardless of its case.
+        ///
+        /// `Default: false`
+        ///
+        /// # Example
+        /// ```rust
+        /// use blogs_md_easy::{parse_placeholder, render_filter, Filter, Span};
+        ///
+        /// let input = Span::new("{{ £greeting | replace = find: world, replacement: Rust, ignore_case: true }}");
+        /// let (_, placeholder) = parse_placeholder(input).unwrap();
+        ///
+        /// let greeting = "Hello, World! Hello, WORLD!".to_string();
+        /// let output = render_filter(greeting, &placeholder.filters[0]);
+        /// assert_eq!(output, "Hello, Rust! Hello, Rust!".to_string());
+        /// ```
+        ignore_case: bool,
+    },
+    /// Reverse a string, character by character.
+    ///
+    /// With the `graphemes` feature, each grapheme is kept together, so that
+    /// combining characters, such as accents, aren't separated from the
+    /// character they belong to.
+    ///
+    /// # Example
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "Hello, World!".to_string();
+    /// let filter = Filter::Reverse;
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "!dlroW ,olleH");
+    /// ```
+    Reverse,
+    /// Take the characters from `start` up to, but not including, `end`.
+    ///
+    /// Indices count characters rather than bytes, and those beyond the end of
+    /// the value are clamped to its length.
+    ///
+    /// `Default arguments: start, end`
+    ///
+    /// # Examples
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "1a2b3c4d5e6f".to_string();
+    /// let filter = Filter::Slice { start: 0, end: Some(8) };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "1a2b3c4d");
+    /// ```
+    ///
+    /// Positions are given as `start` and then `end`, when their names are
+    /// left out.
+    /// ```rust
+    /// use blogs_md_easy::{parse_filter, render_filter, Filter, Span};
+    ///
+    /// let (_, filter) = parse_filter(Span::new("slice = 0, 3")).unwrap();
+    /// assert_eq!(filter, Filter::Slice { start: 0, end: Some(3) });
+    /// assert_eq!(render_filter("£12.50".to_string(), &filter), "£12");
+    /// ```
+    ///
+    /// An `end` beyond the value is clamped, as is a `start` after the `end`.
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let filter = Filter::Slice { start: 2, end: Some(100) };
+    /// assert_eq!(render_filter("Hello".to_string(), &filter), "llo");
+    ///
+    /// let filter = Filter::Slice { start: 4, end: Some(2) };
+    /// assert_eq!(render_filter("Hello".to_string(), &filter), "");
+    /// ```
+    Slice {
+        /// The index of the first character to keep.
+        ///
+        /// `Default: 0`
+        ///
+        /// # Example
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, Filter, Span};
+        ///
+        /// let input = Span::new("slice = start: 2");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert!(matches!(filter, Filter::Slice { .. }));
+        /// assert_eq!(filter, Filter::Slice { start: 2, end: None });
+        /// ```
+        start: usize,
+        /// The index after the last character to keep, or `None` to keep the
+        /// rest of the value.
+        ///
+        /// `Default: None`
+        ///
+        /// # Example
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, Filter, Span};
+        ///
+        /// let input = Span::new("slice = start: 0, end: 8");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert!(matches!(filter, Filter::Slice { .. }));
+        /// assert_eq!(filter, Filter::Slice { start: 0, end: Some(8) });
+        /// ```
+        end: Option<usize>,
+    },
+    /// Remove the whitespace between HTML tags, so that inline elements don't
+    /// have gaps between them.
+    ///
+    /// Only whitespace that sits directly between a `>` and a `<` is removed,
+    /// so the whitespace within text is kept.
+    ///
+    /// # Example
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "<p> <a> x </a> </p>".to_string();
+    /// let filter = Filter::Spaceless;
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "<p><a> x </a></p>");
+    /// ```
+    Spaceless,
+    /// Split a value into a list, on each occurrence of a `separator`.
+    ///
+    /// Each item is trimmed, and empty items are removed. The list is stored
+    /// as a newline separated String, so that it can be passed to list filters
+    /// such as [`Filter::Join`].
+    ///
+    /// `Default argument: separator`
+    ///
+    /// # Example
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "rust, cli,markdown".to_string();
+    /// let filter = Filter::Split { separator: ",".to_string() };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "rust\ncli\nmarkdown");
+    /// ```
+    Split {
+        /// The string that separates each item.
+        ///
+        /// `Default: ","`
+        ///
+        /// # Example
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, Filter, Span};
+        ///
+        /// let input = Span::new("split = ';'");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert!(matches!(filter, Filter::Split { .. }));
+        /// assert_eq!(filter, Filter::Split { separator: ";".to_string() });
+        /// ```
+        separator: String,
+    },
+    /// Converts text to another format.
+    ///
+    /// Currently, the only argument is `case`.
+    ///
+    /// `Default argument: case`
+    ///
+    /// # Example
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter, TextCase};
+    ///
+    /// let input = "Hello, World!".to_string();
+    /// let filter = Filter::Text { case: TextCase::Upper };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "HELLO, WORLD!");
+    /// ```
+    Text {
+        /// Specifies the [`TextCase`] that the font should use.
+        ///
+        /// `Default: lower`
+        ///
+        /// # Examples
+        /// Without an argument, this will default to lowercase.
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, Filter, Span, TextCase};
+        ///
+        /// let input = Span::new("text");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert!(matches!(filter, Filter::Text { .. }));
+        /// assert_eq!(filter, Filter::Text { case: TextCase::Lower });
+        /// ```
+        ///
+        /// Passing in a case, without an argument is possible too.
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, Filter, Span, TextCase};
+        ///
+        /// let input = Span::new("text = upper");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert!(matches!(filter, Filter::Text { .. }));
+        /// assert_eq!(filter, Filter::Text { case: TextCase::Upper });
+        /// ```
+        ///
+        /// Alternatively, it is possible to be more explicit.
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, Filter, Span, TextCase};
+        ///
+        /// let input = Span::new("text = case: snake");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert!(matches!(filter, Filter::Text { .. }));
+        /// assert_eq!(filter, Filter::Text { case: TextCase::Snake });
+        /// ```
+        case: TextCase,
+    },
+    /// Describe how long ago a date and time was, relative to now, such as
+    /// `5 minutes ago` or `2 days ago`. A date in the future is described as
+    /// `in 2 days`.
+    ///
+    /// The value is an ISO 8601 date, optionally with a time and a timezone
+    /// offset, such as `2024-03-10` or `2024-03-10T12:30:00+01:00`. A date
+    /// without a time is midnight, and a time without an offset is UTC. Any
+    /// other value is returned unchanged.
+    ///
+    /// `Default argument: from`
+    ///
+    /// # Examples
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let filter = Filter::TimeAgo { from: Some("2024-03-10T12:00:00Z".to_string()) };
+    /// let time_ago = |input: &str| render_filter(input.to_string(), &filter);
+    ///
+    /// assert_eq!(time_ago("2024-03-10T11:59:30Z"), "just now");
+    /// assert_eq!(time_ago("2024-03-10T11:55:00Z"), "5 minutes ago");
+    /// assert_eq!(time_ago("2024-03-08"), "2 days ago");
+    /// assert_eq!(time_ago("2023-12-01"), "3 months ago");
+    /// ```
+    ///
+    /// A value that isn't a date is left as it is.
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "yesterday".to_string();
+    /// let output = render_filter(input, &Filter::TimeAgo { from: None });
+    ///
+    /// assert_eq!(output, "yesterday");
+    /// ```
+    TimeAgo {
+        /// The date and time to compare against, rather than the current time,
+        /// which makes the output the same on every render.
+        ///
+        /// `Default: None`
+        ///
+        /// # Example
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, Filter, Span};
+        ///
+        /// let input = Span::new("timeago = from: \"2024-03-10T12:00:00Z\"");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert_eq!(filter, Filter::TimeAgo { from: Some("2024-03-10T12:00:00Z".to_string()) });
+        /// ```
+        from: Option<String>,
+    },
+    /// Strip the trailing whitespace from every line, keeping the lines and
+    /// their line endings as they are.
+    ///
+    /// In Markdown, two or more trailing spaces are a hard line break, so
+    /// applying this before `markdown` will also remove those breaks.
+    ///
+    /// # Example
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "First line.  \nSecond line.\t \nThird line.".to_string();
+    /// let filter = Filter::TrimLines;
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "First line.\nSecond line.\nThird line.");
+    /// ```
+    TrimLines,
+    /// Truncates a string to a given length, and applies a `trail`ing string,
+    /// if the string was truncated.
+    ///
+    /// `Default argument: characters`
+    ///
+    /// # Example
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "Hello, World!".to_string();
+    /// let filter = Filter::Truncate { characters: 5, trail: "...".to_string(), safe: false };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "Hello...");
+    /// ```
+    Truncate {
+        /// The number of characters the String will be cut to.
+        ///
+        /// If this number is greater than the String's length, then nothing
+        /// happens to the String.
+        ///
+        /// `Default: 100`
+        ///
+        /// # Example
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, Filter, Span};
+        ///
+        /// let input = Span::new("truncate = trail: --");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert!(matches!(filter, Filter::Truncate { .. }));
+        /// assert_eq!(filter, Filter::Truncate {
+        ///     characters: 100,
+        ///     trail: "--".to_string(),
+        ///     safe: false,
+        /// });
+        /// ```
+        characters: u8,
+        /// The trailing characters to be appended to a truncated String.
+        ///
+        /// Due to this being appended, that means that your string will exceed
+        /// the characters length.  \
+        /// To counter this, you will need to reduce your `characters` value.
+        ///
+        /// `Default: "..."`
+        ///
+        /// # Example
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, Filter, Span};
+        ///
+        /// let input = Span::new("truncate = characters: 42");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert!(matches!(filter, Filter::Truncate { .. }));
+        /// assert_eq!(filter, Filter::Truncate {
+        ///     characters: 42,
+        ///     trail: "...".to_string(),
+        ///     safe: false,
+        /// });
+        /// ```
+        trail: String,
+        /// Whether to avoid cutting through an HTML entity, such as `&amp;`,
+        /// or a tag, by cutting before it instead.
+        ///
+        /// `Default: false`
+        ///
+        /// # Example
+        /// ```rust
+        /// use blogs_md_easy::{render_filter, parse_filter, Filter, Span};
+        ///
+        /// let input = Span::new("truncate = 9, safe: true");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert_eq!(filter, Filter::Truncate { characters: 9, trail: "...".to_string(), safe: true });
+        /// assert_eq!(render_filter("Fish &amp; Chips".to_string(), &filter), "Fish ...");
+        /// ```
+        safe: bool,
+    },
+    /// Truncates a string to a given length by replacing the middle with a
+    /// `trail`ing string, keeping the start and end, such as for long paths or
+    /// hashes.
+    ///
+    /// The length is counted in characters, and includes the `trail`.
+    ///
+    /// `Default argument: characters`
+    ///
+    /// # Example
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "/very/long/path/to/some/file.md".to_string();
+    /// let filter = Filter::TruncateMiddle { characters: 20, trail: "…".to_string() };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "/very/long…e/file.md");
+    /// assert_eq!(output.chars().count(), 20);
+    ///
+    /// let input = "0123456789abcdef".to_string();
+    /// let filter = Filter::TruncateMiddle { characters: 11, trail: "...".to_string() };
+    /// assert_eq!(render_filter(input, &filter), "0123...cdef");
+    /// ```
+    TruncateMiddle {
+        /// The number of characters the String will be cut to.
+        ///
+        /// If this number is greater than the String's length, then nothing
+        /// happens to the String.
+        ///
+        /// `Default: 100`
+        ///
+        /// # Example
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, Filter, Span};
+        ///
+        /// let input = Span::new("truncate_middle = characters: 20");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert_eq!(filter, Filter::TruncateMiddle { characters: 20, trail: "…".to_string() });
+        /// ```
+        characters: u8,
+        /// The characters that replace the middle of a truncated String.
+        ///
+        /// `Default: "…"`
+        ///
+        /// # Example
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, Filter, Span};
+        ///
+        /// let input = Span::new("truncate_middle = 12, trail: ...");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert_eq!(filter, Filter::TruncateMiddle { characters: 12, trail: "...".to_string() });
+        /// ```
+        trail: String,
+    },
+    /// Convert plain punctuation into its typographic equivalent, with `"` and
+    /// `'` becoming curly quotes, `--` an en dash, `---` an em dash, and `...`
+    /// an ellipsis.
+    ///
+    /// An `'` that follows a letter or number, such as in `don't`, is always an
+    /// apostrophe.
+    ///
+    /// Markup is left alone, so that it can be applied to Markdown or HTML.
+    /// That is the text inside of `<…>` tags, inline and fenced code, and lines
+    /// made only of `-`, `=`, or `|`, such as thematic breaks, setext
+    /// underlines, and table separator rows.
+    ///
+    /// # Examples
+    /// Quotes are opened after whitespace or an opening bracket, and closed
+    /// anywhere else.
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = r#"She said, "It's the '90s, isn't it?" ('Yes.')"#.to_string();
+    /// let filter = Filter::Typography;
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "She said, “It’s the ’90s, isn’t it?” (‘Yes.’)");
+    /// ```
+    ///
+    /// Dashes and ellipses.
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "Pages 10--12---or so...".to_string();
+    /// let filter = Filter::Typography;
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "Pages 10–12—or so…");
+    /// ```
+    ///
+    /// Markup is unchanged.
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "<a href=\"x\">it's</a> `\"code\"`\n\n---\n".to_string();
+    /// let output = render_filter(input, &Filter::Typography);
+    ///
+    /// assert_eq!(output, "<a href=\"x\">it’s</a> `\"code\"`\n\n---\n");
+    /// ```
+    Typography,
+    /// Percent-encode every character outside of the unreserved set,
+    /// `A-Z a-z 0-9 - _ . ~`, so that the value is safe to use in a URL.
+    ///
+    /// Spaces are encoded as `%20`, rather than `+`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "rust & markdown/html".to_string();
+    /// let filter = Filter::UrlEncode;
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "rust%20%26%20markdown%2Fhtml");
+    /// ```
+    UrlEncode,
+    /// Decode each percent-encoded character in a value.
+    ///
+    /// A `+` is left as is, rather than decoded into a space, and any `%` that
+    /// is not followed by two hexadecimal digits is kept.
+    ///
+    /// # Example
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "rust%20%26%20markdown%2Fhtml".to_string();
+    /// let filter = Filter::UrlDecode;
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "rust & markdown/html");
+    /// ```
+    UrlDecode,
+    /// Join a `base` URL and the value with a single `/`, no matter whether the
+    /// `base` ends with one, or the value starts with one.
+    ///
+    /// A value that is already absolute, starting with a scheme such as
+    /// `https://example.org` or `mailto:`, or with `//`, is left unchanged. A query or fragment, such as
+    /// `?page=2` or `#top`, is appended to the `base` without a `/`.
+    ///
+    /// `Default argument: base`
+    ///
+    /// # Examples
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let filter = Filter::UrlJoin { base: "https://example.com/".to_string() };
+    /// assert_eq!(render_filter("/blog/one.html".to_string(), &filter), "https://example.com/blog/one.html");
+    /// assert_eq!(render_filter("blog/one.html".to_string(), &filter), "https://example.com/blog/one.html");
+    ///
+    /// let filter = Filter::UrlJoin { base: "https://example.com".to_string() };
+    /// assert_eq!(render_filter("blog/one.html".to_string(), &filter), "https://example.com/blog/one.html");
+    /// assert_eq!(render_filter("".to_string(), &filter), "https://example.com/");
+    /// ```
+    ///
+    /// Absolute URLs, queries, and fragments.
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let filter = Filter::UrlJoin { base: "https://example.com/blog/".to_string() };
+    /// assert_eq!(render_filter("https://example.org/".to_string(), &filter), "https://example.org/");
+    /// assert_eq!(render_filter("//cdn.example.com/a.png".to_string(), &filter), "//cdn.example.com/a.png");
+    /// assert_eq!(render_filter("mailto:me@example.com".to_string(), &filter), "mailto:me@example.com");
+    /// assert_eq!(render_filter("login?next=https://x".to_string(), &filter), "https://example.com/blog/login?next=https://x");
+    /// assert_eq!(render_filter("?page=2".to_string(), &filter), "https://example.com/blog/?page=2");
+    /// assert_eq!(render_filter("#top".to_string(), &filter), "https://example.com/blog/#top");
+    /// ```
+    UrlJoin {
+        /// The URL that the value is joined to.
+        ///
+        /// `Default: ""`
+        ///
+        /// # Example
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, Filter, Span};
+        ///
+        /// let input = Span::new("url_join = base: https://example.com");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert_eq!(filter, Filter::UrlJoin { base: "https://example.com".to_string() });
+        /// ```
+        base: String,
+    },
+    /// Wrap a value with a string `before` and `after` it.
+    ///
+    /// `Default argument: before and after`
+    ///
+    /// # Examples
+    /// The unnamed argument wraps both sides with the same string.
+    /// ```rust
+    /// use blogs_md_easy::{parse_filter, render_filter, Filter, Span};
+    ///
+    /// let (_, filter) = parse_filter(Span::new("wrap = \"")).unwrap();
+    /// assert_eq!(filter, Filter::Wrap { before: "\"".to_string(), after: "\"".to_string() });
+    ///
+    /// let output = render_filter("Hello, World!".to_string(), &filter);
+    /// assert_eq!(output, "\"Hello, World!\"");
+    /// ```
+    ///
+    /// Each side can be given explicitly.
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "Hello, World!".to_string();
+    /// let filter = Filter::Wrap { before: "<em>".to_string(), after: "</em>".to_string() };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "<em>Hello, World!</em>");
+    /// ```
+    Wrap {
+        /// The string placed before the value.
+        ///
+        /// `Default: ""`
+        ///
+        /// # Example
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, Filter, Span};
+        ///
+        /// let input = Span::new("wrap = before: <em>, after: </em>");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert!(matches!(filter, Filter::Wrap { .. }));
+        /// assert_eq!(filter, Filter::Wrap { before: "<em>".to_string(), after: "</em>".to_string() });
+        /// ```
+        before: String,
+        /// The string placed after the value.
+        ///
+        /// `Default: ""`
+        ///
+        /// # Example
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, Filter, Span};
+        ///
+        /// let input = Span::new("wrap = after: '!'");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert!(matches!(filter, Filter::Wrap { .. }));
+        /// assert_eq!(filter, Filter::Wrap { before: "".to_string(), after: "!".to_string() });
+        /// ```
+        after: String,
+    },
+    /// Hard wrap each line of the value, so that no line is longer than
+    /// `width` characters, such as for a plain text email.
+    ///
+    /// Lines are broken at spaces, and runs of spaces within a line are
+    /// collapsed into one. A word longer than the `width` is left on a line of
+    /// its own, unless `break_long_words` is set.
+    ///
+    /// `Default argument: width`
+    ///
+    /// # Examples
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "The quick brown fox jumps over the lazy dog.".to_string();
+    /// let filter = Filter::WrapAt { width: 20, break_long_words: false };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "The quick brown fox\njumps over the lazy\ndog.");
+    /// ```
+    ///
+    /// Long words can be broken to fit.
+    /// ```rust
+    /// use blogs_md_easy::{render_filter, Filter};
+    ///
+    /// let input = "Visit https://example.com/a/long/path today".to_string();
+    /// let filter = Filter::WrapAt { width: 20, break_long_words: true };
+    /// let output = render_filter(input, &filter);
+    ///
+    /// assert_eq!(output, "Visit\nhttps://example.com/\na/long/path today");
+    /// ```
+    WrapAt {
+        /// The maximum number of characters on each line. A width of `0`
+        /// leaves the value unchanged.
+        ///
+        /// `Default: 80`
+        ///
+        /// # Example
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, Filter, Span};
+        ///
+        /// let input = Span::new("wrap_at = width: 72");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert_eq!(filter, Filter::WrapAt { width: 72, break_long_words: false });
+        /// ```
+        width: u8,
+        /// Whether a word longer than the `width` is broken across lines.
+        ///
+        /// `Default: false`
+        ///
+        /// # Example
+        /// ```rust
+        /// use blogs_md_easy::{parse_filter, Filter, Span};
+        ///
+        /// let input = Span::new("wrap_at = 40, break_long_words: true");
+        /// let (_, filter) = parse_filter(input).unwrap();
+        ///
+        /// assert_eq!(filter, Filter::WrapAt { width: 40, break_long_words: true });
+        /// ```
+        break_long_words: bool,
+    },
+}
+
+impl Filter {
+    /// The names of the arguments that this filter accepts, not including the
+    /// unnamed argument or any aliases.
+    ///
+    /// # Example
+    /// ```rust
+    /// use blogs_md_easy::Filter;
+    ///
+    /// let filter = Filter::Truncate { characters: 100, trail: "...".to_string(), safe: false };
+    /// assert_eq!(filter.arguments(), &["characters", "trail", "safe"]);
+    /// assert!(Filter::Ceil.arguments().is_empty());
+    /// ```
+    pub fn arguments(&self) -> &'static [&'static str] {
+        match self {
+            Filter::Bool { .. } => &["truthy", "falsy"],
+            Filter::IsEmpty | Filter::IsNumeric => &[],
+            Filter::Ceil | Filter::Floor | Filter::Ordinal => &[],
+            Filter::FileSize { .. } => &["binary"],
+            Filter::Max { .. } | Filter::Min { .. } => &["value"],
+            Filter::Round { .. } => &["precision", "mode"],
+            Filter::Unformat { .. } => &["separator"],
+            Filter::Base64Encode | Filter::Base64Decode | Filter::Cdata | Filter::CollapseWhitespace | Filter::Dedent | Filter::EscapeXml => &[],
+            Filter::AfterMore { .. } | Filter::BeforeMore { .. } => &["marker"],
+            Filter::Count { .. } => &["needle"],
+            Filter::Fallback { .. } => &["key"],
+            Filter::First { .. } | Filter::Join { .. } | Filter::Last { .. } | Filter::Split { .. } => &["separator"],
+            Filter::Format { .. } => &["template"],
+            Filter::Highlight { .. } => &["term", "tag"],
+            Filter::Indent { .. } => &["spaces", "first"],
+            Filter::FirstParagraph | Filter::JsonEscape | Filter::Reverse | Filter::Spaceless | Filter::TrimLines | Filter::Typography | Filter::UrlEncode | Filter::UrlDecode => &[],
+            Filter::Markdown { .. } => &["html", "gfm", "anchors", "highlight"],
+            Filter::RegexReplace { .. } => &["find", "replacement", "limit", "ignore_case", "regex"],
+            Filter::Remove { .. } => &["text", "limit"],
+            Filter::Replace { .. } => &["find", "replacement", "limit", "ignore_case", "regex"],
+            Filter::Slice { .. } => &["start", "end"],
+            Filter::Text { .. } => &["case"],
+            Filter::TimeAgo { .. } => &["from"],
+            Filter::Truncate { .. } => &["characters", "trail", "safe"],
+            Filter::TruncateMiddle { .. } => &["characters", "trail"],
+            Filter::UrlJoin { .. } => &["base"],
+            Filter::Wrap { .. } => &["before", "after"],
+            Filter::WrapAt { .. } => &["width", "break_long_words"],
+        }
+    }
+}
+
+/// A description of a filter that can be used in a template, such as for
+/// listing every filter that is available.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FilterInfo {
+    /// The name of the filter, as used in a template.
+    pub name: &'static str,
+    /// Alternative names for the filter.
+    pub aliases: &'static [&'static str],
+    /// The name of each argument, along with its default value; which is
+    /// empty when there isn't one. A newline is shown as `\n`.
+    pub arguments: &'static [(&'static str, &'static str)],
+    /// A one line description of what the filter does.
+    pub description: &'static str,
+}
+
+/// Every filter that [`parse_filter`] understands, in alphabetical order.
+///
+/// The parser resolves names and aliases through this list, so a filter that
+/// isn't listed here can't be parsed.
+///
+/// # Example
+/// ```rust
+/// use blogs_md_easy::{parse_filter, Span, FILTERS};
+///
+/// for info in FILTERS {
+///     let (_, filter) = parse_filter(Span::new(info.name)).unwrap();
+///     for alias in info.aliases {
+///         assert_eq!(parse_filter(Span::new(alias)).unwrap().1, filter);
+///     }
+/// }
+/// ```
+pub const FILTERS: &[FilterInfo] = &[
+    FilterInfo { name: "after_more", aliases: &[], arguments: &[("marker", "<!-- more -->")], description: "Take the content after a `<!-- more -->` marker." },
+    FilterInfo { name: "base64_decode", aliases: &[], arguments: &[], description: "Decode a Base64 value, leaving an invalid value unchanged." },
+    FilterInfo { name: "base64_encode", aliases: &[], arguments: &[], description: "Encode the value as Base64." },
+    FilterInfo { name: "before_more", aliases: &[], arguments: &[("marker", "<!-- more -->")], description: "Take the content before a `<!-- more -->` marker, such as a teaser." },
+    FilterInfo { name: "bool", aliases: &["boolean"], arguments: &[("truthy", "true"), ("falsy", "false")], description: "Replace a boolean-like value, such as `yes` or `1`, with a label." },
+    FilterInfo { name: "cdata", aliases: &[], arguments: &[], description: "Wrap the value in a CDATA section, for embedding HTML in XML." },
+    FilterInfo { name: "ceil", aliases: &[], arguments: &[], description: "Round a number up to the nearest whole number." },
+    FilterInfo { name: "collapse", aliases: &["squish"], arguments: &[], description: "Collapse every run of whitespace into a single space, and trim the ends." },
+    FilterInfo { name: "count", aliases: &[], arguments: &[("needle", "")], description: "Count how many times a substring appears in the value." },
+    FilterInfo { name: "dedent", aliases: &["deindent"], arguments: &[], description: "Remove the leading whitespace that every line has in common." },
+    FilterInfo { name: "escape_xml", aliases: &[], arguments: &[], description: "Escape the value for use in XML, such as an RSS feed." },
+    FilterInfo { name: "fallback", aliases: &["coalesce"], arguments: &[("key", "")], description: "Use the value of another variable when this one is empty." },
+    FilterInfo { name: "filesize", aliases: &[], arguments: &[("binary", "false")], description: "Format a number of bytes as a file size, such as `1.5 KB`." },
+    FilterInfo { name: "first", aliases: &[], arguments: &[("separator", "\\n")], description: "Get the first item of a list of values." },
+    FilterInfo { name: "first_paragraph", aliases: &["excerpt"], arguments: &[], description: "Take the first paragraph of the value, up to the first blank line." },
+    FilterInfo { name: "floor", aliases: &[], arguments: &[], description: "Round a number down to the nearest whole number." },
+    FilterInfo { name: "fmt", aliases: &["format", "printf"], arguments: &[("template", "{}")], description: "Place the value into a format string, replacing the first `{}`." },
+    FilterInfo { name: "highlight", aliases: &["highlight_term"], arguments: &[("term", ""), ("tag", "mark")], description: "Wrap every occurrence of a term in an element, ignoring case." },
+    FilterInfo { name: "indent", aliases: &[], arguments: &[("spaces", "4"), ("first", "true")], description: "Indent each line of the value." },
+    FilterInfo { name: "is_empty", aliases: &[], arguments: &[], description: "Replace the value with `true` when it is empty, and `false` otherwise." },
+    FilterInfo { name: "is_numeric", aliases: &[], arguments: &[], description: "Replace the value with `true` when it is a number, and `false` otherwise." },
+    FilterInfo { name: "join", aliases: &[], arguments: &[("separator", ", ")], description: "Join a list back together." },
+    FilterInfo { name: "json_escape", aliases: &["json"], arguments: &[], description: "Escape the value for use inside a JSON string." },
+    FilterInfo { name: "last", aliases: &[], arguments: &[("separator", "\\n")], description: "Get the last item of a list of values." },
+    FilterInfo { name: "lowercase", aliases: &[], arguments: &[], description: "Convert the value to lowercase." },
+    FilterInfo { name: "markdown", aliases: &[], arguments: &[("html", "true"), ("gfm", "false"), ("anchors", "false"), ("highlight", "false")], description: "Convert the value from Markdown into HTML." },
+    FilterInfo { name: "max", aliases: &[], arguments: &[("value", "")], description: "Clamp a number so that it is no greater than the value." },
+    FilterInfo { name: "min", aliases: &[], arguments: &[("value", "")], description: "Clamp a number so that it is no less than the value." },
+    FilterInfo { name: "ordinal", aliases: &[], arguments: &[], description: "Append the English ordinal suffix to a whole number, such as `1st`." },
+    FilterInfo { name: "remove", aliases: &[], arguments: &[("text", ""), ("limit", "")], description: "Remove a substring." },
+    FilterInfo { name: "replace", aliases: &[], arguments: &[("find", ""), ("replacement", ""), ("limit", ""), ("ignore_case", "false"), ("regex", "false")], description: "Replace a substring with another." },
+    FilterInfo { name: "reverse", aliases: &[], arguments: &[], description: "Reverse the order of the characters." },
+    FilterInfo { name: "round", aliases: &[], arguments: &[("precision", "0"), ("mode", "half_up")], description: "Round a number to the given precision." },
+    FilterInfo { name: "slice", aliases: &[], arguments: &[("start", "0"), ("end", "")], description: "Take a range of characters from the value." },
+    FilterInfo { name: "spaceless", aliases: &[], arguments: &[], description: "Remove the whitespace between HTML tags." },
+    FilterInfo { name: "split", aliases: &[], arguments: &[("separator", ",")], description: "Split the value into a list." },
+    FilterInfo { name: "text", aliases: &[], arguments: &[("case", "lower")], description: "Convert the case of the value, such as to `title` or `snake` case." },
+    FilterInfo { name: "timeago", aliases: &["relative_time"], arguments: &[("from", "")], description: "Describe how long ago a date was, such as `2 days ago`." },
+    FilterInfo { name: "trim_lines", aliases: &[], arguments: &[], description: "Strip the trailing whitespace from every line." },
+    FilterInfo { name: "truncate", aliases: &[], arguments: &[("characters", "100"), ("trail", "..."), ("safe", "false")], description: "Truncate the value to a number of characters." },
+    FilterInfo { name: "truncate_middle", aliases: &[], arguments: &[("characters", "100"), ("trail", "…")], description: "Truncate the value to a number of characters by replacing the middle." },
+    FilterInfo { name: "typography", aliases: &["smartypants"], arguments: &[], description: "Convert straight quotes and dashes into their typographic forms." },
+    FilterInfo { name: "unformat", aliases: &[], arguments: &[("separator", ",")], description: "Remove the grouping separators from a number, such as `1,234`." },
+    FilterInfo { name: "uppercase", aliases: &[], arguments: &[], description: "Convert the value to uppercase." },
+    FilterInfo { name: "url_decode", aliases: &["urldecode"], arguments: &[], description: "Decode a percent-encoded value." },
+    FilterInfo { name: "url_encode", aliases: &["urlencode"], arguments: &[], description: "Percent-encode the value for use in a URL." },
+    FilterInfo { name: "url_join", aliases: &[], arguments: &[("base", "")], description: "Join a base URL and the value with a single `/`." },
+    FilterInfo { name: "wrap", aliases: &[], arguments: &[("before", ""), ("after", "")], description: "Wrap the value with a string on either side." },
+    FilterInfo { name: "wrap_at", aliases: &[], arguments: &[("width", "80"), ("break_long_words", "false")], description: "Hard wrap each line at a number of characters." },
+];
+
+/// A simple struct to store the key value pair from within the meta section of
+/// a Markdown file.
+///
+/// # Example
+/// ```rust
+/// use blogs_md_easy::{parse_meta_line, Meta, Span};
+///
+/// let input = Span::new("foo = bar");
+/// let (_, meta) = parse_meta_line(input).unwrap();
+/// // Unwrap because key-values are Some() and comments are None.
+/// let meta = meta.unwrap();
+/// assert_eq!(meta, Meta::new("foo", "bar"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Meta {
+    pub key: String,
+    pub value: String,
+    /// Where the key-value pair was declared, from the start of the key to the
+    /// end of the value.
+    pub selection: Selection,
+}
+
+impl Meta {
+    /// Trims the `key` and `value` and stores them in the respective values in
+    /// this struct, with a default [`Selection`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use blogs_md_easy::Meta;
+    ///
+    /// let meta_with_space = Meta::new("  foo  ", "  bar  ");
+    /// let meta_without_space = Meta::new("foo", "bar");
+    /// assert_eq!(meta_with_space, meta_without_space);
+    /// ```
+    pub fn new(key: &str, value: &str) -> Self {
+        Self {
+            key: key.trim().to_string(),
+            value: value.trim().to_string(),
+            selection: Selection::default(),
+        }
+    }
+}
+
+impl PartialEq for Meta {
+    /// Two `Meta` are equal if their `key` and `value` are, regardless of where
+    /// they were declared.
+    ///
+    /// # Example
+    /// ```rust
+    /// use blogs_md_easy::{parse_meta_key_value, Meta, Span};
+    ///
+    /// let input = Span::new("foo = bar");
+    /// let (_, meta) = parse_meta_key_value(input).unwrap();
+    /// assert_ne!(meta.selection, Meta::new("foo", "bar").selection);
+    /// assert_eq!(meta, Meta::new("foo", "bar"));
+    /// ```
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key && self.value == other.value
+    }
+}
+
+/// A position for a Cursor within a [`Span`].
+///
+/// The `offset` is in bytes, rather than characters, so that it can be used to
+/// slice the original string directly; even when a multibyte character, such
+/// as `£`, comes before it.
+///
+/// # Example
+/// ```rust
+/// use blogs_md_easy::{parse_placeholder_locations, Span};
+///
+/// let template = "£5 🎉 {{ £price }}";
+/// let placeholders = parse_placeholder_locations(Span::new(template)).unwrap();
+/// let selection = &placeholders[0].selection;
+/// assert_eq!(&template[selection.start.offset..selection.end.offset], "{{ £price }}");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Marker {
+    pub line: u32,
+    pub offset: usize,
+}
+
+impl Marker {
+    /// Extracts the `location_line()` and `location_offset()` from the [`Span`].
+    pub fn new(span: Span) -> Self {
+        Self {
+            line: span.location_line(),
+            offset: span.location_offset(),
+        }
+    }
+}
+
+impl Default for Marker {
+    /// Create a `Marker` with a `line` of `1` and `offset` of `1`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use blogs_md_easy::Marker;
+    ///
+    /// let marker_default = Marker::default();
+    /// let marker_new = Marker { line: 1, offset: 1 };
+    /// assert_eq!(marker_default, marker_new);
+    /// ```
+    fn default() -> Self {
+        Self {
+            line: 1,
+            offset: 1,
+        }
+    }
+}
+
+/// A helper struct that contains a start and end [`Marker`] of a [`Span`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Selection {
+    pub start: Marker,
+    pub end: Marker,
+}
+
+impl Selection {
+    /// Generate a new selection from two [`Span`]s.
+    ///
+    /// The `start` argument will simply extract the `location_line` and
+    /// `location_offset` from the [`Span`].
+    /// The `end` argument will use the `location_line`, but will set the offset
+    /// to the `location_offset` added to the `fragment` length to ensure we
+    /// consume the entire match.
+    pub fn from(start: Span, end: Span) -> Self {
+        Self {
+            start: Marker::new(start),
+            // We cannot use `new` because we need to account for the string
+            // fragment length.
+            end: Marker {
+                line: end.location_line(),
+                offset: end.location_offset() + end.fragment().len()
+            }
+        }
+    }
+}
+
+/// A `Placeholder` is a variable that is created within a Template file.
+///
+/// The syntax for a `Placeholder` is as below.
+///
+/// `{{ £variable_name[| filter_name[= [key: ]value]...] }}`
+///
+/// A compulsory `variable_name`, preceded by a `£`.  \
+/// Then an optional pipe (`|`) separated list of [`Filter`]s.  \
+/// Some filters are just a name, although some have additional arguments.
+///
+/// For more explanation on what a `Placeholder` looks like inside a template,
+/// see [`parse_placeholder`].
+///
+/// For more explanation on what a [`Filter`] looks like inside a `Placeholder`,
+/// see [`parse_filter`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Placeholder {
+    pub name: String,
+    pub selection: Selection,
+    pub filters: Vec<Filter>,
+}
+
+impl Placeholder {
+    /// Apply each of the `Placeholder`'s [`Filter`]s to the value in order,
+    /// using [`render_filter`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use blogs_md_easy::{parse_placeholder, Span};
+    ///
+    /// let input = Span::new("{{ £title | uppercase | truncate = 5 }}");
+    /// let (_, placeholder) = parse_placeholder(input).unwrap();
+    /// assert_eq!(placeholder.apply("Hello, World!".to_string()), "HELLO...");
+    /// ```
+    pub fn apply(&self, value: String) -> String {
+        self.filters.iter().fold(value, render_filter)
+    }
+
+    /// Apply each of the `Placeholder`'s [`Filter`]s to the value in order,
+    /// with access to every other variable, using
+    /// [`render_filter_with_variables`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use std::collections::HashMap;
+    /// use blogs_md_easy::{parse_placeholder, Span};
+    ///
+    /// let variables = HashMap::from([("title".to_string(), "My Title".to_string())]);
+    /// let input = Span::new("{{ £subtitle | fallback = title | uppercase }}");
+    /// let (_, placeholder) = parse_placeholder(input).unwrap();
+    /// assert_eq!(placeholder.apply_with_variables("".to_string(), &variables), "MY TITLE");
+    /// ```
+    pub fn apply_with_variables(&self, value: String, variables: &HashMap<String, String>) -> String {
+        self.filters.iter().fold(value, |value, filter| render_filter_with_variables(value, filter, variables))
+    }
+}
+
+/// A `Conditional` is a block within a Template that is only rendered when a
+/// variable has a value.
+///
+/// The syntax for a `Conditional` is as below.
+///
+/// `{{ #if £variable_name }}...{{ /if }}`
+///
+/// The block is kept when the variable exists and isn't empty. To negate this,
+/// use `#unless` and `/unless` instead.
+///
+/// For more explanation on what a `Conditional` looks like inside a template,
+/// see [`parse_conditional`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Conditional {
+    /// The name of the variable that is checked.
+    pub name: String,
+    /// Whether the block is kept when the variable is missing or empty, rather
+    /// than when it has a value.
+    pub negated: bool,
+    /// The entire block, from the opening tag to the closing tag.
+    pub selection: Selection,
+    /// The contents of the block, between the opening and closing tags.
+    pub body: Selection,
+}
+
+/// An `Include` is a directive within a Template that is replaced with the
+/// contents of another file, such as a shared header or footer.
+///
+/// The syntax for an `Include` is as below.
+///
+/// `{{ #include "header.html" }}`
+///
+/// For more explanation on what an `Include` looks like inside a template, see
+/// [`parse_include`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Include {
+    /// The path of the file to include, as it was written in the template.
+    pub path: String,
+    /// The entire directive, from `{{` to `}}`.
+    pub selection: Selection,
+}
+
+/// A `Loop` is a block within a Template that is repeated for each item in a
+/// list variable.
+///
+/// The syntax for a `Loop` is as below.
+///
+/// `{{ #for £item in £list[| filter_name...] }}...{{ /for }}`
+///
+/// Within the block, the `item` variable holds the current item, and takes
+/// precedence over any other variable with the same name.
+///
+/// For more explanation on what a `Loop` looks like inside a template, see
+/// [`parse_loop`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Loop {
+    /// The name of the variable that holds each item.
+    pub item: String,
+    /// The name of the list variable that is looped over.
+    pub name: String,
+    /// The filters that turn the variable into a list.
+    pub filters: Vec<Filter>,
+    /// The entire block, from the opening tag to the closing tag.
+    pub selection: Selection,
+    /// The contents of the block, between the opening and closing tags.
+    pub body: Selection,
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Parsers
+/// Parse any character until the end of the line.
+/// This will return all characters, except the newline which will be consumed
+/// and discarded.
+///
+/// # Examples
+/// When there is no newline.
+/// ```rust
+/// use blogs_md_easy::{parse_until_eol, Span};
+///
+/// let input = Span::new("Hello, World!");
+/// let (input, until_eol) = parse_until_eol(input).unwrap();
+/// assert_eq!(input.fragment(), &"");
+/// assert_eq!(until_eol.fragment(), &"Hello, World!");
+/// ```
+///
+/// When there is a newline, the newline is consumed.
+/// ```rust
+/// use blogs_md_easy::{parse_until_eol, Span};
+///
+/// let input = Span::new("Hello, World!\nThis is Sparta!");
+/// let (input, until_eol) = parse_until_eol(input).unwrap();
+/// assert_eq!(input.fragment(), &"This is Sparta!");
+/// assert_eq!(until_eol.fragment(), &"Hello, World!");
+/// ```
+///
+/// Windows line endings are consumed whole, so the line has no trailing `\r`.
+/// ```rust
+/// use blogs_md_easy::{parse_until_eol, Span};
+///
+/// let input = Span::new("Hello, World!\r\nThis is Sparta!");
+/// let (input, until_eol) = parse_until_eol(input).unwrap();
+/// assert_eq!(input.fragment(), &"This is Sparta!");
+/// assert_eq!(until_eol.fragment(), &"Hello, World!");
+/// ```
+pub fn parse_until_eol(input: Span) -> IResult<Span, Span> {
+    terminated(
+        // A `\r` is only part of the line when it isn't a line ending itself.
+        recognize(many0(alt((
+            is_not("\r\n"),
+            terminated(tag("\r"), not(alt((tag("\n"), eof)))),
+        )))),
+        alt((line_ending, tag("\r"), eof)),
+    )(input)
+}
+
+/// Parse a comment starting with either a `#` or `//` and ending with a newline.
+///
+/// # Example
+/// ```rust
+/// use blogs_md_easy::{parse_meta_comment, Span};
+///
+/// let input = Span::new("# This is a comment");
+/// let (input, meta_comment) = parse_meta_comment(input).unwrap();
+/// assert_eq!(input.fragment(), &"");
+/// assert_eq!(meta_comment.fragment(), &"This is a comment");
+/// ```
+pub fn parse_meta_comment(input: Span) -> IResult<Span, Span> {
+    preceded(
+        // All comments start with either a `#` or `//` followed by a space(s).
+        tuple((alt((tag("#"), tag("//"))), space0)),
+        parse_until_eol,
+    )(input)
+}
+
+/// Parse a key, that starts with an optional `£`, followed by an alphabetic
+/// character, then any number of alphanumeric characters, hyphens and
+/// underscores.
+///
+/// # Examples
+/// A valid variable, consisting of letters and underscores.
+/// ```rust
+/// use blogs_md_easy::{parse_meta_key, Span};
+///
+/// let input = Span::new("£publish_date");
+/// let (_, variable) = parse_meta_key(input).unwrap();
+/// assert_eq!(variable.fragment(), &"publish_date");
+///
+/// let input = Span::new("$publish_date");
+/// let (_, variable) = parse_meta_key(input).unwrap();
+/// assert_eq!(variable.fragment(), &"publish_date");
+/// ```
+/// An invalid example, variables cannot start with a number.
+/// ```rust
+/// use blogs_md_easy::{parse_meta_key, Span};
+///
+/// let input = Span::new("£1_to_2");
+/// let variable = parse_meta_key(input);
+/// assert!(variable.is_err());
+/// ```
+pub fn parse_meta_key(input: Span) -> IResult<Span, Span> {
+    preceded(
+        opt(alt((tag("£"), tag("$")))),
+        parse_variable_name
+    )(input)
+}
+
+/// Parse any number of characters until the end of the line or string.
+///
+/// # Examples
+/// Meta values are essentially just anything that isn't a newline.
+/// ```rust
+/// use blogs_md_easy::{parse_meta_value, Span};
+///
+/// let input = Span::new("This is a value");
+/// let (_, value) = parse_meta_value(input).unwrap();
+/// assert_eq!(value.fragment(), &"This is a value");
+/// ```
+///
+/// However, if you need newlines, then wrap the string in double quotes.  \
+/// Don't forget to escape your quotes too!
+/// ```rust
+/// use blogs_md_easy::{parse_meta_value, Span};
+///
+/// let input = Span::new(r#""This \"value\" is on
+/// a new line""#);
+/// let (_, value) = parse_meta_value(input).unwrap();
+/// assert_eq!(value.fragment(), &"This \\\"value\\\" is on\na new line");
+/// ```
+pub fn parse_meta_value(input: Span) -> IResult<Span, Span> {
+    alt((
+        // Match a delimited quote string.
+        delimited(
+            tag(r#"""#),
+            // Match everything that isn't `\"`.
+            escaped(
+                is_not(r#"\""#),
+                '\\',
+                one_of(r#"nrt\""#)
+            ),
+            tag(r#"""#),
+        ),
+        // The value of the variable, everything after the equals sign.
+        // Continue to a newline or the end of the string.
+        parse_until_eol,
+    ))(input)
+}
+
+/// Parse a key-value pair of meta_key and meta_value.
+///
+/// # Example
+/// ```rust
+/// use blogs_md_easy::{parse_meta_key_value, Marker, Selection, Span};
+///
+/// let input = Span::new("£publish_date = 2021-01-01");
+/// let (_, meta) = parse_meta_key_value(input).unwrap();
+/// assert_eq!(meta.key, "publish_date");
+/// assert_eq!(meta.value, "2021-01-01");
+/// assert_eq!(meta.selection, Selection {
+///     start: Marker { line: 1, offset: 0 },
+///     end: Marker { line: 1, offset: 27 },
+/// });
+/// ```
+pub fn parse_meta_key_value(input: Span) -> IResult<Span, Meta> {
+    consumed(separated_pair(
+        parse_meta_key,
+        recognize(tuple((space0, tag("="), space0))),
+        parse_meta_value
+    ))(input)
+    .map(|(input, (pair, (key, value)))| {
+        // An unquoted value consumes the line ending, which isn't part of it.
+        let pair_text = pair.fragment().trim_end_matches(['\r', '\n']);
+        (input, Meta {
+            // A quoted value can span multiple lines.
+            selection: Selection {
+                start: Marker::new(pair),
+                end: Marker {
+                    line: pair.location_line() + pair_text.matches('\n').count() as u32,
+                    offset: pair.location_offset() + pair_text.len(),
+                },
+            },
+            ..Meta::new(key.fragment(), value.fragment())
+        })
+    })
+}
+
+/// Parse a line of meta data. This can either be a comment or a key-value pair.
+///
+/// # Examples
+/// Parsing of a comment returns None.
+/// ```rust
+/// use blogs_md_easy::{parse_meta_line, Span};
+///
+/// let input = Span::new("# This is a comment");
+/// let (_, meta) = parse_meta_line(input).unwrap();
+/// assert!(meta.is_none());
+/// ```
+/// Parsing of a key-value pair returns a Meta object.
+/// ```rust
+/// use blogs_md_easy::{parse_meta_line, Span};
+///
+/// let input = Span::new("£publish_date = 2021-01-01");
+/// let (_, meta) = parse_meta_line(input).unwrap();
+/// assert!(&meta.is_some());
+/// let meta = meta.unwrap();
+/// assert_eq!(&meta.key, "publish_date");
+/// assert_eq!(&meta.value, "2021-01-01");
+/// ```
+pub fn parse_meta_line(input: Span) -> IResult<Span, Option<Meta>> {
+    let (input, _) = space0(input)?;
+    let (input, res) = alt((
+        parse_meta_comment.map(|_| None),
+        parse_meta_key_value.map(Some),
+    ))(input)?;
+    let (input, _) = multispace0(input)?;
+    Ok((input, res))
+}
+
+/// Parse the meta section. This is either a `:meta`, `<meta>`, or `<?meta` tag
+/// surrounding a Vector of [`parse_meta_line`].
+///
+/// # Example
+/// ```rust
+/// use blogs_md_easy::{parse_meta_section, Marker, Meta, Selection, Span};
+///
+/// let input = Span::new(":meta\n// This is the published date\npublish_date = 2021-01-01\n:meta\n# Markdown title");
+/// let (input, meta) = parse_meta_section(input).unwrap();
+/// // Comments are ignored and removed from the Vector.
+/// assert_eq!(meta.len(), 1);
+/// assert_eq!(meta, vec![
+///     Meta {
+///         key: "publish_date".to_string(),
+///         value: "2021-01-01".to_string(),
+///         selection: Selection {
+///             start: Marker { line: 3, offset: 36 },
+///             end: Marker { line: 3, offset: 61 },
+///         },
+///     },
+/// ]);
+/// assert_eq!(meta[0].selection.start.line, 3);
+/// assert_eq!(input.fragment(), &"# Markdown title");
+/// ```
+///
+/// An empty section is valid, and has no meta values.
+/// ```rust
+/// use blogs_md_easy::{parse_meta_section, Span};
+///
+/// let input = Span::new(":meta\n:meta\n# Markdown title");
+/// let (input, meta) = parse_meta_section(input).unwrap();
+/// assert!(meta.is_empty());
+/// assert_eq!(input.fragment(), &"# Markdown title");
+/// ```
+pub fn parse_meta_section(input: Span) -> IResult<Span, Vec<Meta>> {
+    alt((
+        // I can't think of a more elegant solution for ensuring the pairs match
+        // one another. The previous solution could open with `:meta` and close
+        // with `</meta>` for example.
+        // A section may be empty, in which case there are no meta values.
+        delimited(
+            tuple((multispace0, tag(":meta"), multispace0)),
+            many0(parse_meta_line),
+            tuple((multispace0, tag(":meta"), multispace0)),
+        ),
+        delimited(
+            tuple((multispace0, tag("<?"), opt(tag("meta")), multispace0)),
+            many0(parse_meta_line),
+            tuple((multispace0, tag("?>"), multispace0)),
+        ),
+        delimited(
+            tuple((multispace0, tag("<meta>"), multispace0)),
+            many0(parse_meta_line),
+            tuple((multispace0, tag("</meta>"), multispace0)),
+        ),
+    ))(input)
+    // Filter out None values, leaving only legitimate meta values.
+    .map(|(input, res)| {
+        // When calling flatten on Option<> types, None values are considered
+        // empty iterators and removed, Some values are considered iterators
+        // with a single element and are therefore unwrapped and returned.
+        (input, res.into_iter().flatten().collect())
+    })
+}
+
+/// Parse a meta section that directly follows the title, rather than coming
+/// first.
+///
+/// Returns the whole title, including its `#`s or tags, so that it can be
+/// kept as part of the content.
+///
+/// # Example
+/// ```rust
+/// use blogs_md_easy::{parse_meta_after_title, Meta, Span};
+///
+/// let input = Span::new("# Markdown title\n:meta\nauthor = John Doe\n:meta\nThis is my content");
+/// let (input, (title, meta)) = parse_meta_after_title(input, 1).unwrap();
+/// assert_eq!(title.fragment(), &"# Markdown title");
+/// assert_eq!(meta, vec![Meta::new("author", "John Doe")]);
+/// assert_eq!(meta[0].selection.start.line, 3);
+/// assert_eq!(input.fragment(), &"This is my content");
+///
+/// // A meta section further down is just content.
+/// let input = Span::new("# Markdown title\nIntro\n:meta\nauthor = John Doe\n:meta");
+/// assert!(parse_meta_after_title(input, 1).is_err());
+/// ```
+pub fn parse_meta_after_title(input: Span, level: u8) -> IResult<Span, (Span, Vec<Meta>)> {
+    let (input, _) = multispace0(input)?;
+    tuple((
+        recognize(|input| parse_title(input, level)),
+        parse_meta_section,
+    ))(input)
+}
+
+/// Parse the title of the document. At the default level of `1`, this is either
+/// a Markdown title, written with a leading `#` or underlined with `=`, or an
+/// HTML heading with the `h1` tag.
+///
+/// # Arguments
+/// * `input` - A LocatedSpan of the markdown file.
+/// * `level` - The heading level of the title, from `1` to `6`. For example,
+///   level `2` expects `##`, a title underlined with `-`, or an `h2` tag.
+///
+/// # Examples
+/// Using a Markdown heading.
+/// ```rust
+/// use blogs_md_easy::{parse_title, Span};
+///
+/// let input = Span::new("# This is the title");
+/// let (_, title) = parse_title(input, 1).unwrap();
+/// assert_eq!(title.fragment(), &"This is the title");
+/// ```
+/// Using an HTML heading.
+/// ```rust
+/// use blogs_md_easy::{parse_title, Span};
+///
+/// let input = Span::new("<h1>This is the title</h1>");
+/// let (_, title) = parse_title(input, 1).unwrap();
+/// assert_eq!(title.fragment(), &"This is the title");
+/// ```
+/// Using a Markdown heading that is underlined, also known as a setext heading.
+/// ```rust
+/// use blogs_md_easy::{parse_title, Span};
+///
+/// let input = Span::new("This is the title\n=================\nContent");
+/// let (input, title) = parse_title(input, 1).unwrap();
+/// assert_eq!(title.fragment(), &"This is the title");
+/// assert_eq!(input.fragment(), &"\nContent");
+///
+/// // Horizontal rules are not titles.
+/// assert!(parse_title(Span::new("---\n==="), 1).is_err());
+/// assert!(parse_title(Span::new("Content\n---"), 1).is_err());
+/// ```
+/// Using a different heading level.
+/// ```rust
+/// use blogs_md_easy::{parse_title, Span};
+///
+/// let (_, title) = parse_title(Span::new("## This is the title"), 2).unwrap();
+/// assert_eq!(title.fragment(), &"This is the title");
+/// let (_, title) = parse_title(Span::new("<h2>This is the title</h2>"), 2).unwrap();
+/// assert_eq!(title.fragment(), &"This is the title");
+///
+/// // Headings of any other level are not the title.
+/// assert!(parse_title(Span::new("# This is the title"), 2).is_err());
+/// assert!(parse_title(Span::new("## This is the title"), 1).is_err());
+/// ```
+pub fn parse_title(input: Span, level: u8) -> IResult<Span, Span> {
+    if !(1..=6).contains(&level) {
+        return Err(nom::Err::Error(nom::error::Error::new(input, nom::error::ErrorKind::Verify)));
+    }
+
+    let (input, _) = multispace0(input)?;
+
+    let open_tag = format!("<h{}>", level);
+    let close_tag = format!("</h{}>", level);
+    // Setext headings only exist for the first two levels.
+    let underline = match level {
+        1 => Some('='),
+        2 => Some('-'),
+        _ => None,
+    };
+
+    let (input, title) = alt((
+        // Either a Markdown title...
+        preceded(
+            tuple((
+                verify(take_while1(|c| c == '#'), |hashes: &Span| hashes.len() == level as usize),
+                space0,
+            )),
+            take_till(|c| c == '\n' || c == '\r')
+        ),
+        // ... or an HTML title...
+        delimited(tag(open_tag.as_str()), take_until(close_tag.as_str()), tag(close_tag.as_str())),
+        // ... or an underlined Markdown title.
+        terminated(
+            // A line made up of only these characters is a horizontal rule, or
+            // an underline itself, rather than a title.
+            verify(take_till(|c| c == '\n' || c == '\r'), |line: &Span| {
+                underline.is_some() && !line.chars().all(|c| " \t-*_=".contains(c))
+            }),
+            tuple((
+                line_ending,
+                space0,
+                take_while1(|c| Some(c) == underline),
+                space0,
+                peek(alt((line_ending, eof))),
+            ))
+        ),
+    ))(input)?;
+
+    Ok((input.to_owned(), title.to_owned()))
+}
+
+/// Rewrite of the `nom::is_alphabetic` function that takes a char instead.
+///
+/// # Example
+/// ```rust
+/// use blogs_md_easy::is_alphabetic;
+///
+/// assert!(is_alphabetic('a'));
+/// assert!(is_alphabetic('A'));
+/// assert!(!is_alphabetic('1'));
+/// assert!(!is_alphabetic('-'));
+/// ```
+pub fn is_alphabetic(input: char) -> bool {
+    vec!['a'..='z', 'A'..='Z'].into_iter().flatten().any(|c| c == input)
+}
+
+/// A function that checks if a character is valid for a filter name.
+///
+/// The filter name is the value before the `=` in a Template.
+///
+/// # Example
+/// ```rust
+/// use blogs_md_easy::is_filter_name;
+///
+/// assert!(is_filter_name('a'));
+/// assert!(is_filter_name('A'));
+/// assert!(is_filter_name('1'));
+/// assert!(!is_filter_name('-'));
+/// assert!(!is_filter_name(' '));
+/// ```
+pub fn is_filter_name(input: char) -> bool {
+    input.is_alphanumeric() || ['_'].contains(&input)
+}
+
+/// A function that checks if a character is valid for a filter argument name.
+///
+/// This is the string preceding the `=` in the `meta` section.
+///
+/// # Example
+/// ```rust
+/// use blogs_md_easy::is_filter_arg;
+///
+/// assert!(is_filter_arg('a'));
+/// assert!(is_filter_arg('A'));
+/// assert!(is_filter_arg('1'));
+/// assert!(!is_filter_arg('-'));
+/// assert!(!is_filter_arg(' '));
+/// ```
+pub fn is_filter_arg(input: char) -> bool {
+    input.is_alphanumeric() || ['_'].contains(&input)
+}
+
+/// A function that checks if a character is valid for a filter argument value.
+///
+/// This is the string following the `=` in the `meta` section.
+///
+/// # Example
+/// ```rust
+/// use blogs_md_easy::is_filter_value;
+///
+/// assert!(is_filter_value('a'));
+/// assert!(is_filter_value('A'));
+/// assert!(is_filter_value('1'));
+/// assert!(!is_filter_value('|'));
+/// assert!(!is_filter_value(','));
+/// assert!(!is_filter_value('{'));
+/// assert!(!is_filter_value('}'));
+/// ```
+pub fn is_filter_value(input: char) -> bool {
+    input.is_alphanumeric()
+    || ![' ', '|', ',', '{', '}'].contains(&input)
+}
+
+/// Variable names must start with an alphabetic character, then any number of
+/// alphanumeric characters, hyphens and underscores.
+///
+/// # Examples
+/// Variables can consist of letters and underscores.
+/// ```rust
+/// use blogs_md_easy::{parse_variable_name, Span};
+///
+/// let input = Span::new("publish_date");
+/// let (_, variable) = parse_variable_name(input).unwrap();
+/// assert_eq!(variable.fragment(), &"publish_date");
+/// ```
+///
+/// Variables cannot start with a number or underscore.
+/// ```rust
+/// use blogs_md_easy::{parse_variable_name, Span};
+///
+/// let input = Span::new("1_to_2");
+/// let variable = parse_variable_name(input);
+/// assert!(variable.is_err());
+/// ```
+pub fn parse_variable_name(input: Span) -> IResult<Span, Span> {
+    recognize(tuple((
+        take_while_m_n(1, 1, is_alphabetic),
+        many0(alt((alphanumeric1, tag("-"), tag("_")))),
+    )))(input)
+}
+
+/// Parse a template placeholder variable. This is a `£` followed by a variable
+/// name.
+///
+/// # Examples
+/// Variables must start with a `£`.
+/// ```rust
+/// use blogs_md_easy::{parse_variable, Span};
+///
+/// let input = Span::new("£variable");
+/// let (_, variable) = parse_variable(input).unwrap();
+/// assert_eq!(variable.fragment(), &"variable");
+/// ```
+///
+/// In keeping with what people are used to, it is also possible to use a `$`.
+/// ```rust
+/// use blogs_md_easy::{parse_variable, Span};
+///
+/// let input = Span::new("$variable");
+/// let (_, variable) = parse_variable(input).unwrap();
+/// assert_eq!(variable.fragment(), &"variable");
+/// ```
+///
+/// Failing to start with a `£` will return an error.
+/// ```rust
+/// use blogs_md_easy::{parse_variable, Span};
+///
+/// let input = Span::new("variable");
+/// let variable = parse_variable(input);
+/// assert!(variable.is_err());
+/// ```
+pub fn parse_variable(input: Span) -> IResult<Span, Span> {
+    preceded(
+        alt((tag("£"), tag("$"))),
+        parse_variable_name
+    )(input)
+}
+
+/// Parse the value of a filter argument.
+///
+/// The value can be surrounded by single or double quotes, in which case any
+/// character except that quote is allowed; such as spaces, commas, and pipes.
+///
+/// # Examples
+/// An unquoted value stops at the first character that isn't allowed.
+/// ```rust
+/// use blogs_md_easy::{parse_filter_value, Span};
+///
+/// let input = Span::new("20, trail: ...");
+/// let (input, value) = parse_filter_value(input).unwrap();
+/// assert_eq!(value.fragment(), &"20");
+/// assert_eq!(input.fragment(), &", trail: ...");
+/// ```
+///
+/// A quoted value can contain spaces and commas.
+/// ```rust
+/// use blogs_md_easy::{parse_filter_value, Span};
+///
+/// let input = Span::new("', ' }}");
+/// let (input, value) = parse_filter_value(input).unwrap();
+/// assert_eq!(value.fragment(), &", ");
+/// assert_eq!(input.fragment(), &" }}");
+/// ```
+///
+/// An unquoted value can contain a `,`, `|`, `{`, or `}` when it is escaped
+/// with a backslash. The backslash is kept, and removed by
+/// [`unescape_filter_value`].
+/// ```rust
+/// use blogs_md_easy::{parse_filter_value, Span};
+///
+/// let input = Span::new(r"a\,b, c");
+/// let (input, value) = parse_filter_value(input).unwrap();
+/// assert_eq!(value.fragment(), &r"a\,b");
+/// assert_eq!(input.fragment(), &", c");
+/// ```
+pub fn parse_filter_value(input: Span) -> IResult<Span, Span> {
+    alt((
+        delimited(tag("'"), take_until("'"), tag("'")),
+        delimited(tag("\""), take_until("\""), tag("\"")),
+        recognize(many0(alt((
+            take_while1(|c| c != '\\' && is_filter_value(c)),
+            recognize(tuple((tag("\\"), one_of(",|{}")))),
+            tag("\\"),
+        )))),
+    ))(input)
+}
+
+/// Remove the backslash from each escaped `,`, `|`, `{`, and `}` in a filter
+/// argument value. Any other backslash is kept, such as in a regular
+/// expression.
+///
+/// # Example
+/// ```rust
+/// use blogs_md_easy::unescape_filter_value;
+///
+/// assert_eq!(unescape_filter_value(r"1\, 2 \| 3"), "1, 2 | 3");
+/// assert_eq!(unescape_filter_value(r"\d+\.\d+"), r"\d+\.\d+");
+/// assert_eq!(unescape_filter_value(r"\\|"), r"\|");
+/// ```
+pub fn unescape_filter_value(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('\\', Some(next @ (',' | '|' | '{' | '}'))) => {
+                unescaped.push(*next);
+                chars.next();
+            },
+            _ => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+/// Parser that will parse exclusively the key-values from after a filter.  \
+/// This will return the key (before the `:`) and the value (after the `:`). It
+/// will also return a key of `_` if no key was provided.
+///
+/// # Examples
+/// Ensure that a key-value pair, separated by a colon, can be parsed into a
+/// tuple.
+/// ```rust
+/// use blogs_md_easy::{parse_filter_key_value, Span};
+///
+/// let input = Span::new("trail: ...");
+/// let (_, args) = parse_filter_key_value(input).unwrap();
+/// assert_eq!(args, ("trail", "..."));
+/// ```
+///
+/// Ensure that a single value can be parsed into a tuple with a key of `_`.
+/// ```rust
+/// use blogs_md_easy::{parse_filter_key_value, Span};
+///
+/// let input = Span::new("20");
+/// let (_, args) = parse_filter_key_value(input).unwrap();
+/// assert_eq!(args, ("_", "20"));
+/// ```
+///
+/// A value containing a colon, such as a URL or a time, is not mistaken for a
+/// key-value pair, as a key must start with a letter and its colon must not be
+/// followed by a `/`.
+/// ```rust
+/// use blogs_md_easy::{parse_filter_key_value, Span};
+///
+/// let input = Span::new("https://example.com/");
+/// let (_, args) = parse_filter_key_value(input).unwrap();
+/// assert_eq!(args, ("_", "https://example.com/"));
+///
+/// let input = Span::new("12:30");
+/// let (_, args) = parse_filter_key_value(input).unwrap();
+/// assert_eq!(args, ("_", "12:30"));
+/// ```
+pub fn parse_filter_key_value(input: Span<'_>) -> IResult<Span<'_>, (&str, &str)> {
+    alt((
+        // This matches a key-value separated by a colon.
+        // Example: `truncate = characters: 20`
+        separated_pair(
+            verify(take_while1(is_filter_arg), |arg: &Span| arg.fragment().starts_with(char::is_alphabetic))
+            .map(|arg: Span| *arg.fragment()),
+            tuple((space0, tag(":"), not(tag("/")), space0)),
+            parse_filter_value.map(|value: Span| *value.fragment()),
+        ),
+        // But it's also possible to just provide a value.
+        // Example: `truncate = 20`
+        // Something must be consumed, otherwise a filter without arguments
+        // would have an empty value, rather than its default.
+        verify(consumed(parse_filter_value), |(consumed, _): &(Span, Span)| !consumed.is_empty())
+        .map(|(_, value): (Span, Span)| ("_", *value.fragment()))
+    ))(input)
+}
+
+/// Parser that will parse exclusively the key-values from after a filter.  \
+/// The signature of a filter is `filter_name = key1: value1, key2: value2,...`,
+/// or just `filter_name = value`.
+///
+/// # Examples
+/// Ensure that a key-value pair, separated by a colon, can be parsed into a
+/// tuple.
+/// ```rust
+/// use blogs_md_easy::{parse_filter_args, Span};
+///
+/// let input = Span::new("characters: 20, trail: ...");
+/// let (_, args) = parse_filter_args(input).unwrap();
+/// assert_eq!(args, vec![
+///     ("characters", "20"),
+///     ("trail", "..."),
+/// ]);
+/// ```
+///
+/// Ensure that a single value can be parsed into a tuple with a key of `_`.
+/// ```rust
+/// use blogs_md_easy::{parse_filter_args, Span};
+///
+/// let input = Span::new("20");
+/// let (_, args) = parse_filter_args(input).unwrap();
+/// assert_eq!(args, vec![
+///     ("_", "20")
+/// ]);
+/// ```
+pub fn parse_filter_args(input: Span<'_>) -> IResult<Span<'_>, Vec<(&str, &str)>> {
+    separated_list1(
+        tuple((space0, tag(","), space0)),
+        parse_filter_key_value
+    )(input)
+}
+
+/// Alternative names for filter arguments, and the argument that they map to.
+const ARGUMENT_ALIASES: [(&str, &str); 10] = [
+    ("chars", "characters"),
+    ("length", "characters"),
+    ("digits", "precision"),
+    ("places", "precision"),
+    ("sep", "separator"),
+    ("delimiter", "separator"),
+    ("search", "find"),
+    ("with", "replacement"),
+    ("max", "limit"),
+    ("ellipsis", "trail"),
+];
+
+/// Map an alternative argument name, such as `chars`, to the name that filters
+/// expect, such as `characters`. Any other name is returned as is.
+///
+/// # Example
+/// ```rust
+/// use blogs_md_easy::canonical_argument;
+///
+/// assert_eq!(canonical_argument("chars"), "characters");
+/// assert_eq!(canonical_argument("digits"), "precision");
+/// assert_eq!(canonical_argument("characters"), "characters");
+/// assert_eq!(canonical_argument("_"), "_");
+/// ```
+pub fn canonical_argument(key: &str) -> &str {
+    ARGUMENT_ALIASES.iter()
+        .find(|(alias, _)| *alias == key)
+        .map_or(key, |(_, canonical)| canonical)
+}
+
+/// Parse a [`Filter`], and optionally its arguments if present.
+///
+/// # Examples
+/// A filter with no arguments.
+/// ```rust
+/// use blogs_md_easy::{parse_filter, Filter, Span, TextCase};
+///
+/// let input = Span::new("lowercase");
+/// let (_, filter) = parse_filter(input).unwrap();
+/// assert!(matches!(filter, Filter::Text { case: TextCase::Lower }));
+/// ```
+///
+/// A filter with just a value, but no key.  \
+/// This will be parsed as a key of `_`, which will then be set to a key of the
+/// given enum Struct variant that is deemed the default.  \
+/// In the case of [`Filter::Truncate`], this will be the `characters`.
+/// ```rust
+/// use blogs_md_easy::{parse_filter, Filter, Span};
+///
+/// let input = Span::new("truncate = 20");
+/// let (_, filter) = parse_filter(input).unwrap();
+/// assert_eq!(filter, Filter::Truncate { characters: 20, trail: "...".to_string(), safe: false });
+/// ```
+///
+/// A filter with multiple arguments, and given keys.
+/// ```rust
+/// use blogs_md_easy::{parse_filter, Filter, Span};
+///
+/// let input = Span::new("truncate = characters: 15, trail:...");
+/// let (_, filter) = parse_filter(input).unwrap();
+/// assert!(matches!(filter, Filter::Truncate { .. }));
+/// assert_eq!(filter, Filter::Truncate {
+///     characters: 15,
+///     trail: "...".to_string(),
+///     safe: false,
+/// });
+/// ```
+///
+/// For some filters, default values are provided, if not present.
+/// ```rust
+/// use blogs_md_easy::{parse_filter, Filter, Span};
+///
+/// let input = Span::new("truncate = trail:...");
+/// let (_, filter) = parse_filter(input).unwrap();
+/// assert!(matches!(filter, Filter::Truncate { .. }));
+/// assert_eq!(filter, Filter::Truncate {
+///     characters: 100,
+///     trail: "...".to_string(),
+///     safe: false,
+/// });
+/// ```
+pub fn parse_filter(input: Span) -> IResult<Span, Filter> {
+    parse_filter_with_unknown_arguments(input)
+    .map(|(input, (filter, _))| (input, filter))
+}
+
+/// Parse a [`Filter`], along with the names of any arguments that it doesn't
+/// accept, which are otherwise ignored by [`parse_filter`].
+///
+/// # Example
+/// ```rust
+/// use blogs_md_easy::{parse_filter_with_unknown_arguments, Filter, Span};
+///
+/// let input = Span::new("truncate = charcters: 7, trail: ...");
+/// let (_, (filter, unknown)) = parse_filter_with_unknown_arguments(input).unwrap();
+/// assert_eq!(filter, Filter::Truncate { characters: 100, trail: "...".to_string(), safe: false });
+/// assert_eq!(unknown, vec!["charcters"]);
+///
+/// // Aliases and the unnamed argument are known.
+/// let input = Span::new("truncate = 7, chars: 7");
+/// let (_, (_, unknown)) = parse_filter_with_unknown_arguments(input).unwrap();
+/// assert!(unknown.is_empty());
+/// ```
+pub fn parse_filter_with_unknown_arguments(input: Span) -> IResult<Span, (Filter, Vec<String>)> {
+    separated_pair(
+        take_while(is_filter_name),
+        opt(tuple((space0, tag("="), space0))),
+        opt(parse_filter_args)
+    )(input)
+    .and_then(|(input, (name, args))| {
+        let args = args.unwrap_or_default().into_iter()
+            .map(|(key, value)| (key, unescape_filter_value(value)))
+            .collect::<Vec<(&str, String)>>();
+        let keys = args.iter()
+            .map(|(key, _)| *key)
+            .filter(|key| *key != "_")
+            .collect::<Vec<&str>>();
+        // Filters with more than one default argument take them in order.
+        let positional = args.iter().filter(|(key, _)| *key == "_").map(|(_, value)| value.as_str()).collect::<Vec<&str>>();
+        let args: HashMap<&str, &str> = args.iter()
+            .map(|(key, value)| (canonical_argument(key), value.as_str()))
+            .collect();
+
+        // Names and aliases are resolved through `FILTERS`, so that every filter
+        // that can be parsed is listed there too.
+        let name_lowercase = name.fragment().trim().to_lowercase();
+        let Some(info) = FILTERS.iter().find(|info| info.name == name_lowercase || info.aliases.contains(&name_lowercase.as_str())) else {
+            // Either an empty or an unknown filter name.
+            return Err(nom::Err::Error(nom::error::Error::new(name, nom::error::ErrorKind::Tag)));
+        };
+
+        let filter = match info.name {
+            // Boolean filters.
+            "bool" => Filter::Bool {
+                truthy: args.get("truthy").unwrap_or(&"true").to_string(),
+                falsy: args.get("falsy").unwrap_or(&"false").to_string(),
+            },
+            "is_empty" => Filter::IsEmpty,
+            "is_numeric" => Filter::IsNumeric,
+
+            // Maths filters.
+            "ceil" => Filter::Ceil,
+            "filesize" => Filter::FileSize {
+                binary: args.get("binary").is_some_and(|binary| binary.eq_ignore_ascii_case("true")),
+            },
+            "floor" => Filter::Floor,
+            // Without a valid value, there is nothing to clamp to.
+            "max" => Filter::Max {
+                value: args.get("value").or(args.get("_"))
+                    .and_then(|value| value.parse::<f64>().ok())
+                    .unwrap_or(f64::INFINITY),
+            },
+            "min" => Filter::Min {
+                value: args.get("value").or(args.get("_"))
+                    .and_then(|value| value.parse::<f64>().ok())
+                    .unwrap_or(f64::NEG_INFINITY),
+            },
+            "ordinal" => Filter::Ordinal,
+            "round" => Filter::Round {
+                precision: args.get("precision").unwrap_or(
+                    args.get("_").unwrap_or(&"0")
+                ).parse::<i8>().unwrap_or(0),
+                // Default is `mode: RoundingMode::HalfUp`.
+                mode: args.get("mode").unwrap_or(&"half_up")
+                    .parse::<RoundingMode>().unwrap_or(RoundingMode::HalfUp),
+            },
+
+            // String filters.
+            "after_more" => Filter::AfterMore {
+                marker: args.get("marker").unwrap_or(
+                    args.get("_").unwrap_or(&"<!-- more -->")
+                ).to_string(),
+            },
+            "base64_encode" => Filter::Base64Encode,
+            "base64_decode" => Filter::Base64Decode,
+            "before_more" => Filter::BeforeMore {
+                marker: args.get("marker").unwrap_or(
+                    args.get("_").unwrap_or(&"<!-- more -->")
+                ).to_string(),
+            },
+            "cdata" => Filter::Cdata,
+            "collapse" => Filter::CollapseWhitespace,
+            "count" => Filter::Count {
+                needle: args.get("needle").unwrap_or(
+                    args.get("_").unwrap_or(&"")
+                ).to_string(),
+            },
+            "dedent" => Filter::Dedent,
+            "escape_xml" => Filter::EscapeXml,
+            "fallback" => Filter::Fallback {
+                key: args.get("key").unwrap_or(
+                    args.get("_").unwrap_or(&"")
+                ).trim_start_matches(['£', '$']).to_string(),
+            },
+            "first" => Filter::First {
+                // As with `split`, an empty separator falls back to the default.
+                separator: Some(args.get("separator").unwrap_or(
+                    args.get("_").unwrap_or(&"\n")
+                ))
+                .filter(|separator| !separator.is_empty())
+                .unwrap_or(&"\n")
+                .to_string(),
+            },
+            "first_paragraph" => Filter::FirstParagraph,
+            "fmt" => Filter::Format {
+                template: args.get("template").unwrap_or(
+                    args.get("_").unwrap_or(&"{}")
+                ).to_string(),
+            },
+            "highlight" => Filter::Highlight {
+                term: args.get("term").unwrap_or(
+                    args.get("_").unwrap_or(&"")
+                ).to_string(),
+                tag: Some(*args.get("tag").unwrap_or(&"mark"))
+                    .filter(|tag| !tag.is_empty() && tag.chars().all(is_filter_name))
+                    .unwrap_or("mark")
+                    .to_string(),
+            },
+            "indent" => Filter::Indent {
+                spaces: args.get("spaces").unwrap_or(
+                    args.get("_").unwrap_or(&"4")
+                ).parse::<u8>().unwrap_or(4),
+                first: !args.get("first").is_some_and(|first| first.eq_ignore_ascii_case("false")),
+            },
+            "json_escape" => Filter::JsonEscape,
+            "join" => Filter::Join {
+                separator: args.get("separator").unwrap_or(
+                    args.get("_").unwrap_or(&", ")
+                ).to_string(),
+            },
+            "last" => Filter::Last {
+                // As with `split`, an empty separator falls back to the default.
+                separator: Some(args.get("separator").unwrap_or(
+                    args.get("_").unwrap_or(&"\n")
+                ))
+                .filter(|separator| !separator.is_empty())
+                .unwrap_or(&"\n")
+                .to_string(),
+            },
+            "lowercase" => Filter::Text { case: TextCase::Lower },
+            "uppercase" => Filter::Text { case: TextCase::Upper },
+            "markdown" => Filter::Markdown {
+                allow_html: !args.get("html").is_some_and(|html| html.eq_ignore_ascii_case("false")),
+                gfm: args.get("gfm").is_some_and(|gfm| gfm.eq_ignore_ascii_case("true")),
+                anchors: args.get("anchors").is_some_and(|anchors| anchors.eq_ignore_ascii_case("true")),
+                highlight: args.get("highlight").is_some_and(|highlight| highlight.eq_ignore_ascii_case("true")),
+            },
+            "replace" if args.get("regex").is_some_and(|regex| regex.eq_ignore_ascii_case("true")) => Filter::RegexReplace {
+                pattern: args.get("find").unwrap_or(
+                    args.get("_").unwrap_or(&"")
+                ).to_string(),
+                replacement: args.get("replacement").unwrap_or(&"").to_string(),
+                limit: args.get("limit").map(|s| s.parse::<u8>().ok()).unwrap_or(None),
+                ignore_case: args.get("ignore_case").is_some_and(|ignore_case| ignore_case.eq_ignore_ascii_case("true")),
+            },
+            "remove" => Filter::Remove {
+                text: args.get("text").unwrap_or(
+                    args.get("_").unwrap_or(&"")
+                ).to_string(),
+                limit: args.get("limit").map(|s| s.parse::<u8>().ok()).unwrap_or(None),
+            },
+            "replace" => Filter::Replace {
+                find: args.get("find").unwrap_or(
+                    args.get("_").unwrap_or(&"")
+                ).to_string(),
+                replacement: args.get("replacement").unwrap_or(&"").to_string(),
+                limit: args.get("limit").map(|s| s.parse::<u8>().ok()).unwrap_or(None),
+                ignore_case: args.get("ignore_case").is_some_and(|ignore_case| ignore_case.eq_ignore_ascii_case("true")),
+            },
+            "reverse" => Filter::Reverse,
+            "slice" => Filter::Slice {
+                start: args.get("start").or(positional.first())
+                    .and_then(|start| start.parse::<usize>().ok())
+                    .unwrap_or(0),
+                end: args.get("end").or(positional.get(1))
+                    .and_then(|end| end.parse::<usize>().ok()),
+            },
+            "spaceless" => Filter::Spaceless,
+            "split" => Filter::Split {
+                // An empty separator would split every character, so fall
+                // back to the default.
+                separator: Some(args.get("separator").unwrap_or(
+                    args.get("_").unwrap_or(&",")
+                ))
+                .filter(|separator| !separator.is_empty())
+                .unwrap_or(&",")
+                .to_string(),
+            },
+            "trim_lines" => Filter::TrimLines,
+            "truncate" => Filter::Truncate {
+                // Attempt to get the characters, but if we can't then we use
+                // the unnamed value, defined as "_".
+                characters: args.get("characters").unwrap_or(
+                    args.get("_").unwrap_or(&"100")
+                ).parse::<u8>().unwrap_or(100),
+                trail: args.get("trail").unwrap_or(&"...").to_string(),
+                safe: args.get("safe").is_some_and(|value| value.eq_ignore_ascii_case("true")),
+            },
+            "truncate_middle" => Filter::TruncateMiddle {
+                characters: args.get("characters").unwrap_or(
+                    args.get("_").unwrap_or(&"100")
+                ).parse::<u8>().unwrap_or(100),
+                trail: args.get("trail").unwrap_or(&"…").to_string(),
+            },
+            "text" => Filter::Text {
+                // Default is `case: TextCase::Lower`.
+                case: args.get("case").unwrap_or(
+                    args.get("_").unwrap_or(&"lower")
+                ).parse::<TextCase>().unwrap_or(TextCase::Lower)
+            },
+            "timeago" => Filter::TimeAgo {
+                from: args.get("from").or(args.get("_")).map(|from| from.to_string()),
+            },
+            "typography" => Filter::Typography,
+            "unformat" => Filter::Unformat {
+                separator: args.get("separator").unwrap_or(
+                    args.get("_").unwrap_or(&",")
+                ).to_string(),
+            },
+            "url_encode" => Filter::UrlEncode,
+            "url_decode" => Filter::UrlDecode,
+            "url_join" => Filter::UrlJoin {
+                base: args.get("base").unwrap_or(
+                    args.get("_").unwrap_or(&"")
+                ).to_string(),
+            },
+            "wrap" => Filter::Wrap {
+                before: args.get("before").unwrap_or(
+                    args.get("_").unwrap_or(&"")
+                ).to_string(),
+                after: args.get("after").unwrap_or(
+                    args.get("_").unwrap_or(&"")
+                ).to_string(),
+            },
+            "wrap_at" => Filter::WrapAt {
+                width: args.get("width").unwrap_or(
+                    args.get("_").unwrap_or(&"80")
+                ).parse::<u8>().unwrap_or(80),
+                break_long_words: args.get("break_long_words").is_some_and(|value| value.eq_ignore_ascii_case("true")),
+            },
+            // A filter listed in `FILTERS`, which hasn't been implemented.
+            _ => return Err(nom::Err::Error(nom::error::Error::new(name, nom::error::ErrorKind::Tag))),
+        };
+
+        let unknown = keys.into_iter()
+            .filter(|key| !filter.arguments().contains(&canonical_argument(key)))
+            .map(str::to_string)
+            .collect();
+        Ok((input, (filter, unknown)))
+    })
+}
+
+/// Parsers a pipe (`|`) separated list of [`Filter`]s.
+///
+/// # Examples
+/// A single filter.
+/// ```rust
+/// use blogs_md_easy::{parse_filters, Filter, Span, TextCase};
+///
+/// // As in {{ £my_variable | lowercase }}
+/// let input = Span::new("| lowercase");
+/// let (_, filters) = parse_filters(input).unwrap();
+/// assert!(matches!(filters[0], Filter::Text { case: TextCase::Lower }));
+/// ```
+///
+/// Multiple filters chained together with `|`.
+/// ```rust
+/// use blogs_md_easy::{parse_filters, Filter, Span, TextCase};
+///
+/// // As in {{ £my_variable | lowercase | truncate = trail: ..! }}
+/// let input = Span::new("| lowercase | truncate = trail: ..!");
+/// let (_, filters) = parse_filters(input).unwrap();
+/// assert!(matches!(filters[0], Filter::Text { case: TextCase::Lower }));
+/// assert!(matches!(filters[1], Filter::Truncate { .. }));
+/// assert_eq!(filters[1], Filter::Truncate {
+///     characters: 100,
+///     trail: "..!".to_string(),
+///     safe: false,
+/// });
+/// ```
+///
+/// An empty filter, such as after a trailing pipe, is an error rather than
+/// being skipped. Use [`find_placeholder_error`] to point to where it is.
+/// ```rust
+/// use blogs_md_easy::{parse_filters, Span};
+///
+/// // As in {{ £my_variable | lowercase | }}
+/// let input = Span::new("| lowercase | ");
+/// assert!(parse_filters(input).is_err());
+/// ```
+pub fn parse_filters(input: Span) -> IResult<Span, Vec<Filter>> {
+    preceded(
+        tuple((space0, tag("|"), space0)),
+        terminated(
+            separated_list1(tuple((space0, tag("|"), space0)), parse_filter),
+            // A pipe without a filter after it.
+            not(tuple((space0, tag("|")))),
+        )
+    )(input)
+}
+
+/// Parse a template [`Placeholder`].
+///
+/// This is a variable name, surrounded by `{{` and `}}`.  \
+/// Whitespace is optional.
+///
+/// # Examples
+/// A simple [`Placeholder`].
+/// ```rust
+/// use blogs_md_easy::{parse_placeholder, Span};
+///
+/// let input = Span::new("{{ £variable }}");
+/// let (_, placeholder) = parse_placeholder(input).unwrap();
+/// assert_eq!(placeholder.name.as_str(), "variable");
+/// assert_eq!(placeholder.selection.start.offset, 0);
+/// assert_eq!(placeholder.selection.end.offset, 16);
+/// ```
+///
+/// A [`Placeholder`] without whitespace.
+/// ```rust
+/// use blogs_md_easy::{parse_placeholder, Span};
+///
+/// let input = Span::new("{{£variable}}");
+/// let (_, placeholder) = parse_placeholder(input).unwrap();
+/// assert_eq!(placeholder.name.as_str(), "variable");
+/// assert_eq!(placeholder.selection.start.offset, 0);
+/// assert_eq!(placeholder.selection.end.offset, 14);
+/// ```
+///
+/// A [`Placeholder`] with a single [`Filter`].
+/// ```rust
+/// use blogs_md_easy::{parse_placeholder, Filter, Span, TextCase};
+///
+/// let input = Span::new("{{ £variable | uppercase }}");
+/// let (_, placeholder) = parse_placeholder(input).unwrap();
+/// assert_eq!(placeholder.name.as_str(), "variable");
+/// assert_eq!(placeholder.selection.start.offset, 0);
+/// assert_eq!(placeholder.selection.end.offset, 28);
+/// assert!(matches!(placeholder.filters[0], Filter::Text { case: TextCase::Upper }));
+/// ```
+///
+/// A [`Placeholder`] with a two [`Filter`]s.
+/// ```rust
+/// use blogs_md_easy::{parse_placeholder, Filter, Span, TextCase};
+///
+/// let input = Span::new("{{ £variable | lowercase | truncate = characters: 42 }}");
+/// let (_, placeholder) = parse_placeholder(input).unwrap();
+/// assert_eq!(placeholder.name.as_str(), "variable");
+/// assert_eq!(placeholder.selection.start.offset, 0);
+/// assert_eq!(placeholder.selection.end.offset, 56);
+/// assert!(matches!(placeholder.filters[0], Filter::Text { case: TextCase::Lower }));
+/// assert_eq!(placeholder.filters[1], Filter::Truncate { characters: 42, trail: "...".to_string(), safe: false });
+/// ```
+pub fn parse_placeholder(input: Span) -> IResult<Span, Placeholder> {
+    parse_placeholder_with_unknown_arguments(input)
+    .map(|(input, (placeholder, _))| (input, placeholder))
+}
 
-/// A simple struct to store the key value pair from within the meta section of
-/// a Markdown file.
+/// Parse a [`Placeholder`], along with the names of any arguments that its
+/// filters don't accept, which are likely to be typos and are otherwise
+/// ignored by [`parse_placeholder`].
 ///
 /// # Example
 /// ```rust
-/// use blogs_md_easy::{parse_meta_line, Meta, Span};
+/// use blogs_md_easy::{parse_placeholder_with_unknown_arguments, Span};
 ///
-/// let input = Span::new("foo = bar");
-/// let (_, meta) = parse_meta_line(input).unwrap();
-/// // Unwrap because key-values are Some() and comments are None.
-/// let meta = meta.unwrap();
-/// assert_eq!(meta, Meta::new("foo", "bar"));
+/// let input = Span::new("{{ £title | truncate = charcters: 7 | wrap = before: '<', afterr: '>' }}");
+/// let (_, (placeholder, unknown)) = parse_placeholder_with_unknown_arguments(input).unwrap();
+/// assert_eq!(placeholder.name, "title");
+/// assert_eq!(unknown, vec!["charcters", "afterr"]);
 /// ```
-#[derive(Debug, PartialEq)]
-pub struct Meta {
-    pub key: String,
-    pub value: String,
-}
+pub fn parse_placeholder_with_unknown_arguments(input: Span) -> IResult<Span, (Placeholder, Vec<String>)> {
+    tuple((
+        tuple((tag("{{"), multispace0)),
+        parse_variable,
+        opt(preceded(
+            tuple((space0, tag("|"), space0)),
+            separated_list1(tuple((space0, tag("|"), space0)), parse_filter_with_unknown_arguments)
+        )),
+        tuple((multispace0, tag("}}"))),
+    ))(input)
+    .map(|(input, (start, variable, filters, end))| {
+        let (mut filters, unknown_arguments): (Vec<Filter>, Vec<Vec<String>>) = filters.unwrap_or_default().into_iter().unzip();
+        let unknown_arguments = unknown_arguments.into_iter().flatten().collect();
 
-impl Meta {
-    /// Trims the `key` and `value` and stores them in the respective values in
-    /// this struct.
-    ///
-    /// # Example
-    /// ```rust
-    /// use blogs_md_easy::Meta;
-    ///
-    /// let meta_with_space = Meta::new("  foo  ", "  bar  ");
-    /// let meta_without_space = Meta::new("foo", "bar");
-    /// assert_eq!(meta_with_space, meta_without_space);
-    /// ```
-    pub fn new(key: &str, value: &str) -> Self {
-        Self {
-            key: key.trim().to_string(),
-            value: value.trim().to_string(),
+        // By default, £content will always be parsed as Markdown.
+        if variable.to_ascii_lowercase().as_str() == "content" && !filters.iter().any(|filter| matches!(filter, Filter::Markdown { .. })) {
+            filters.push(Filter::Markdown { allow_html: true, gfm: false, anchors: false, highlight: false });
         }
-    }
-}
 
-/// A position for a Cursor within a [`Span`].
-#[derive(Clone, Copy, Debug, PartialEq)]
-pub struct Marker {
-    pub line: u32,
-    pub offset: usize,
+        (input, (Placeholder {
+            name: variable.to_string(),
+            filters,
+            selection: Selection::from(start.0, end.1),
+        }, unknown_arguments))
+    })
 }
 
-impl Marker {
-    /// Extracts the `location_line()` and `location_offset()` from the [`Span`].
-    pub fn new(span: Span) -> Self {
-        Self {
-            line: span.location_line(),
-            offset: span.location_offset(),
-        }
-    }
+/// Parse a string consuming - and discarding - any character, and stopping at
+/// the first matched placeholder, returning a [`Placeholder`] struct.
+///
+/// # Example
+/// ```rust
+/// use blogs_md_easy::{take_till_placeholder, Marker, Placeholder, Selection, Span};
+///
+/// let input = Span::new("Hello, {{ £name }}!");
+/// let (input, placeholder) = take_till_placeholder(input).expect("to parse input");
+/// assert_eq!(input.fragment(), &"!");
+/// assert_eq!(placeholder, Placeholder {
+///     name: "name".to_string(),
+///     selection: Selection {
+///         start: Marker {
+///             line: 1,
+///             offset: 7,
+///         },
+///         end: Marker {
+///             line: 1,
+///             offset: 19,
+///         },
+///     },
+///     filters: vec![],
+/// });
+/// ```
+pub fn take_till_placeholder(input: Span) -> IResult<Span, Placeholder> {
+    many_till(anychar, parse_placeholder)(input)
+    // Map to remove anychar's captures.
+    .map(|(input, (_, placeholder))| (input, placeholder))
 }
 
-impl Default for Marker {
-    /// Create a `Marker` with a `line` of `1` and `offset` of `1`.
-    ///
-    /// # Example
-    /// ```rust
-    /// use blogs_md_easy::Marker;
-    ///
-    /// let marker_default = Marker::default();
-    /// let marker_new = Marker { line: 1, offset: 1 };
-    /// assert_eq!(marker_default, marker_new);
-    /// ```
-    fn default() -> Self {
-        Self {
-            line: 1,
-            offset: 1,
-        }
-    }
-}
+/// Consume an entire string, and return a Vector of a tuple; where the first
+/// element is a String of the variable name, and the second element is the
+/// [`Placeholder`].
+///
+/// # Example
+/// ```rust
+/// use blogs_md_easy::{parse_placeholder_locations, Span};
+///
+/// let input = Span::new("Hello, {{ £name }}!");
+/// let placeholders = parse_placeholder_locations(input).unwrap();
+/// assert_eq!(placeholders.len(), 1);
+/// assert_eq!(placeholders[0].name.as_str(), "name");
+/// assert_eq!(placeholders[0].selection.start.offset, 7);
+/// assert_eq!(placeholders[0].selection.end.offset, 19);
+/// ```
+pub fn parse_placeholder_locations(input: Span) -> Result<Vec<Placeholder>, Box<dyn Error>> {
+    let mut placeholders = parse_placeholder_locations_in_order(input)?;
 
-/// A helper struct that contains a start and end [`Marker`] of a [`Span`].
-#[derive(Clone, Copy, Debug, Default, PartialEq)]
-pub struct Selection {
-    pub start: Marker,
-    pub end: Marker,
-}
+    // Reverse so that when we replace each placeholder, the offsets do not
+    // affect offsets after this point.
+    placeholders.reverse();
 
-impl Selection {
-    /// Generate a new selection from two [`Span`]s.
-    ///
-    /// The `start` argument will simply extract the `location_line` and
-    /// `location_offset` from the [`Span`].
-    /// The `end` argument will use the `location_line`, but will set the offset
-    /// to the `location_offset` added to the `fragment` length to ensure we
-    /// consume the entire match.
-    pub fn from(start: Span, end: Span) -> Self {
-        Self {
-            start: Marker::new(start),
-            // We cannot use `new` because we need to account for the string
-            // fragment length.
-            end: Marker {
-                line: end.location_line(),
-                offset: end.location_offset() + end.fragment().len()
-            }
-        }
-    }
+    Ok(placeholders)
 }
 
-/// A `Placeholder` is a variable that is created within a Template file.
-///
-/// The syntax for a `Placeholder` is as below.
-///
-/// `{{ £variable_name[| filter_name[= [key: ]value]...] }}`
-///
-/// A compulsory `variable_name`, preceded by a `£`.  \
-/// Then an optional pipe (`|`) separated list of [`Filter`]s.  \
-/// Some filters are just a name, although some have additional arguments.
+/// Consume an entire string, and return every [`Placeholder`] in the order
+/// that they appear in the source, rather than the reverse order of
+/// [`parse_placeholder_locations`].
 ///
-/// For more explanation on what a `Placeholder` looks like inside a template,
-/// see [`parse_placeholder`].
+/// # Example
+/// ```rust
+/// use blogs_md_easy::{parse_placeholder_locations_in_order, Span};
 ///
-/// For more explanation on what a [`Filter`] looks like inside a `Placeholder`,
-/// see [`parse_filter`].
-#[derive(Clone, Debug, Default, PartialEq)]
-pub struct Placeholder {
-    pub name: String,
-    pub selection: Selection,
-    pub filters: Vec<Filter>,
+/// let input = Span::new("{{ £greeting }}, {{ £name }}!");
+/// let placeholders = parse_placeholder_locations_in_order(input).unwrap();
+/// assert_eq!(placeholders.len(), 2);
+/// assert_eq!(placeholders[0].name.as_str(), "greeting");
+/// assert_eq!(placeholders[1].name.as_str(), "name");
+/// ```
+pub fn parse_placeholder_locations_in_order(input: Span) -> Result<Vec<Placeholder>, Box<dyn Error>> {
+    let (_, placeholders) = many0(take_till_placeholder)(input).unwrap_or((input, Vec::new()));
+    Ok(placeholders)
 }
 
-
-////////////////////////////////////////////////////////////////////////////////
-// Parsers
-/// Parse any character until the end of the line.
-/// This will return all characters, except the newline which will be consumed
-/// and discarded.
-///
-/// # Examples
-/// When there is no newline.
-/// ```rust
-/// use blogs_md_easy::{parse_until_eol, Span};
+/// Find every [`Placeholder`] in a template, in the order that they appear.
 ///
-/// let input = Span::new("Hello, World!");
-/// let (input, until_eol) = parse_until_eol(input).unwrap();
-/// assert_eq!(input.fragment(), &"");
-/// assert_eq!(until_eol.fragment(), &"Hello, World!");
-/// ```
+/// Unlike [`parse_placeholder_locations`], which is ordered for replacing, this
+/// is intended for tooling that needs to know what a template expects, such as
+/// the filters applied to each variable.
 ///
-/// When there is a newline, the newline is consumed.
+/// # Example
 /// ```rust
-/// use blogs_md_easy::{parse_until_eol, Span};
+/// use blogs_md_easy::{template_placeholders, Filter, TextCase};
 ///
-/// let input = Span::new("Hello, World!\nThis is Sparta!");
-/// let (input, until_eol) = parse_until_eol(input).unwrap();
-/// assert_eq!(input.fragment(), &"This is Sparta!");
-/// assert_eq!(until_eol.fragment(), &"Hello, World!");
+/// let placeholders = template_placeholders("<h1>{{ £title | uppercase }}</h1>{{ £content }}");
+/// assert_eq!(placeholders.len(), 2);
+/// assert_eq!(placeholders[0].name, "title");
+/// assert_eq!(placeholders[0].filters, vec![Filter::Text { case: TextCase::Upper }]);
+/// assert_eq!(placeholders[1].name, "content");
 /// ```
-pub fn parse_until_eol(input: Span) -> IResult<Span, Span> {
-    terminated(
-        alt((take_until("\n"), rest)),
-        alt((tag("\n"), tag(""))),
-    )(input)
+pub fn template_placeholders(template: &str) -> Vec<Placeholder> {
+    parse_placeholder_locations_in_order(Span::new(template)).unwrap_or_default()
 }
 
-/// Parse a comment starting with either a `#` or `//` and ending with a newline.
+/// Format a parse error as a friendly message, quoting the offending line of
+/// the source with a caret beneath the column where parsing failed.
+///
+/// The `offset` is the byte offset into `source`, such as from
+/// `Span::location_offset()`, and `expected` describes what should have been
+/// found there.
 ///
 /// # Example
 /// ```rust
-/// use blogs_md_easy::{parse_meta_comment, Span};
+/// use blogs_md_easy::format_parse_error;
 ///
-/// let input = Span::new("# This is a comment");
-/// let (input, meta_comment) = parse_meta_comment(input).unwrap();
-/// assert_eq!(input.fragment(), &"");
-/// assert_eq!(meta_comment.fragment(), &"This is a comment");
+/// let source = "<h1>\n{{ £title | }}\n</h1>";
+/// let message = format_parse_error(source, 18, "a filter name");
+/// assert_eq!(message, "Expected a filter name on line 2, column 13:\n{{ £title | }}\n            ^");
 /// ```
-pub fn parse_meta_comment(input: Span) -> IResult<Span, Span> {
-    preceded(
-        // All comments start with either a `#` or `//` followed by a space(s).
-        tuple((alt((tag("#"), tag("//"))), space0)),
-        parse_until_eol,
-    )(input)
+pub fn format_parse_error(source: &str, offset: usize, expected: &str) -> String {
+    let offset = offset.min(source.len());
+    let line_start = source[..offset].rfind('\n').map_or(0, |index| index + 1);
+    let line_end = source[offset..].find('\n').map_or(source.len(), |index| offset + index);
+    let line = source[line_start..line_end].trim_end_matches('\r');
+    let line_number = source[..line_start].matches('\n').count() + 1;
+
+    // Keep any tabs, so that the caret lines up with the column.
+    let indent = source[line_start..offset].chars()
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect::<String>();
+
+    format!(
+        "Expected {} on line {}, column {}:\n{}\n{}^",
+        expected,
+        line_number,
+        indent.chars().count() + 1,
+        line,
+        indent
+    )
 }
 
-/// Parse a key, that starts with an optional `£`, followed by an alphabetic
-/// character, then any number of alphanumeric characters, hyphens and
-/// underscores.
+/// Find the first malformed [`Placeholder`] in a template, returning a
+/// message from [`format_parse_error`] that points to where it went wrong.
+///
+/// A `{{` is only considered the start of a placeholder when it is followed by
+/// a `£` or `$`, so any other use of braces is left alone.
 ///
 /// # Examples
-/// A valid variable, consisting of letters and underscores.
+/// An empty filter is pointed out.
 /// ```rust
-/// use blogs_md_easy::{parse_meta_key, Span};
+/// use blogs_md_easy::find_placeholder_error;
 ///
-/// let input = Span::new("£publish_date");
-/// let (_, variable) = parse_meta_key(input).unwrap();
-/// assert_eq!(variable.fragment(), &"publish_date");
+/// let error = find_placeholder_error("{{ £x | }}").unwrap();
+/// assert_eq!(error, "Expected a filter name on line 1, column 9:\n{{ £x | }}\n        ^");
+/// ```
 ///
-/// let input = Span::new("$publish_date");
-/// let (_, variable) = parse_meta_key(input).unwrap();
-/// assert_eq!(variable.fragment(), &"publish_date");
+/// A valid template has no errors.
+/// ```rust
+/// use blogs_md_easy::find_placeholder_error;
+///
+/// assert!(find_placeholder_error("{{ £title | uppercase }} {{ not a placeholder }}").is_none());
 /// ```
-/// An invalid example, variables cannot start with a number.
+pub fn find_placeholder_error(template: &str) -> Option<String> {
+    type SpanError<'a> = nom::error::Error<Span<'a>>;
+
+    for (start, _) in template.match_indices("{{") {
+        let start = start + 2;
+        let offset = |span: Span| start + span.location_offset();
+        let (input, _) = multispace0::<Span, SpanError>(Span::new(&template[start..])).ok()?;
+
+        let Some(sigil) = input.fragment().chars().next().filter(|c| ['£', '$'].contains(c)) else {
+            continue;
+        };
+        let Ok((mut input, _)) = parse_variable(input) else {
+            return Some(format_parse_error(template, offset(input) + sigil.len_utf8(), "a variable name"));
+        };
+
+        loop {
+            let (rest, pipe) = opt(tuple((space0::<Span, SpanError>, tag("|"), space0)))(input).ok()?;
+            if pipe.is_none() {
+                break;
+            }
+            match parse_filter_with_unknown_arguments(rest) {
+                Ok((rest, _)) => input = rest,
+                Err(_) => return Some(format_parse_error(template, offset(rest), "a filter name")),
+            }
+        }
+
+        let (input, _) = multispace0::<Span, SpanError>(input).ok()?;
+        if !input.fragment().starts_with("}}") {
+            return Some(format_parse_error(template, offset(input), "`|` or `}}`"));
+        }
+    }
+
+    None
+}
+
+/// Find the unique names of the variables that a template's placeholders use,
+/// in the order that they first appear.
+///
+/// # Example
 /// ```rust
-/// use blogs_md_easy::{parse_meta_key, Span};
+/// use blogs_md_easy::template_variables;
 ///
-/// let input = Span::new("£1_to_2");
-/// let variable = parse_meta_key(input);
-/// assert!(variable.is_err());
+/// let template = "<title>{{ £title }}</title><h1>{{ £title | uppercase }}</h1>{{ £content }}";
+/// assert_eq!(template_variables(template), vec!["title", "content"]);
 /// ```
-pub fn parse_meta_key(input: Span) -> IResult<Span, Span> {
-    preceded(
-        opt(alt((tag("£"), tag("$")))),
-        parse_variable_name
-    )(input)
+pub fn template_variables(template: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for placeholder in template_placeholders(template) {
+        if !names.contains(&placeholder.name) {
+            names.push(placeholder.name);
+        }
+    }
+    names
 }
 
-/// Parse any number of characters until the end of the line or string.
+/// Parse the opening tag of a [`Conditional`] block, returning whether the
+/// block is negated, and the variable name.
 ///
 /// # Examples
-/// Meta values are essentially just anything that isn't a newline.
 /// ```rust
-/// use blogs_md_easy::{parse_meta_value, Span};
-///
-/// let input = Span::new("This is a value");
-/// let (_, value) = parse_meta_value(input).unwrap();
-/// assert_eq!(value.fragment(), &"This is a value");
-/// ```
+/// use blogs_md_easy::{parse_conditional_start, Span};
 ///
-/// However, if you need newlines, then wrap the string in double quotes.  \
-/// Don't forget to escape your quotes too!
-/// ```rust
-/// use blogs_md_easy::{parse_meta_value, Span};
+/// let input = Span::new("{{ #if £subtitle }}");
+/// let (_, (negated, variable)) = parse_conditional_start(input).unwrap();
+/// assert!(!negated);
+/// assert_eq!(variable.fragment(), &"subtitle");
 ///
-/// let input = Span::new(r#""This \"value\" is on
-/// a new line""#);
-/// let (_, value) = parse_meta_value(input).unwrap();
-/// assert_eq!(value.fragment(), &"This \\\"value\\\" is on\na new line");
+/// let input = Span::new("{{#unless £subtitle}}");
+/// let (_, (negated, variable)) = parse_conditional_start(input).unwrap();
+/// assert!(negated);
+/// assert_eq!(variable.fragment(), &"subtitle");
 /// ```
-pub fn parse_meta_value(input: Span) -> IResult<Span, Span> {
-    alt((
-        // Match a delimited quote string.
-        delimited(
-            tag(r#"""#),
-            // Match everything that isn't `\"`.
-            escaped(
-                is_not(r#"\""#),
-                '\\',
-                one_of(r#"nrt\""#)
-            ),
-            tag(r#"""#),
+pub fn parse_conditional_start(input: Span) -> IResult<Span, (bool, Span)> {
+    delimited(
+        tuple((tag("{{"), multispace0, tag("#"))),
+        separated_pair(
+            alt((tag("if"), tag("unless"))).map(|keyword: Span| *keyword.fragment() == "unless"),
+            multispace1,
+            parse_variable,
         ),
-        // The value of the variable, everything after the equals sign.
-        // Continue to a newline or the end of the string.
-        parse_until_eol,
-    ))(input)
+        tuple((multispace0, tag("}}"))),
+    )(input)
 }
 
-/// Parse a key-value pair of meta_key and meta_value.
+/// Parse the closing tag of a [`Conditional`] block, returning whether it
+/// closes a negated block.
 ///
 /// # Example
 /// ```rust
-/// use blogs_md_easy::{parse_meta_key_value, Span};
+/// use blogs_md_easy::{parse_conditional_end, Span};
 ///
-/// let input = Span::new("£publish_date = 2021-01-01");
-/// let (_, meta) = parse_meta_key_value(input).unwrap();
-/// assert_eq!(meta.key, "publish_date");
-/// assert_eq!(meta.value, "2021-01-01");
+/// let (_, negated) = parse_conditional_end(Span::new("{{ /if }}")).unwrap();
+/// assert!(!negated);
+///
+/// let (_, negated) = parse_conditional_end(Span::new("{{/unless}}")).unwrap();
+/// assert!(negated);
 /// ```
-pub fn parse_meta_key_value(input: Span) -> IResult<Span, Meta> {
-    separated_pair(
-        parse_meta_key,
-        recognize(tuple((space0, tag("="), space0))),
-        parse_meta_value
+pub fn parse_conditional_end(input: Span) -> IResult<Span, bool> {
+    delimited(
+        tuple((tag("{{"), multispace0, tag("/"))),
+        alt((tag("if"), tag("unless"))).map(|keyword: Span| *keyword.fragment() == "unless"),
+        tuple((multispace0, tag("}}"))),
     )(input)
-    .map(|(input, (key, value))| {
-        (input, Meta::new(key.fragment(), value.fragment()))
-    })
 }
 
-/// Parse a line of meta data. This can either be a comment or a key-value pair.
+/// Parse an entire [`Conditional`] block, from the opening tag to its matching
+/// closing tag.
+///
+/// Blocks can be nested, and the closing tag must match the opening tag, so an
+/// `#if` is closed by `/if`, and an `#unless` by `/unless`.
 ///
 /// # Examples
-/// Parsing of a comment returns None.
 /// ```rust
-/// use blogs_md_easy::{parse_meta_line, Span};
+/// use blogs_md_easy::{parse_conditional, Span};
 ///
-/// let input = Span::new("# This is a comment");
-/// let (_, meta) = parse_meta_line(input).unwrap();
-/// assert!(meta.is_none());
+/// let input = Span::new("{{ #if £subtitle }}<h2>{{ £subtitle }}</h2>{{ /if }}!");
+/// let (input, conditional) = parse_conditional(input).unwrap();
+/// assert_eq!(input.fragment(), &"!");
+/// assert_eq!(conditional.name, "subtitle");
+/// assert!(!conditional.negated);
+/// assert_eq!(conditional.selection.start.offset, 0);
+/// assert_eq!(conditional.selection.end.offset, 54);
+/// assert_eq!(conditional.body.start.offset, 20);
+/// assert_eq!(conditional.body.end.offset, 45);
 /// ```
-/// Parsing of a key-value pair returns a Meta object.
+///
+/// A block without a matching closing tag is not a `Conditional`.
 /// ```rust
-/// use blogs_md_easy::{parse_meta_line, Span};
+/// use blogs_md_easy::{parse_conditional, Span};
 ///
-/// let input = Span::new("£publish_date = 2021-01-01");
-/// let (_, meta) = parse_meta_line(input).unwrap();
-/// assert!(&meta.is_some());
-/// let meta = meta.unwrap();
-/// assert_eq!(&meta.key, "publish_date");
-/// assert_eq!(&meta.value, "2021-01-01");
+/// let input = Span::new("{{ #if £subtitle }}<h2>{{ £subtitle }}</h2>{{ /unless }}");
+/// assert!(parse_conditional(input).is_err());
 /// ```
-pub fn parse_meta_line(input: Span) -> IResult<Span, Option<Meta>> {
-    let (input, _) = space0(input)?;
-    let (input, res) = alt((
-        parse_meta_comment.map(|_| None),
-        parse_meta_key_value.map(Some),
-    ))(input)?;
-    let (input, _) = multispace0(input)?;
-    Ok((input, res))
+pub fn parse_conditional(input: Span) -> IResult<Span, Conditional> {
+    let (body, (negated, variable)) = parse_conditional_start(input)?;
+    let (after, (closing, end_negated)) = parse_block_body(body, parse_conditional_start, parse_conditional_end)?;
+    if end_negated != negated {
+        return Err(nom::Err::Error(nom::error::Error::new(closing, nom::error::ErrorKind::Tag)));
+    }
+
+    Ok((after, Conditional {
+        name: variable.to_string(),
+        negated,
+        selection: Selection { start: Marker::new(input), end: Marker::new(after) },
+        body: Selection { start: Marker::new(body), end: Marker::new(closing) },
+    }))
 }
 
-/// Parse the meta section. This is either a `:meta`, `<meta>`, or `<?meta` tag
-/// surrounding a Vector of [`parse_meta_line`].
-///
-/// # Example
-/// ```rust
-/// use blogs_md_easy::{parse_meta_section, Meta, Span};
+/// Consume the body of a block, up to the closing tag that matches its depth,
+/// so that the same kind of block can be nested within it.
 ///
-/// let input = Span::new(":meta\n// This is the published date\npublish_date = 2021-01-01\n:meta\n# Markdown title");
-/// let (input, meta) = parse_meta_section(input).unwrap();
-/// // Comments are ignored and removed from the Vector.
-/// assert_eq!(meta.len(), 1);
-/// assert_eq!(meta, vec![
-///     Meta {
-///         key: "publish_date".to_string(),
-///         value: "2021-01-01".to_string(),
-///     },
-/// ]);
-/// assert_eq!(input.fragment(), &"# Markdown title");
-/// ```
-pub fn parse_meta_section(input: Span) -> IResult<Span, Vec<Meta>> {
-    alt((
-        // I can't think of a more elegant solution for ensuring the pairs match
-        // one another. The previous solution could open with `:meta` and close
-        // with `</meta>` for example.
-        delimited(
-            tuple((multispace0, tag(":meta"), multispace0)),
-            many1(parse_meta_line),
-            tuple((multispace0, tag(":meta"), multispace0)),
-        ),
-        delimited(
-            tuple((multispace0, tag("<?"), opt(tag("meta")), multispace0)),
-            many1(parse_meta_line),
-            tuple((multispace0, tag("?>"), multispace0)),
-        ),
-        delimited(
-            tuple((multispace0, tag("<meta>"), multispace0)),
-            many1(parse_meta_line),
-            tuple((multispace0, tag("</meta>"), multispace0)),
-        ),
-    ))(input)
-    // Filter out None values, leaving only legitimate meta values.
-    .map(|(input, res)| {
-        // When calling flatten on Option<> types, None values are considered
-        // empty iterators and removed, Some values are considered iterators
-        // with a single element and are therefore unwrapped and returned.
-        (input, res.into_iter().flatten().collect())
-    })
+/// Returns the input following the closing tag, along with the input at the
+/// start of the closing tag and the output of the `end` parser.
+fn parse_block_body<'a, S, E>(
+    input: Span<'a>,
+    start: fn(Span<'a>) -> IResult<Span<'a>, S>,
+    end: fn(Span<'a>) -> IResult<Span<'a>, E>,
+) -> IResult<Span<'a>, (Span<'a>, E)> {
+    let mut depth = 0;
+    let mut rest = input;
+    loop {
+        if let Ok((after, _)) = start(rest) {
+            depth += 1;
+            rest = after;
+        } else if let Ok((after, output)) = end(rest) {
+            if depth == 0 {
+                return Ok((after, (rest, output)));
+            }
+            depth -= 1;
+            rest = after;
+        } else {
+            (rest, _) = anychar(rest)?;
+        }
+    }
 }
 
-/// Parse the title of the document. This is either a Markdown title or an HTML
-/// heading with the `h1` tag.
+/// Consume an entire string, and return a Vector of the outermost
+/// [`Conditional`] blocks, sorted in reverse order.
+///
+/// Any nested blocks are within the `body` of their parent, and can be found by
+/// parsing the body again.
 ///
 /// # Examples
-/// Using a Markdown heading.
 /// ```rust
-/// use blogs_md_easy::{parse_title, Span};
+/// use blogs_md_easy::{parse_conditional_locations, Span};
 ///
-/// let input = Span::new("# This is the title");
-/// let (_, title) = parse_title(input).unwrap();
-/// assert_eq!(title.fragment(), &"This is the title");
+/// let input = Span::new("{{ #if £a }}A{{ /if }} and {{ #unless £b }}{{ #if £c }}C{{ /if }}{{ /unless }}");
+/// let conditionals = parse_conditional_locations(input).unwrap();
+/// assert_eq!(conditionals.len(), 2);
+/// assert_eq!(conditionals[0].name, "b");
+/// assert!(conditionals[0].negated);
+/// assert_eq!(conditionals[1].name, "a");
 /// ```
-/// Using an HTML heading.
+///
+/// A tag without its partner is an error, rather than being left in the output.
 /// ```rust
-/// use blogs_md_easy::{parse_title, Span};
+/// use blogs_md_easy::{parse_conditional_locations, Span};
 ///
-/// let input = Span::new("<h1>This is the title</h1>");
-/// let (_, title) = parse_title(input).unwrap();
-/// assert_eq!(title.fragment(), &"This is the title");
+/// let input = Span::new("{{ #if £a }}A");
+/// assert!(parse_conditional_locations(input).is_err());
+///
+/// let input = Span::new("A{{ /if }}");
+/// assert!(parse_conditional_locations(input).is_err());
 /// ```
-pub fn parse_title(input: Span) -> IResult<Span, Span> {
-    let (input, _) = multispace0(input)?;
+pub fn parse_conditional_locations(input: Span) -> Result<Vec<Conditional>, Box<dyn Error>> {
+    let mut conditionals = parse_block_locations(input, "conditional", parse_conditional_start, parse_conditional, parse_conditional_end)?;
 
-    let (input, title) = alt((
-        // Either a Markdown title...
-        preceded(tuple((tag("#"), space0)), take_till(|c| c == '\n' || c == '\r')),
-        // ... or an HTML title.
-        delimited(tag("<h1>"), take_until("</h1>"), tag("</h1>"))
-    ))(input)?;
+    // Sort in reverse so that when we replace each block, the offsets do not
+    // affect offsets after this point.
+    conditionals.sort_by_key(|conditional| std::cmp::Reverse(conditional.selection.start.offset));
 
-    Ok((input.to_owned(), title.to_owned()))
+    Ok(conditionals)
 }
 
-/// Rewrite of the `nom::is_alphabetic` function that takes a char instead.
+/// Parse the opening tag of a [`Loop`] block, returning the name of the item
+/// variable, the name of the list variable, and the filters applied to the
+/// list.
 ///
-/// # Example
+/// Without any filters, the list is split on commas, as with [`Filter::Split`].
+///
+/// # Examples
 /// ```rust
-/// use blogs_md_easy::is_alphabetic;
+/// use blogs_md_easy::{parse_loop_start, Filter, Span};
 ///
-/// assert!(is_alphabetic('a'));
-/// assert!(is_alphabetic('A'));
-/// assert!(!is_alphabetic('1'));
-/// assert!(!is_alphabetic('-'));
+/// let input = Span::new("{{ #for £tag in £tags }}");
+/// let (_, (item, list, filters)) = parse_loop_start(input).unwrap();
+/// assert_eq!(item.fragment(), &"tag");
+/// assert_eq!(list.fragment(), &"tags");
+/// assert_eq!(filters, vec![Filter::Split { separator: ",".to_string() }]);
 /// ```
-pub fn is_alphabetic(input: char) -> bool {
-    vec!['a'..='z', 'A'..='Z'].into_iter().flatten().any(|c| c == input)
+///
+/// The list can be given filters, just like a placeholder.
+/// ```rust
+/// use blogs_md_easy::{parse_loop_start, Filter, Span};
+///
+/// let input = Span::new("{{#for £tag in £tags | split = ';'}}");
+/// let (_, (_, _, filters)) = parse_loop_start(input).unwrap();
+/// assert_eq!(filters, vec![Filter::Split { separator: ";".to_string() }]);
+/// ```
+pub fn parse_loop_start(input: Span) -> IResult<Span, (Span, Span, Vec<Filter>)> {
+    delimited(
+        tuple((tag("{{"), multispace0, tag("#for"), multispace1)),
+        tuple((
+            parse_variable,
+            delimited(multispace1, tag("in"), multispace1),
+            parse_variable,
+            opt(parse_filters),
+        )),
+        tuple((multispace0, tag("}}"))),
+    )(input)
+    .map(|(input, (item, _, list, filters))| {
+        let filters = filters.unwrap_or(vec![Filter::Split { separator: ",".to_string() }]);
+        (input, (item, list, filters))
+    })
 }
 
-/// A function that checks if a character is valid for a filter name.
-///
-/// The filter name is the value before the `=` in a Template.
+/// Parse the closing tag of a [`Loop`] block.
 ///
 /// # Example
 /// ```rust
-/// use blogs_md_easy::is_filter_name;
+/// use blogs_md_easy::{parse_loop_end, Span};
 ///
-/// assert!(is_filter_name('a'));
-/// assert!(is_filter_name('A'));
-/// assert!(is_filter_name('1'));
-/// assert!(!is_filter_name('-'));
-/// assert!(!is_filter_name(' '));
+/// let (input, _) = parse_loop_end(Span::new("{{ /for }}!")).unwrap();
+/// assert_eq!(input.fragment(), &"!");
 /// ```
-pub fn is_filter_name(input: char) -> bool {
-    input.is_alphanumeric() || ['_'].contains(&input)
+pub fn parse_loop_end(input: Span) -> IResult<Span, Span> {
+    recognize(tuple((tag("{{"), multispace0, tag("/for"), multispace0, tag("}}"))))(input)
 }
 
-/// A function that checks if a character is valid for a filter argument name.
-///
-/// This is the string preceding the `=` in the `meta` section.
+/// Parse an entire [`Loop`] block, from the opening tag to its matching closing
+/// tag. Loops can be nested.
 ///
 /// # Example
 /// ```rust
-/// use blogs_md_easy::is_filter_arg;
+/// use blogs_md_easy::{parse_loop, Span};
 ///
-/// assert!(is_filter_arg('a'));
-/// assert!(is_filter_arg('A'));
-/// assert!(is_filter_arg('1'));
-/// assert!(!is_filter_arg('-'));
-/// assert!(!is_filter_arg(' '));
+/// let input = Span::new("{{ #for £tag in £tags }}<li>{{ £tag }}</li>{{ /for }}!");
+/// let (input, block) = parse_loop(input).unwrap();
+/// assert_eq!(input.fragment(), &"!");
+/// assert_eq!(block.item, "tag");
+/// assert_eq!(block.name, "tags");
+/// assert_eq!(block.selection.start.offset, 0);
+/// assert_eq!(block.selection.end.offset, 56);
+/// assert_eq!(block.body.start.offset, 26);
+/// assert_eq!(block.body.end.offset, 46);
 /// ```
-pub fn is_filter_arg(input: char) -> bool {
-    input.is_alphanumeric() || ['_'].contains(&input)
+pub fn parse_loop(input: Span) -> IResult<Span, Loop> {
+    let (body, (item, list, filters)) = parse_loop_start(input)?;
+    let (after, (closing, _)) = parse_block_body(body, parse_loop_start, parse_loop_end)?;
+
+    Ok((after, Loop {
+        item: item.to_string(),
+        name: list.to_string(),
+        filters,
+        selection: Selection { start: Marker::new(input), end: Marker::new(after) },
+        body: Selection { start: Marker::new(body), end: Marker::new(closing) },
+    }))
 }
 
-/// A function that checks if a character is valid for a filter argument value.
+/// Consume an entire string, and return a Vector of the outermost [`Loop`]
+/// blocks, sorted in reverse order.
 ///
-/// This is the string following the `=` in the `meta` section.
+/// # Examples
+/// ```rust
+/// use blogs_md_easy::{parse_loop_locations, Span};
 ///
-/// # Example
+/// let input = Span::new("{{ #for £a in £list }}{{ #for £b in £list }}{{ /for }}{{ /for }} {{ #for £c in £list }}{{ /for }}");
+/// let loops = parse_loop_locations(input).unwrap();
+/// assert_eq!(loops.len(), 2);
+/// assert_eq!(loops[0].item, "c");
+/// assert_eq!(loops[1].item, "a");
+/// ```
+///
+/// A tag without its partner is an error.
 /// ```rust
-/// use blogs_md_easy::is_filter_value;
+/// use blogs_md_easy::{parse_loop_locations, Span};
 ///
-/// assert!(is_filter_value('a'));
-/// assert!(is_filter_value('A'));
-/// assert!(is_filter_value('1'));
-/// assert!(!is_filter_value('|'));
-/// assert!(!is_filter_value(','));
-/// assert!(!is_filter_value('{'));
-/// assert!(!is_filter_value('}'));
+/// let input = Span::new("{{ #for £tag in £tags }}");
+/// assert!(parse_loop_locations(input).is_err());
 /// ```
-pub fn is_filter_value(input: char) -> bool {
-    input.is_alphanumeric()
-    || ![' ', '|', ',', '{', '}'].contains(&input)
+pub fn parse_loop_locations(input: Span) -> Result<Vec<Loop>, Box<dyn Error>> {
+    let mut loops = parse_block_locations(input, "loop", parse_loop_start, parse_loop, parse_loop_end)?;
+
+    // Sort in reverse so that when we replace each block, the offsets do not
+    // affect offsets after this point.
+    loops.sort_by_key(|block| std::cmp::Reverse(block.selection.start.offset));
+
+    Ok(loops)
 }
 
-/// Variable names must start with an alphabetic character, then any number of
-/// alphanumeric characters, hyphens and underscores.
+/// Parse an [`Include`] directive. The path can be quoted with either single or
+/// double quotes, or left unquoted if it contains no spaces.
 ///
 /// # Examples
-/// Variables can consist of letters and underscores.
 /// ```rust
-/// use blogs_md_easy::{parse_variable_name, Span};
+/// use blogs_md_easy::{parse_include, Span};
 ///
-/// let input = Span::new("publish_date");
-/// let (_, variable) = parse_variable_name(input).unwrap();
-/// assert_eq!(variable.fragment(), &"publish_date");
+/// let input = Span::new("{{ #include \"partials/header.html\" }}<main>");
+/// let (input, include) = parse_include(input).unwrap();
+/// assert_eq!(input.fragment(), &"<main>");
+/// assert_eq!(include.path, "partials/header.html");
+/// assert_eq!(include.selection.start.offset, 0);
+/// assert_eq!(include.selection.end.offset, 37);
 /// ```
 ///
-/// Variables cannot start with a number or underscore.
 /// ```rust
-/// use blogs_md_easy::{parse_variable_name, Span};
+/// use blogs_md_easy::{parse_include, Span};
 ///
-/// let input = Span::new("1_to_2");
-/// let variable = parse_variable_name(input);
-/// assert!(variable.is_err());
+/// let input = Span::new("{{#include footer.html}}");
+/// let (_, include) = parse_include(input).unwrap();
+/// assert_eq!(include.path, "footer.html");
 /// ```
-pub fn parse_variable_name(input: Span) -> IResult<Span, Span> {
-    recognize(tuple((
-        take_while_m_n(1, 1, is_alphabetic),
-        many0(alt((alphanumeric1, tag("-"), tag("_")))),
-    )))(input)
+pub fn parse_include(input: Span) -> IResult<Span, Include> {
+    consumed(delimited(
+        tuple((tag("{{"), multispace0, tag("#include"), multispace1)),
+        verify(parse_filter_value, |path: &Span| !path.is_empty()),
+        tuple((multispace0, tag("}}"))),
+    ))(input)
+    .map(|(input, (directive, path))| (input, Include {
+        path: path.to_string(),
+        selection: Selection::from(directive, directive),
+    }))
 }
 
-/// Parse a template placeholder variable. This is a `£` followed by a variable
-/// name.
+/// Consume an entire string, and return a Vector of every [`Include`], sorted
+/// in reverse order.
 ///
-/// # Examples
-/// Variables must start with a `£`.
+/// # Example
 /// ```rust
-/// use blogs_md_easy::{parse_variable, Span};
+/// use blogs_md_easy::{parse_include_locations, Span};
+///
+/// let input = Span::new("{{ #include header.html }}<main></main>{{ #include footer.html }}");
+/// let includes = parse_include_locations(input).unwrap();
+/// assert_eq!(includes.len(), 2);
+/// assert_eq!(includes[0].path, "footer.html");
+/// assert_eq!(includes[1].path, "header.html");
+/// ```
+pub fn parse_include_locations(input: Span) -> Result<Vec<Include>, Box<dyn Error>> {
+    let (_, mut includes) = many0(
+        many_till(anychar, parse_include).map(|(_, include)| include)
+    )(input).unwrap_or((input, Vec::new()));
+
+    // Sort in reverse so that when we replace each include, the offsets do not
+    // affect offsets after this point.
+    includes.sort_by_key(|include| std::cmp::Reverse(include.selection.start.offset));
+
+    Ok(includes)
+}
+
+/// Consume an entire string, and return the outermost blocks, as parsed by
+/// `block`. An opening or closing tag without its partner is an error.
+fn parse_block_locations<'a, S, B, E>(
+    input: Span<'a>,
+    kind: &str,
+    start: fn(Span<'a>) -> IResult<Span<'a>, S>,
+    block: fn(Span<'a>) -> IResult<Span<'a>, B>,
+    end: fn(Span<'a>) -> IResult<Span<'a>, E>,
+) -> Result<Vec<B>, Box<dyn Error>> {
+    let mut blocks = Vec::new();
+    let mut rest = input;
+
+    while !rest.is_empty() {
+        if start(rest).is_ok() {
+            let (after, parsed) = block(rest)
+                .map_err(|_| format!("The {} block on line {} is not closed.", kind, rest.location_line()))?;
+            blocks.push(parsed);
+            rest = after;
+        } else if end(rest).is_ok() {
+            Err(format!("The {} block closed on line {} was never opened.", kind, rest.location_line()))?;
+        } else if let Ok((after, _)) = anychar::<Span, nom::error::Error<Span>>(rest) {
+            rest = after;
+        }
+    }
+
+    Ok(blocks)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Functions
+
+/// Replaces a substring in the original string with a replacement string.
+///
+/// # Arguments
+///
+/// * `original` - The original string.
+/// * `start` - The start position of the substring in the original string.
+/// * `end` - The end position of the substring in the original string.
+/// * `replacement` - The string to replace the substring.
+///
+/// # Returns
+///
+/// * A new string with the replacement in place of the original substring.
+///
+/// # Example
+/// ```
+/// use blogs_md_easy::replace_substring;
 ///
-/// let input = Span::new("£variable");
-/// let (_, variable) = parse_variable(input).unwrap();
-/// assert_eq!(variable.fragment(), &"variable");
+/// let original = "Hello, World!";
+/// let start = 7;
+/// let end = 12;
+/// let replacement = "Rust";
+/// let result = replace_substring(original, start, end, replacement);
+/// println!("{}", result);  // Prints: "Hello, Rust!"
 /// ```
 ///
-/// In keeping with what people are used to, it is also possible to use a `$`.
-/// ```rust
-/// use blogs_md_easy::{parse_variable, Span};
+/// The positions are byte offsets, and are clamped to the string rather than
+/// panicking. A position inside of a multibyte character is widened to include
+/// the whole character.
+/// ```
+/// use blogs_md_easy::replace_substring;
 ///
-/// let input = Span::new("$variable");
-/// let (_, variable) = parse_variable(input).unwrap();
-/// assert_eq!(variable.fragment(), &"variable");
+/// // The `£` is two bytes, so byte 1 is inside of it.
+/// assert_eq!(replace_substring("£5", 1, 3, "€6"), "€6");
+/// assert_eq!(replace_substring("Hello", 3, 100, "p!"), "Help!");
+/// assert_eq!(replace_substring("Hello", 4, 2, "!"), "Hell!o");
 /// ```
+pub fn replace_substring(original: &str, start: usize, end: usize, replacement: &str) -> String {
+    let mut start = start.min(original.len());
+    while !original.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut end = end.clamp(start, original.len());
+    while !original.is_char_boundary(end) {
+        end += 1;
+    }
+
+    let mut result = String::with_capacity(original.len() - (end - start) + replacement.len());
+    result.push_str(&original[..start]);
+    result.push_str(replacement);
+    result.push_str(&original[end..]);
+    result
+}
+
+/// Elements whose whitespace is significant, and so must not be minified.
+const PRESERVED_ELEMENTS: [&str; 5] = ["pre", "code", "textarea", "script", "style"];
+
+/// Checks whether the string starts with an opening tag for the given element
+/// name, ignoring case.
 ///
-/// Failing to start with a `£` will return an error.
+/// # Example
 /// ```rust
-/// use blogs_md_easy::{parse_variable, Span};
+/// use blogs_md_easy::is_opening_tag;
 ///
-/// let input = Span::new("variable");
-/// let variable = parse_variable(input);
-/// assert!(variable.is_err());
+/// assert!(is_opening_tag("<pre>", "pre"));
+/// assert!(is_opening_tag("<PRE class=\"rust\">", "pre"));
+/// assert!(!is_opening_tag("<premium>", "pre"));
+/// assert!(!is_opening_tag("</pre>", "pre"));
 /// ```
-pub fn parse_variable(input: Span) -> IResult<Span, Span> {
-    preceded(
-        alt((tag("£"), tag("$"))),
-        parse_variable_name
-    )(input)
+pub fn is_opening_tag(input: &str, name: &str) -> bool {
+    input.starts_with('<')
+    && input.get(1..=name.len()).is_some_and(|tag| tag.eq_ignore_ascii_case(name))
+    && input[name.len() + 1..].starts_with(['>', '/', ' ', '\t', '\n', '\r'])
 }
 
-/// Parser that will parse exclusively the key-values from after a filter.  \
-/// This will return the key (before the `:`) and the value (after the `:`). It
-/// will also return a key of `_` if no key was provided.
+/// Minify an HTML string by collapsing every run of whitespace into a single
+/// space, and removing any leading and trailing whitespace.
+///
+/// The contents of `<pre>`, `<code>`, `<textarea>`, `<script>`, and `<style>`
+/// elements are left untouched, as their whitespace is significant.
 ///
 /// # Examples
-/// Ensure that a key-value pair, separated by a colon, can be parsed into a
-/// tuple.
+/// Whitespace between tags is collapsed.
 /// ```rust
-/// use blogs_md_easy::{parse_filter_key_value, Span};
+/// use blogs_md_easy::minify_html;
 ///
-/// let input = Span::new("trail: ...");
-/// let (_, args) = parse_filter_key_value(input).unwrap();
-/// assert_eq!(args, ("trail", "..."));
+/// let html = "<main>\n    <h1>Title</h1>\n\n    <p>Some   text.</p>\n</main>\n";
+/// assert_eq!(minify_html(html), "<main> <h1>Title</h1> <p>Some text.</p> </main>");
 /// ```
 ///
-/// Ensure that a single value can be parsed into a tuple with a key of `_`.
+/// Whitespace inside a `<pre>` block is preserved.
 /// ```rust
-/// use blogs_md_easy::{parse_filter_key_value, Span};
+/// use blogs_md_easy::minify_html;
 ///
-/// let input = Span::new("20");
-/// let (_, args) = parse_filter_key_value(input).unwrap();
-/// assert_eq!(args, ("_", "20"));
+/// let html = "<p>Code:</p>\n<pre><code>fn main() {\n    println!();\n}</code></pre>";
+/// assert_eq!(minify_html(html), "<p>Code:</p> <pre><code>fn main() {\n    println!();\n}</code></pre>");
 /// ```
-pub fn parse_filter_key_value(input: Span) -> IResult<Span, (&str, &str)> {
-    alt((
-        // This matches a key-value separated by a colon.
-        // Example: `truncate = characters: 20`
-        separated_pair(
-            take_while(is_filter_arg).map(|arg: Span| *arg.fragment()),
-            tuple((space0, tag(":"), space0)),
-            take_while(is_filter_value).map(|value: Span| *value.fragment()),
-        ),
-        // But it's also possible to just provide a value.
-        // Example: `truncate = 20`
-        take_while(is_filter_value)
-        .map(|value: Span| ("_", *value.fragment()))
-    ))(input)
+pub fn minify_html(html: &str) -> String {
+    let mut minified = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some(c) = rest.chars().next() {
+        // Copy preserved elements verbatim, up to and including their closing
+        // tag.
+        if let Some(name) = PRESERVED_ELEMENTS.iter().find(|name| is_opening_tag(rest, name)) {
+            let closing = format!("</{}>", name);
+            // ASCII lowercase keeps the byte offsets identical to `rest`.
+            let end = rest.to_ascii_lowercase()
+                .find(&closing)
+                .map(|index| index + closing.len())
+                .unwrap_or(rest.len());
+            minified.push_str(&rest[..end]);
+            rest = &rest[end..];
+        } else if c.is_whitespace() {
+            minified.push(' ');
+            rest = rest.trim_start();
+        } else {
+            minified.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+
+    minified.trim().to_string()
 }
 
-/// Parser that will parse exclusively the key-values from after a filter.  \
-/// The signature of a filter is `filter_name = key1: value1, key2: value2,...`,
-/// or just `filter_name = value`.
-///
-/// # Examples
-/// Ensure that a key-value pair, separated by a colon, can be parsed into a
-/// tuple.
-/// ```rust
-/// use blogs_md_easy::{parse_filter_args, Span};
+/// Format a number for output from a maths [`Filter`].
 ///
-/// let input = Span::new("characters: 20, trail: ...");
-/// let (_, args) = parse_filter_args(input).unwrap();
-/// assert_eq!(args, vec![
-///     ("characters", "20"),
-///     ("trail", "..."),
-/// ]);
-/// ```
+/// Whole numbers are printed without a decimal point, and any other number is
+/// printed with the fewest decimal places that represent it. Negative zero is
+/// printed as `0`.
 ///
-/// Ensure that a single value can be parsed into a tuple with a key of `_`.
+/// # Examples
 /// ```rust
-/// use blogs_md_easy::{parse_filter_args, Span};
+/// use blogs_md_easy::format_number;
 ///
-/// let input = Span::new("20");
-/// let (_, args) = parse_filter_args(input).unwrap();
-/// assert_eq!(args, vec![
-///     ("_", "20")
-/// ]);
+/// assert_eq!(format_number(4.0), "4");
+/// assert_eq!(format_number(-4.0), "-4");
+/// assert_eq!(format_number(1.5), "1.5");
+/// assert_eq!(format_number(0.1 + 0.2), "0.30000000000000004");
+/// assert_eq!(format_number(-0.0), "0");
+/// assert_eq!(format_number(1e21), "1000000000000000000000");
 /// ```
-pub fn parse_filter_args(input: Span) -> IResult<Span, Vec<(&str, &str)>> {
-    separated_list1(
-        tuple((space0, tag(","), space0)),
-        parse_filter_key_value
-    )(input)
+pub fn format_number(number: f64) -> String {
+    if number == 0.0 {
+        // Both `0.0` and `-0.0` are equal, so this also drops the sign.
+        return "0".to_string();
+    }
+
+    // The `Display` implementation of `f64` already omits a trailing `.0`, and
+    // prints the shortest decimal that parses back to the same number.
+    number.to_string()
 }
 
-/// Parse a [`Filter`], and optionally its arguments if present.
+/// Parse a number that may contain grouping separators, such as `1,234.5`,
+/// by removing every character of `separators` before parsing it.
 ///
 /// # Examples
-/// A filter with no arguments.
 /// ```rust
-/// use blogs_md_easy::{parse_filter, Filter, Span, TextCase};
+/// use blogs_md_easy::parse_number;
 ///
-/// let input = Span::new("lowercase");
-/// let (_, filter) = parse_filter(input).unwrap();
-/// assert!(matches!(filter, Filter::Text { case: TextCase::Lower }));
+/// assert_eq!(parse_number("1,234.5", ","), Some(1234.5));
+/// assert_eq!(parse_number(" 1 234 ", " "), Some(1234.0));
+/// assert_eq!(parse_number("1.234,5", "."), None);
+/// assert_eq!(parse_number("many", ","), None);
 /// ```
+pub fn parse_number(input: &str, separators: &str) -> Option<f64> {
+    input.trim()
+        .chars()
+        .filter(|c| !separators.contains(*c))
+        .collect::<String>()
+        .parse::<f64>()
+        .ok()
+}
+
+/// Parse an ISO 8601 date, optionally with a time and a timezone offset, into
+/// the number of seconds since the Unix epoch.
 ///
-/// A filter with just a value, but no key.  \
-/// This will be parsed as a key of `_`, which will then be set to a key of the
-/// given enum Struct variant that is deemed the default.  \
-/// In the case of [`Filter::Truncate`], this will be the `characters`.
-/// ```rust
-/// use blogs_md_easy::{parse_filter, Filter, Span};
-///
-/// let input = Span::new("truncate = 20");
-/// let (_, filter) = parse_filter(input).unwrap();
-/// assert_eq!(filter, Filter::Truncate { characters: 20, trail: "...".to_string() });
-/// ```
+/// A date without a time is midnight, and a time without an offset is UTC.
+fn parse_datetime(input: &str) -> Option<i64> {
+    let input = input.trim();
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(input) {
+        return Some(datetime.timestamp());
+    }
+
+    // RFC 3339 requires the seconds, so try again with them added.
+    let offset = input.rfind(['Z', 'z', '+', '-']).filter(|index| input[..*index].contains(':'));
+    if let Some(index) = offset {
+        if let Ok(datetime) = DateTime::parse_from_rfc3339(&format!("{}:00{}", &input[..index], &input[index..])) {
+            return Some(datetime.timestamp());
+        }
+    }
+
+    ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M"]
+        .into_iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(input, format).ok())
+        .or_else(|| NaiveDate::parse_from_str(input, "%Y-%m-%d").ok()?.and_hms_opt(0, 0, 0))
+        .map(|datetime| datetime.and_utc().timestamp())
+}
+
+/// Describe a number of elapsed seconds in words, such as `5 minutes ago`.
+fn describe_time_ago(seconds: i64) -> String {
+    let elapsed = seconds.unsigned_abs();
+    let (amount, unit) = match elapsed {
+        0..=59 => return "just now".to_string(),
+        60..=3_599 => (elapsed / 60, "minute"),
+        3_600..=86_399 => (elapsed / 3_600, "hour"),
+        86_400..=2_591_999 => (elapsed / 86_400, "day"),
+        2_592_000..=31_535_999 => (elapsed / 2_592_000, "month"),
+        _ => (elapsed / 31_536_000, "year"),
+    };
+    let plural = if amount == 1 { "" } else { "s" };
+
+    if seconds < 0 {
+        format!("in {} {}{}", amount, unit, plural)
+    } else {
+        format!("{} {}{} ago", amount, unit, plural)
+    }
+}
+
+/// Convert a string into a URL friendly slug, by lowercasing it and joining
+/// each run of alphanumeric characters with a hyphen.
 ///
-/// A filter with multiple arguments, and given keys.
+/// # Examples
 /// ```rust
-/// use blogs_md_easy::{parse_filter, Filter, Span};
+/// use blogs_md_easy::slugify;
 ///
-/// let input = Span::new("truncate = characters: 15, trail:...");
-/// let (_, filter) = parse_filter(input).unwrap();
-/// assert!(matches!(filter, Filter::Truncate { .. }));
-/// assert_eq!(filter, Filter::Truncate {
-///     characters: 15,
-///     trail: "...".to_string(),
-/// });
+/// assert_eq!(slugify("Hello, World!"), "hello-world");
+/// assert_eq!(slugify("  Rust 2021 -- Edition "), "rust-2021-edition");
+/// assert_eq!(slugify("!?"), "");
 /// ```
+pub fn slugify(input: &str) -> String {
+    input
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<String>>()
+        .join("-")
+}
+
+/// Escape the characters that have a special meaning in HTML, so that a string
+/// can be placed in an element or attribute as text.
 ///
-/// For some filters, default values are provided, if not present.
+/// # Examples
 /// ```rust
-/// use blogs_md_easy::{parse_filter, Filter, Span};
+/// use blogs_md_easy::escape_html;
 ///
-/// let input = Span::new("truncate = trail:...");
-/// let (_, filter) = parse_filter(input).unwrap();
-/// assert!(matches!(filter, Filter::Truncate { .. }));
-/// assert_eq!(filter, Filter::Truncate {
-///     characters: 100,
-///     trail: "...".to_string(),
-/// });
+/// assert_eq!(escape_html("Tom & Jerry"), "Tom &amp; Jerry");
+/// assert_eq!(escape_html("<a href=\"#\">'1 < 2'</a>"), "&lt;a href=&quot;#&quot;&gt;&#39;1 &lt; 2&#39;&lt;/a&gt;");
 /// ```
-pub fn parse_filter(input: Span) -> IResult<Span, Filter> {
-    separated_pair(
-        take_while(is_filter_name),
-        opt(tuple((space0, tag("="), space0))),
-        opt(parse_filter_args)
-    )(input)
-    .map(|(input, (name, args))| {
-        let args: HashMap<&str, &str> = args.unwrap_or_default().into_iter().collect();
-
-        (input, match name.fragment().to_lowercase().trim() {
-            // Maths filters.
-            "ceil" => Filter::Ceil,
-            "floor" => Filter::Floor,
-            "round" => Filter::Round {
-                precision: args.get("precision").unwrap_or(
-                    args.get("_").unwrap_or(&"0")
-                ).parse::<u8>().unwrap_or(0),
-            },
-
-            // String filters.
-            "lowercase" => Filter::Text { case: TextCase::Lower },
-            "uppercase" => Filter::Text { case: TextCase::Upper },
-            "markdown" => Filter::Markdown,
-            "replace" => Filter::Replace {
-                find: args.get("find").unwrap_or(
-                    args.get("_").unwrap_or(&"")
-                ).to_string(),
-                replacement: args.get("replacement").unwrap_or(&"").to_string(),
-                limit: args.get("limit").map(|s| s.parse::<u8>().ok()).unwrap_or(None),
-            },
-            "reverse" => Filter::Reverse,
-            "truncate" => Filter::Truncate {
-                // Attempt to get the characters, but if we can't then we use
-                // the unnamed value, defined as "_".
-                characters: args.get("characters").unwrap_or(
-                    args.get("_").unwrap_or(&"100")
-                ).parse::<u8>().unwrap_or(100),
-                trail: args.get("trail").unwrap_or(&"...").to_string(),
-            },
-            "text" => Filter::Text {
-                // Default is `case: TextCase::Lower`.
-                case: args.get("case").unwrap_or(
-                    args.get("_").unwrap_or(&"lower")
-                ).parse::<TextCase>().unwrap_or(TextCase::Lower)
-            },
-            _ => {
-                dbg!(name);
-                unreachable!();
-            }
-        })
+pub fn escape_html(input: &str) -> String {
+    input.chars().fold(String::with_capacity(input.len()), |mut escaped, c| {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+        escaped
     })
 }
 
-/// Parsers a pipe (`|`) separated list of [`Filter`]s.
+/// Escape the characters that have a special meaning in XML, using the five
+/// entities that every XML parser understands.
+///
+/// This differs from [`escape_html`] only in using `&apos;`, which is defined
+/// by XML but not by HTML 4.
 ///
 /// # Examples
-/// A single filter.
 /// ```rust
-/// use blogs_md_easy::{parse_filters, Filter, Span, TextCase};
+/// use blogs_md_easy::escape_xml;
 ///
-/// // As in {{ £my_variable | lowercase }}
-/// let input = Span::new("| lowercase");
-/// let (_, filters) = parse_filters(input).unwrap();
-/// assert!(matches!(filters[0], Filter::Text { case: TextCase::Lower }));
+/// assert_eq!(escape_xml("Fish & Chips"), "Fish &amp; Chips");
+/// assert_eq!(escape_xml("<a title='1 < 2'>"), "&lt;a title=&apos;1 &lt; 2&apos;&gt;");
 /// ```
+pub fn escape_xml(input: &str) -> String {
+    escape_html(input).replace("&#39;", "&apos;")
+}
+
+/// Remove any inline Markdown syntax from a string, such as emphasis, code, and
+/// links, leaving only the plain text.
 ///
-/// Multiple filters chained together with `|`.
+/// # Examples
 /// ```rust
-/// use blogs_md_easy::{parse_filters, Filter, Span, TextCase};
+/// use blogs_md_easy::strip_markdown;
 ///
-/// // As in {{ £my_variable | lowercase | truncate = trail: ..! }}
-/// let input = Span::new("| lowercase | truncate = trail: ..!");
-/// let (_, filters) = parse_filters(input).unwrap();
-/// assert!(matches!(filters[0], Filter::Text { case: TextCase::Lower }));
-/// assert!(matches!(filters[1], Filter::Truncate { .. }));
-/// assert_eq!(filters[1], Filter::Truncate {
-///     characters: 100,
-///     trail: "..!".to_string(),
-/// });
+/// assert_eq!(strip_markdown("My *great* post"), "My great post");
+/// assert_eq!(strip_markdown("Using `cargo` with [Rust](https://rust-lang.org)"), "Using cargo with Rust");
+/// assert_eq!(strip_markdown("Tom & Jerry \\*unescaped\\*"), "Tom & Jerry *unescaped*");
+///
+/// // Only inline syntax is removed.
+/// assert_eq!(strip_markdown("2024. A year in review"), "2024. A year in review");
 /// ```
-pub fn parse_filters(input: Span) -> IResult<Span, Vec<Filter>> {
-    preceded(
-        tuple((space0, tag("|"), space0)),
-        separated_list1(tuple((space0, tag("|"), space0)), parse_filter)
-    )(input)
+pub fn strip_markdown(input: &str) -> String {
+    input
+        .lines()
+        .map(|line| {
+            // Parsing each line as the text of a heading means that only inline
+            // syntax applies, so `1. Intro` isn't mistaken for a list.
+            match markdown::to_mdast(&format!("# {}", line), &markdown::ParseOptions::gfm()) {
+                Ok(node) => plain_text(&node),
+                // Plain Markdown never fails to parse, but there's no harm in
+                // falling back to the original line.
+                Err(_) => line.to_string(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
 }
 
-/// Parse a template [`Placeholder`].
+/// Concatenate the text of a Markdown node and its children, including the alt
+/// text of images.
+fn plain_text(node: &markdown::mdast::Node) -> String {
+    match node {
+        markdown::mdast::Node::Image(image) => image.alt.to_owned(),
+        node => match node.children() {
+            Some(children) => children.iter().map(plain_text).collect(),
+            None => node.to_string(),
+        },
+    }
+}
+
+/// The tags that separate the text either side of them, such as a paragraph or
+/// line break, as opposed to inline tags such as `<em>`.
+const BLOCK_TAGS: &[&str] = &[
+    "address", "article", "aside", "blockquote", "br", "dd", "div", "dl", "dt",
+    "figcaption", "figure", "footer", "h1", "h2", "h3", "h4", "h5", "h6",
+    "header", "hr", "li", "main", "nav", "ol", "p", "pre", "section", "table",
+    "tbody", "td", "tfoot", "th", "thead", "tr", "ul",
+];
+
+/// Remove any HTML tags from a string, and decode the entities that
+/// [`escape_html`] produces, leaving plain text.
 ///
-/// This is a variable name, surrounded by `{{` and `}}`.  \
-/// Whitespace is optional.
+/// A non-breaking space, or any other entity, is replaced with a space. So is
+/// a `<br>` or block tag, such as `<p>`, unless it is already next to
+/// whitespace, so that the words either side of it stay apart.
 ///
 /// # Examples
-/// A simple [`Placeholder`].
-/// ```rust
-/// use blogs_md_easy::{parse_placeholder, Span};
-///
-/// let input = Span::new("{{ £variable }}");
-/// let (_, placeholder) = parse_placeholder(input).unwrap();
-/// assert_eq!(placeholder.name.as_str(), "variable");
-/// assert_eq!(placeholder.selection.start.offset, 0);
-/// assert_eq!(placeholder.selection.end.offset, 16);
-/// ```
-///
-/// A [`Placeholder`] without whitespace.
 /// ```rust
-/// use blogs_md_easy::{parse_placeholder, Span};
+/// use blogs_md_easy::strip_html;
 ///
-/// let input = Span::new("{{£variable}}");
-/// let (_, placeholder) = parse_placeholder(input).unwrap();
-/// assert_eq!(placeholder.name.as_str(), "variable");
-/// assert_eq!(placeholder.selection.start.offset, 0);
-/// assert_eq!(placeholder.selection.end.offset, 14);
+/// assert_eq!(strip_html("<p>Some <em>important</em> text.</p>"), "Some important text.");
+/// assert_eq!(strip_html("<h1>Tom &amp; Jerry&#39;s &lt;Cartoon&gt;</h1>"), "Tom & Jerry's <Cartoon>");
+/// assert_eq!(strip_html("A&nbsp;B&hellip;"), "A B ");
+/// assert_eq!(strip_html("Hello<br>World, <b>bold</b>ly"), "Hello World, boldly");
 /// ```
+pub fn strip_html(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some(c) = rest.chars().next() {
+        let end = match c {
+            '<' => rest.find('>'),
+            '&' => rest.find(';').filter(|end| rest[1..*end].chars().all(|c| c.is_alphanumeric() || c == '#')),
+            _ => None,
+        };
+        match end {
+            Some(end) if c == '&' => {
+                text.push_str(match &rest[1..end] {
+                    "amp" => "&",
+                    "lt" => "<",
+                    "gt" => ">",
+                    "quot" => "\"",
+                    "#39" | "apos" => "'",
+                    _ => " ",
+                });
+                rest = &rest[end + 1..];
+            },
+            Some(end) => {
+                let name = rest[1..end]
+                    .trim_start_matches('/')
+                    .split(|c: char| !c.is_ascii_alphanumeric())
+                    .next()
+                    .unwrap_or_default()
+                    .to_ascii_lowercase();
+                let separated = text.chars().last().is_none_or(char::is_whitespace)
+                    || rest[end + 1..].chars().next().is_none_or(char::is_whitespace);
+                if BLOCK_TAGS.contains(&name.as_str()) && !separated {
+                    text.push(' ');
+                }
+                rest = &rest[end + 1..];
+            },
+            None => {
+                text.push(c);
+                rest = &rest[c.len_utf8()..];
+            },
+        }
+    }
+
+    text
+}
+
+/// Give each `<h1>` to `<h6>` in an HTML string an `id`, using the [`slugify`]
+/// of its text.
 ///
-/// A [`Placeholder`] with a single [`Filter`].
+/// Headings that would share an `id` are given a numeric suffix, and headings
+/// that already have an `id` are left untouched. Neither will reuse an `id`
+/// that is already in the document.
+///
+/// # Examples
 /// ```rust
-/// use blogs_md_easy::{parse_placeholder, Filter, Span, TextCase};
+/// use blogs_md_easy::add_heading_ids;
 ///
-/// let input = Span::new("{{ £variable | uppercase }}");
-/// let (_, placeholder) = parse_placeholder(input).unwrap();
-/// assert_eq!(placeholder.name.as_str(), "variable");
-/// assert_eq!(placeholder.selection.start.offset, 0);
-/// assert_eq!(placeholder.selection.end.offset, 28);
-/// assert!(matches!(placeholder.filters[0], Filter::Text { case: TextCase::Upper }));
+/// let html = "<h1>Title</h1>\n<h2>Sub <em>heading</em></h2>";
+/// assert_eq!(add_heading_ids(html), "<h1 id=\"title\">Title</h1>\n<h2 id=\"sub-heading\">Sub <em>heading</em></h2>");
 /// ```
 ///
-/// A [`Placeholder`] with a two [`Filter`]s.
+/// Duplicate headings are made unique.
 /// ```rust
-/// use blogs_md_easy::{parse_placeholder, Filter, Span, TextCase};
+/// use blogs_md_easy::add_heading_ids;
 ///
-/// let input = Span::new("{{ £variable | lowercase | truncate = characters: 42 }}");
-/// let (_, placeholder) = parse_placeholder(input).unwrap();
-/// assert_eq!(placeholder.name.as_str(), "variable");
-/// assert_eq!(placeholder.selection.start.offset, 0);
-/// assert_eq!(placeholder.selection.end.offset, 56);
-/// assert!(matches!(placeholder.filters[0], Filter::Text { case: TextCase::Lower }));
-/// assert_eq!(placeholder.filters[1], Filter::Truncate { characters: 42, trail: "...".to_string() });
+/// let html = "<h2>Title</h2><h2>Title</h2><h2>Title</h2>";
+/// assert_eq!(add_heading_ids(html), "<h2 id=\"title\">Title</h2><h2 id=\"title-1\">Title</h2><h2 id=\"title-2\">Title</h2>");
+///
+/// let html = "<h2>Title</h2><a id=\"title\"></a>";
+/// assert_eq!(add_heading_ids(html), "<h2 id=\"title-1\">Title</h2><a id=\"title\"></a>");
 /// ```
-pub fn parse_placeholder(input: Span) -> IResult<Span, Placeholder> {
-    tuple((
-        tuple((tag("{{"), multispace0)),
-        parse_variable,
-        opt(parse_filters),
-        tuple((multispace0, tag("}}"))),
-    ))(input)
-    .map(|(input, (start, variable, filters, end))| {
-        let mut filters = filters.unwrap_or_default();
+pub fn add_heading_ids(html: &str) -> String {
+    static ID_ATTRIBUTE: OnceLock<Regex> = OnceLock::new();
+    let id_attribute = ID_ATTRIBUTE.get_or_init(|| {
+        Regex::new(r#"(?i)\sid\s*=\s*(?:"([^"]*)"|'([^']*)')"#).expect("to be a valid pattern")
+    });
 
-        // By default, £content will always be parsed as Markdown.
-        if variable.to_ascii_lowercase().as_str() == "content" && !filters.contains(&Filter::Markdown) {
-            filters.push(Filter::Markdown);
+    let mut output = String::with_capacity(html.len());
+    // Start with the ids already in the document, so that none are repeated.
+    let mut ids: HashSet<String> = id_attribute.captures_iter(html)
+        .filter_map(|captures| captures.get(1).or(captures.get(2)))
+        .map(|id| id.as_str().to_string())
+        .collect();
+    // ASCII lowercase keeps the byte offsets identical to `html`, and is only
+    // done once, rather than for each heading.
+    let lowercase = html.to_ascii_lowercase();
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let heading = (1..=6)
+            .map(|level| format!("h{}", level))
+            .find(|name| is_opening_tag(rest, name));
+        let (Some(name), Some(tag_end)) = (heading, rest.find('>')) else {
+            output.push('<');
+            rest = &rest[1..];
+            continue;
+        };
+
+        let lowercase_rest = &lowercase[html.len() - rest.len()..];
+        let closing = format!("</{}>", name);
+        let content_end = lowercase_rest.find(&closing).unwrap_or(rest.len());
+        let tag = &rest[..tag_end];
+        output.push_str(tag);
+        if !id_attribute.is_match(tag) && content_end > tag_end {
+            let mut slug = slugify(&strip_html(&rest[tag_end + 1..content_end]));
+            if slug.is_empty() {
+                slug = "heading".to_string();
+            }
+
+            let mut id = slug.clone();
+            let mut suffix = 0;
+            while ids.contains(&id) {
+                suffix += 1;
+                id = format!("{}-{}", slug, suffix);
+            }
+            output.push_str(&format!(" id=\"{}\"", id));
+            ids.insert(id);
         }
+        rest = &rest[tag_end..];
+    }
+    output.push_str(rest);
 
-        (input, Placeholder {
-            name: variable.to_string(),
-            filters,
-            selection: Selection::from(start.0, end.1)
-        })
-    })
+    output
 }
 
-/// Parse a string consuming - and discarding - any character, and stopping at
-/// the first matched placeholder, returning a [`Placeholder`] struct.
+/// Reverse the escaping that Markdown applies to the contents of a code block.
+#[cfg(feature = "highlight")]
+fn unescape_html(html: &str) -> String {
+    html.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&amp;", "&")
+}
+
+/// Syntax highlight a single code block, returning `None` if the language is
+/// not recognised.
+#[cfg(feature = "highlight")]
+fn highlight_code(code: &str, language: &str) -> Option<String> {
+    static SYNTAX_SET: OnceLock<SyntaxSet> = OnceLock::new();
+    let syntax_set = SYNTAX_SET.get_or_init(SyntaxSet::load_defaults_newlines);
+    let syntax = syntax_set.find_syntax_by_token(language)?;
+
+    let mut generator = ClassedHTMLGenerator::new_with_class_style(syntax, syntax_set, ClassStyle::Spaced);
+    for line in LinesWithEndings::from(code) {
+        generator.parse_html_for_line_which_includes_newline(line).ok()?;
+    }
+    Some(generator.finalize())
+}
+
+/// Syntax highlight each fenced code block in an HTML string, that was
+/// generated from Markdown.
+///
+/// The language is taken from the `language-` class of the `<code>` element,
+/// and each token is wrapped in a `<span>` with class names describing it,
+/// such as `<span class="storage type rust">`. These can be styled with any
+/// [syntect](https://docs.rs/syntect) compatible stylesheet.
+///
+/// Code blocks without a language, or with a language that isn't recognised,
+/// are left untouched.
 ///
 /// # Example
 /// ```rust
-/// use blogs_md_easy::{take_till_placeholder, Marker, Placeholder, Selection, Span};
+/// use blogs_md_easy::highlight_code_blocks;
 ///
-/// let input = Span::new("Hello, {{ £name }}!");
-/// let (input, placeholder) = take_till_placeholder(input).expect("to parse input");
-/// assert_eq!(input.fragment(), &"!");
-/// assert_eq!(placeholder, Placeholder {
-///     name: "name".to_string(),
-///     selection: Selection {
-///         start: Marker {
-///             line: 1,
-///             offset: 7,
-///         },
-///         end: Marker {
-///             line: 1,
-///             offset: 19,
-///         },
-///     },
-///     filters: vec![],
-/// });
+/// let html = "<pre><code class=\"language-rust\">let x = 1;\n</code></pre>";
+/// let output = highlight_code_blocks(html);
+///
+/// assert!(output.starts_with("<pre><code class=\"language-rust\"><span class=\"source rust\">"));
+/// assert!(output.contains("<span class=\"storage type rust\">let</span>"));
 /// ```
-pub fn take_till_placeholder(input: Span) -> IResult<Span, Placeholder> {
-    many_till(anychar, parse_placeholder)(input)
-    // Map to remove anychar's captures.
-    .map(|(input, (_, placeholder))| (input, placeholder))
+#[cfg(feature = "highlight")]
+pub fn highlight_code_blocks(html: &str) -> String {
+    const OPENING: &str = "<pre><code class=\"language-";
+    const CLOSING: &str = "</code></pre>";
+
+    let mut output = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some(start) = rest.find(OPENING) {
+        let (before, block) = rest.split_at(start);
+        output.push_str(before);
+
+        let (Some(tag_end), Some(code_end)) = (block[OPENING.len()..].find('>').map(|end| end + OPENING.len()), block.find(CLOSING)) else {
+            rest = block;
+            break;
+        };
+        let language = block[OPENING.len()..tag_end].trim_end_matches('"');
+        let code = &block[tag_end + 1..code_end];
+
+        output.push_str(&block[..=tag_end]);
+        output.push_str(&highlight_code(&unescape_html(code), language).unwrap_or_else(|| code.to_string()));
+        output.push_str(CLOSING);
+        rest = &block[code_end + CLOSING.len()..];
+    }
+    output.push_str(rest);
+
+    output
 }
 
-/// Consume an entire string, and return a Vector of a tuple; where the first
-/// element is a String of the variable name, and the second element is the
-/// [`Placeholder`].
+/// Syntax highlighting requires the `highlight` feature, so without it the HTML
+/// is returned unchanged.
 ///
 /// # Example
 /// ```rust
-/// use blogs_md_easy::{parse_placeholder_locations, Span};
+/// use blogs_md_easy::highlight_code_blocks;
 ///
-/// let input = Span::new("Hello, {{ £name }}!");
-/// let placeholders = parse_placeholder_locations(input).unwrap();
-/// assert_eq!(placeholders.len(), 1);
-/// assert_eq!(placeholders[0].name.as_str(), "name");
-/// assert_eq!(placeholders[0].selection.start.offset, 7);
-/// assert_eq!(placeholders[0].selection.end.offset, 19);
+/// let html = "<pre><code class=\"language-rust\">let x = 1;\n</code></pre>";
+/// assert_eq!(highlight_code_blocks(html), html);
 /// ```
-pub fn parse_placeholder_locations(input: Span) -> Result<Vec<Placeholder>, Box<dyn Error>> {
-    let (_, mut placeholders) = many0(take_till_placeholder)(input).unwrap_or((input, Vec::new()));
+#[cfg(not(feature = "highlight"))]
+pub fn highlight_code_blocks(html: &str) -> String {
+    html.to_string()
+}
 
-    // Sort in reverse so that when we replace each placeholder, the offsets do
-    // not affect offsets after this point.
-    placeholders.sort_by(|a, b| b.selection.start.offset.cmp(&a.selection.start.offset));
+/// Reverse a string by grapheme, so that combining characters, such as accents,
+/// stay with the character that they belong to.
+#[cfg(feature = "graphemes")]
+fn reverse_text(text: &str) -> String {
+    text.graphemes(true).rev().collect()
+}
 
-    Ok(placeholders)
+/// Reverse a string by `char`, as grapheme support requires the `graphemes`
+/// feature.
+#[cfg(not(feature = "graphemes"))]
+fn reverse_text(text: &str) -> String {
+    text.chars().rev().collect()
 }
 
-////////////////////////////////////////////////////////////////////////////////
-// Functions
+/// Replace quotes, dashes, and ellipses with their typographic equivalents,
+/// line by line, leaving fenced code blocks and lines of only `-`, `=`, or `|`
+/// unchanged.
+fn smarten_typography(text: &str) -> String {
+    let mut smart = String::with_capacity(text.len());
+    let mut fence: Option<&str> = None;
 
-/// Replaces a substring in the original string with a replacement string.
-///
-/// # Arguments
-///
-/// * `original` - The original string.
-/// * `start` - The start position of the substring in the original string.
-/// * `end` - The end position of the substring in the original string.
-/// * `replacement` - The string to replace the substring.
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            smart.push_str(line);
+        } else if let Some(marker) = ["```", "~~~"].into_iter().find(|marker| trimmed.starts_with(marker)) {
+            fence = Some(marker);
+            smart.push_str(line);
+        } else if !trimmed.is_empty() && trimmed.chars().all(|c| "-=|: \t".contains(c)) {
+            // Thematic breaks, setext underlines, and table separator rows.
+            smart.push_str(line);
+        } else {
+            smart.push_str(&smarten_line(line));
+        }
+    }
+
+    smart
+}
+
+/// Replace quotes, dashes, and ellipses in a line with their typographic
+/// equivalents, by scanning each character along with the one before it.
 ///
-/// # Returns
+/// HTML tags and inline code are copied as they are.
+fn smarten_line(line: &str) -> String {
+    let chars = line.chars().collect::<Vec<char>>();
+    let mut smart = String::with_capacity(line.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        // Skip over a tag, such as `<a href="...">`, or a run of backticks
+        // along with the code that it opens, up to a run of the same length.
+        let verbatim = match chars[i] {
+            '<' if chars.get(i + 1).is_some_and(|c| c.is_ascii_alphabetic() || "/!?".contains(*c)) => {
+                chars[i..].iter().position(|c| *c == '>').map(|end| end + 1)
+            },
+            '`' => {
+                let ticks = chars[i..].iter().take_while(|c| **c == '`').count();
+                let mut end = i + ticks;
+                loop {
+                    match chars[end..].iter().position(|c| *c == '`') {
+                        Some(offset) => {
+                            let start = end + offset;
+                            let run = chars[start..].iter().take_while(|c| **c == '`').count();
+                            end = start + run;
+                            if run == ticks {
+                                break Some(end - i);
+                            }
+                        },
+                        // An unclosed run is just backticks.
+                        None => break Some(ticks),
+                    }
+                }
+            },
+            _ => None,
+        };
+        if let Some(length) = verbatim {
+            smart.extend(&chars[i..i + length]);
+            i += length;
+            continue;
+        }
+
+        let previous = i.checked_sub(1).map(|i| chars[i]);
+        // A quote opens at the start, or after whitespace or an opening
+        // bracket or dash.
+        let is_opening = previous.is_none_or(|c| c.is_whitespace() || "([{<—–".contains(c));
+
+        let (replacement, length) = match chars[i] {
+            '-' if chars.get(i + 1) == Some(&'-') && chars.get(i + 2) == Some(&'-') => ('—', 3),
+            '-' if chars.get(i + 1) == Some(&'-') => ('–', 2),
+            '.' if chars.get(i + 1) == Some(&'.') && chars.get(i + 2) == Some(&'.') => ('…', 3),
+            '"' if is_opening => ('“', 1),
+            '"' => ('”', 1),
+            // An abbreviated year, such as `'90s`, starts with an apostrophe.
+            '\'' if is_opening && !chars.get(i + 1).is_some_and(char::is_ascii_digit) => ('‘', 1),
+            '\'' => ('’', 1),
+            c => (c, 1),
+        };
+        smart.push(replacement);
+        i += length;
+    }
+
+    smart
+}
+
+/// Estimate how many minutes it takes to read some content, rounded up.
 ///
-/// * A new string with the replacement in place of the original substring.
+/// Only words that contain a letter or number are counted, so that Markdown
+/// syntax, such as a `#` or `---`, is ignored.
 ///
 /// # Example
-/// ```
-/// use blogs_md_easy::replace_substring;
+/// ```rust
+/// use blogs_md_easy::reading_time;
 ///
-/// let original = "Hello, World!";
-/// let start = 7;
-/// let end = 12;
-/// let replacement = "Rust";
-/// let result = replace_substring(original, start, end, replacement);
-/// println!("{}", result);  // Prints: "Hello, Rust!"
+/// let content = "# Title\n\n".to_string() + &"word ".repeat(450);
+/// assert_eq!(reading_time(&content, 200), 3);
+/// assert_eq!(reading_time(&content, 451), 1);
+/// assert_eq!(reading_time("", 200), 0);
 /// ```
-pub fn replace_substring(original: &str, start: usize, end: usize, replacement: &str) -> String {
-    let mut result = String::new();
-    result.push_str(&original[..start]);
-    result.push_str(replacement);
-    result.push_str(&original[end..]);
-    result
+pub fn reading_time(content: &str, words_per_minute: u32) -> u32 {
+    let words = content.split_whitespace()
+        .filter(|word| word.chars().any(char::is_alphanumeric))
+        .count();
+    words.div_ceil(words_per_minute.max(1) as usize) as u32
 }
 
 /// Creates a HashMap of key-value pairs from meta values.
@@ -1439,6 +5293,8 @@ pub fn replace_substring(original: &str, start: usize, end: usize, replacement:
 /// # Arguments
 /// * `markdown` - A LocatedSpan of the markdown file.
 /// * `meta_values` - An optional vector of Meta values.
+/// * `title_level` - The heading level that the title is parsed from, see
+///   [`parse_title`].
 ///
 /// # Returns
 /// Convert the meta_values into a [`HashMap`], then parse the title and content
@@ -1450,12 +5306,31 @@ pub fn replace_substring(original: &str, start: usize, end: usize, replacement:
 ///
 /// let markdown = Span::new(":meta\nauthor = John Doe\n:meta\n# Markdown title\nContent paragraph");
 /// let (markdown, meta_values) = parse_meta_section(markdown).unwrap_or((markdown, vec![]));
-/// let variables = create_variables(markdown, meta_values).expect("to create variables");
+/// let variables = create_variables(markdown, meta_values, 1).expect("to create variables");
 /// assert_eq!(variables.get("title").unwrap(), "Markdown title");
 /// assert_eq!(variables.get("author").unwrap(), "John Doe");
 /// assert_eq!(variables.get("content").unwrap(), "# Markdown title\nContent paragraph");
 /// ```
-pub fn create_variables(markdown: Span, meta_values: Vec<Meta>) -> Result<HashMap<String, String>, Box<dyn Error>> {
+///
+/// Meta values can reference any other variable by wrapping its key in braces,
+/// regardless of the order they are declared in.
+/// ```
+/// use blogs_md_easy::{create_variables, parse_meta_section, Span};
+///
+/// let markdown = Span::new(":meta\ncanonical = {site_url}/posts\nsite_url = https://example.com\n:meta\n# Markdown title");
+/// let (markdown, meta_values) = parse_meta_section(markdown).unwrap_or((markdown, vec![]));
+/// let variables = create_variables(markdown, meta_values, 1).expect("to create variables");
+/// assert_eq!(variables.get("canonical").unwrap(), "https://example.com/posts");
+/// ```
+pub fn create_variables(markdown: Span, meta_values: Vec<Meta>, title_level: u8) -> Result<HashMap<String, String>, Box<dyn Error>> {
+    // Keep the keys in the order they were declared, so that they are always
+    // resolved, and any cycle reported, in the same order.
+    let mut meta_keys: Vec<String> = Vec::with_capacity(meta_values.len());
+    for meta in &meta_values {
+        if !meta_keys.contains(&meta.key) {
+            meta_keys.push(meta.key.to_owned());
+        }
+    }
     let mut variables: HashMap<String, String> = meta_values
         .into_iter()
         .map(|meta| (meta.key.to_owned(), meta.value.to_owned()))
@@ -1463,11 +5338,11 @@ pub fn create_variables(markdown: Span, meta_values: Vec<Meta>) -> Result<HashMa
 
     // Make sure that we have a title and content variable.
     if !variables.contains_key("title") {
-        if let Ok(title) = parse_title(markdown) {
+        if let Ok(title) = parse_title(markdown, title_level) {
             let (_, title) = title;
-            variables.insert("title".to_string(), title.to_string());
+            variables.insert("title".to_string(), strip_markdown(title.fragment()));
         } else {
-            return Err("Missing title".to_string())?;
+            Err("Missing title".to_string())?;
         }
     }
     if !variables.contains_key("content") {
@@ -1475,9 +5350,63 @@ pub fn create_variables(markdown: Span, meta_values: Vec<Meta>) -> Result<HashMa
         variables.insert("content".to_string(), content);
     }
 
+    // Only the meta values can reference other variables, as the title and
+    // content come from the Markdown, where braces are just text.
+    let mut resolved: HashMap<String, String> = variables
+        .iter()
+        .filter(|(key, _)| !meta_keys.contains(*key))
+        .map(|(key, value)| (key.to_owned(), value.to_owned()))
+        .collect();
+    for key in &meta_keys {
+        resolve_variable(key, &variables, &mut resolved, &mut vec![])?;
+    }
+    variables.extend(resolved);
+
     Ok(variables)
 }
 
+/// Resolve each `{key}` reference within a variable's value, recursively, so
+/// that a meta value can be built from other variables.
+///
+/// References to keys that don't exist are left as they are. Resolved values
+/// are cached in `resolved`, and `chain` holds the keys currently being
+/// resolved, in order, so that a cycle can be reported rather than recursing
+/// forever.
+fn resolve_variable(key: &str, variables: &HashMap<String, String>, resolved: &mut HashMap<String, String>, chain: &mut Vec<String>) -> Result<String, Box<dyn Error>> {
+    if let Some(value) = resolved.get(key) {
+        return Ok(value.to_owned());
+    }
+    // Report the cycle from the key that started it.
+    if let Some(start) = chain.iter().position(|visited| visited == key) {
+        let mut cycle = chain[start..].to_vec();
+        cycle.push(key.to_string());
+        Err(format!("Circular meta reference: {}", cycle.join(" -> ")))?;
+    }
+    let Some(value) = variables.get(key) else {
+        return Ok(format!("{{{}}}", key));
+    };
+
+    static REFERENCE: OnceLock<Regex> = OnceLock::new();
+    let reference = REFERENCE.get_or_init(|| {
+        Regex::new(r"\{([A-Za-z][A-Za-z0-9_-]*)\}").expect("to be a valid pattern")
+    });
+
+    chain.push(key.to_string());
+    let mut output = String::with_capacity(value.len());
+    let mut last = 0;
+    for captures in reference.captures_iter(value) {
+        let (Some(whole), Some(name)) = (captures.get(0), captures.get(1)) else { continue };
+        output.push_str(&value[last..whole.start()]);
+        output.push_str(&resolve_variable(name.as_str(), variables, resolved, chain)?);
+        last = whole.end();
+    }
+    output.push_str(&value[last..]);
+    chain.pop();
+
+    resolved.insert(key.to_string(), output.to_owned());
+    Ok(output)
+}
+
 /// Make the start of each word capital, splitting on `sep`.
 ///
 /// # Examples
@@ -1538,6 +5467,46 @@ pub fn split_string(phrase: String, separators: &[char]) -> Vec<String> {
     words
 }
 
+/// Compile a regular expression once, for every render that uses it, returning
+/// `None` if the pattern is invalid.
+fn cached_regex(pattern: &str, ignore_case: bool) -> Option<Regex> {
+    // Keyed by the pattern and whether its case is ignored.
+    type Cache = HashMap<(String, bool), Option<Regex>>;
+    static CACHE: OnceLock<Mutex<Cache>> = OnceLock::new();
+    let mut cache = CACHE.get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    cache.entry((pattern.to_string(), ignore_case))
+        .or_insert_with(|| RegexBuilder::new(pattern).case_insensitive(ignore_case).build().ok())
+        .clone()
+}
+
+/// Take a variable, and run it through a [`Filter`] function, with access to
+/// every other variable, to get the new output.
+///
+/// This is needed by filters that read another variable, such as
+/// [`Filter::Fallback`]. Any other filter is the same as [`render_filter`].
+///
+/// # Example
+/// ```rust
+/// use std::collections::HashMap;
+/// use blogs_md_easy::{render_filter_with_variables, Filter, TextCase};
+///
+/// let variables = HashMap::from([("title".to_string(), "My Title".to_string())]);
+///
+/// let filter = Filter::Fallback { key: "title".to_string() };
+/// assert_eq!(render_filter_with_variables("".to_string(), &filter, &variables), "My Title");
+///
+/// let filter = Filter::Text { case: TextCase::Upper };
+/// assert_eq!(render_filter_with_variables("hello".to_string(), &filter, &variables), "HELLO");
+/// ```
+pub fn render_filter_with_variables(variable: String, filter: &Filter, variables: &HashMap<String, String>) -> String {
+    match filter {
+        Filter::Fallback { key } if variable.trim().is_empty() => variables.get(key).cloned().unwrap_or_default(),
+        filter => render_filter(variable, filter),
+    }
+}
+
 /// Take a variable, and run it through a [`Filter`] function to get the new
 /// output.
 ///
@@ -1558,61 +5527,362 @@ pub fn split_string(phrase: String, separators: &[char]) -> Vec<String> {
 /// use blogs_md_easy::{render_filter, Filter};
 ///
 /// let variable = "hello, world!".to_string();
-/// assert_eq!("hello...", render_filter(variable, &Filter::Truncate { characters: 5, trail: "...".to_string() }));
+/// assert_eq!("hello...", render_filter(variable, &Filter::Truncate { characters: 5, trail: "...".to_string(), safe: false }));
 /// ```
 pub fn render_filter(variable: String, filter: &Filter) -> String {
     match filter {
+        // Boolean filters.
+        Filter::Bool { truthy, falsy } => match variable.trim().to_lowercase().as_str() {
+            "true" | "1" | "yes" | "on" => truthy.to_owned(),
+            _ => falsy.to_owned(),
+        },
+        Filter::IsEmpty => variable.trim().is_empty().to_string(),
+        Filter::IsNumeric => variable.trim().parse::<f64>().is_ok_and(f64::is_finite).to_string(),
+
         // Maths filters.
-        Filter::Ceil => variable.parse::<f64>().unwrap_or_default().ceil().to_string(),
-        Filter::Floor => variable.parse::<f64>().unwrap_or_default().floor().to_string(),
-        Filter::Round { precision } => variable
-            .parse::<f64>()
+        Filter::Ceil => format_number(parse_number(&variable, ",").unwrap_or_default().ceil()),
+        Filter::FileSize { binary } => match variable.trim().parse::<u64>() {
+            Ok(bytes) => {
+                let (base, units) = if *binary {
+                    (1024.0, ["KiB", "MiB", "GiB", "TiB"])
+                } else {
+                    (1000.0, ["KB", "MB", "GB", "TB"])
+                };
+
+                let mut size = bytes as f64;
+                if size < base {
+                    format!("{} B", bytes)
+                } else {
+                    let mut unit = units[0];
+                    size /= base;
+                    for next in &units[1..] {
+                        // Compare the size as it will be shown, so that it
+                        // never rounds up to a whole unit, such as `1000.0 KB`.
+                        if (size * 10.0).round() / 10.0 < base {
+                            break;
+                        }
+                        size /= base;
+                        unit = next;
+                    }
+                    format!("{:.1} {}", size, unit)
+                }
+            },
+            Err(_) => variable,
+        },
+        Filter::Floor => format_number(parse_number(&variable, ",").unwrap_or_default().floor()),
+        Filter::Max { value } => match parse_number(&variable, ",") {
+            Some(number) if !number.is_nan() => format_number(number.min(*value)),
+            _ => variable,
+        },
+        Filter::Min { value } => match parse_number(&variable, ",") {
+            Some(number) if !number.is_nan() => format_number(number.max(*value)),
+            _ => variable,
+        },
+        Filter::Ordinal => match variable.trim().parse::<i64>() {
+            Ok(number) => {
+                let suffix = match (number.unsigned_abs() % 10, number.unsigned_abs() % 100) {
+                    (_, 11..=13) => "th",
+                    (1, _) => "st",
+                    (2, _) => "nd",
+                    (3, _) => "rd",
+                    _ => "th",
+                };
+                format!("{}{}", number, suffix)
+            },
+            Err(_) => variable,
+        },
+        Filter::Round { precision, mode } => {
+            let value = parse_number(&variable, ",").unwrap_or_default();
+            let round = |value: f64| match mode {
+                RoundingMode::HalfUp => value.round(),
+                RoundingMode::HalfDown if value.fract().abs() == 0.5 => value.trunc(),
+                RoundingMode::HalfDown => value.round(),
+                RoundingMode::HalfEven => value.round_ties_even(),
+            };
+
+            if *precision < 0 {
+                // Dividing and multiplying by a whole power of ten avoids the
+                // error introduced by fractions such as `0.01`.
+                let scale = 10_f64.powi(-(*precision as i32));
+                format_number(round(value.div(scale)).mul(scale))
+            } else {
+                // Move the decimal place `precision` places to the right,
+                // round away the remaining decimals, and move it back.
+                let scale = 10_f64.powi(*precision as i32);
+                format_number(round(value.mul(scale)).div(scale))
+            }
+        },
+        Filter::Unformat { separator } => match parse_number(&variable, separator) {
+            Some(number) => format_number(number),
+            None => variable,
+        },
+
+        // String filters.
+        Filter::AfterMore { marker } => match variable.split_once(marker.as_str()) {
+            Some((_, after)) => after.trim_start().to_string(),
+            None => String::new(),
+        },
+        Filter::Base64Encode => BASE64_STANDARD.encode(variable),
+        Filter::Base64Decode => BASE64_STANDARD
+            .decode(&variable)
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .unwrap_or(variable),
+        Filter::BeforeMore { marker } => match variable.split_once(marker.as_str()) {
+            Some((before, _)) => before.trim_end().to_string(),
+            None => variable,
+        },
+        // End the section just before the `>`, and start a new one for it.
+        Filter::Cdata => format!("<![CDATA[{}]]>", variable.replace("]]>", "]]]]><![CDATA[>")),
+        Filter::CollapseWhitespace => variable.split_whitespace().collect::<Vec<&str>>().join(" "),
+        Filter::Count { needle } => match needle.is_empty() {
+            true => "0".to_string(),
+            false => variable.matches(needle.as_str()).count().to_string(),
+        },
+        Filter::Dedent => {
+            let indent = variable.lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| &line[..line.len() - line.trim_start().len()])
+                .reduce(|common, indent| {
+                    let length = common.char_indices()
+                        .zip(indent.chars())
+                        .take_while(|((_, a), b)| a == b)
+                        .last()
+                        .map_or(0, |((index, c), _)| index + c.len_utf8());
+                    &common[..length]
+                })
+                .unwrap_or_default();
+
+            variable
+                .split_inclusive('\n')
+                .map(|line| {
+                    let content = line.trim_end_matches(['\r', '\n']);
+                    // Keep the line ending, whether it is `\n` or `\r\n`.
+                    let ending = &line[content.len()..];
+                    match content.trim().is_empty() {
+                        true => ending.to_string(),
+                        false => content[indent.len()..].to_string() + ending,
+                    }
+                })
+                .collect()
+        },
+        Filter::EscapeXml => escape_xml(&variable),
+        // Without the other variables, there is nothing to fall back to.
+        Filter::Fallback { .. } => variable,
+        Filter::First { separator } => variable
+            .split(separator.as_str())
+            .map(str::trim)
+            .find(|item| !item.is_empty())
             .unwrap_or_default()
-            // Be default, Rust rounds away all decimals.
-            // So we want to move the decimal places `precision` places to the
-            // left.
-            .mul(10_f64.powi((*precision as u32) as i32))
-            // Now round, removing all decimal places.
-            .round()
-            // Now move the decimal place back.
-            .div(10_f64.powi((*precision as u32) as i32))
             .to_string(),
+        Filter::FirstParagraph => variable
+            .lines()
+            .skip_while(|line| line.trim().is_empty())
+            .take_while(|line| !line.trim().is_empty())
+            .collect::<Vec<&str>>()
+            .join("\n"),
+        Filter::Format { template } => {
+            let mut output = String::with_capacity(template.len() + variable.len());
+            let mut replaced = false;
+            let mut rest = template.as_str();
+            while let Some(c) = rest.chars().next() {
+                if rest.starts_with("{{") || rest.starts_with("}}") {
+                    output.push(c);
+                    rest = &rest[2..];
+                } else if !replaced && rest.starts_with("{}") {
+                    output.push_str(&variable);
+                    replaced = true;
+                    rest = &rest[2..];
+                } else {
+                    output.push(c);
+                    rest = &rest[c.len_utf8()..];
+                }
+            }
+            if !replaced {
+                output.push_str(&variable);
+            }
+            output
+        },
+        Filter::Highlight { term, tag } => {
+            let regex = RegexBuilder::new(&regex::escape(term)).case_insensitive(true).build();
+            match regex {
+                Ok(regex) if !term.is_empty() => {
+                    // The tag is placed in the output as it is, so it must be
+                    // a name, rather than something that can't be closed.
+                    let tag = match !tag.is_empty() && tag.chars().all(is_filter_name) {
+                        true => tag.as_str(),
+                        false => "mark",
+                    };
+                    let highlight = |text: &str| regex
+                        .replace_all(text, |captures: &regex::Captures| format!("<{}>{}</{}>", tag, &captures[0], tag))
+                        .to_string();
 
-        // String filters.
-        Filter::Markdown  => {
+                    // Only highlight the text between HTML tags.
+                    static HTML_TAG: OnceLock<Regex> = OnceLock::new();
+                    let html_tag = HTML_TAG.get_or_init(|| Regex::new(r"<[^>]*>").expect("to be a valid pattern"));
+                    let mut output = String::with_capacity(variable.len());
+                    let mut last = 0;
+                    for html in html_tag.find_iter(&variable) {
+                        output.push_str(&highlight(&variable[last..html.start()]));
+                        output.push_str(html.as_str());
+                        last = html.end();
+                    }
+                    output.push_str(&highlight(&variable[last..]));
+                    output
+                },
+                _ => variable,
+            }
+        },
+        Filter::Indent { spaces, first } => {
+            let indent = " ".repeat(*spaces as usize);
+            variable
+                .split('\n')
+                .enumerate()
+                .map(|(index, line)| match (index > 0 || *first) && !line.trim().is_empty() {
+                    true => format!("{}{}", indent, line),
+                    false => line.to_string(),
+                })
+                .collect::<Vec<String>>()
+                .join("\n")
+        },
+        Filter::JsonEscape => variable.chars().fold(String::with_capacity(variable.len()), |mut escaped, c| {
+            match c {
+                '"' => escaped.push_str("\\\""),
+                '\\' => escaped.push_str("\\\\"),
+                '\n' => escaped.push_str("\\n"),
+                '\r' => escaped.push_str("\\r"),
+                '\t' => escaped.push_str("\\t"),
+                '\u{08}' => escaped.push_str("\\b"),
+                '\u{0C}' => escaped.push_str("\\f"),
+                c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+                c => escaped.push(c),
+            }
+            escaped
+        }),
+        Filter::Join { separator } => variable.lines().collect::<Vec<&str>>().join(separator),
+        Filter::Last { separator } => variable
+            .split(separator.as_str())
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .last()
+            .unwrap_or_default()
+            .to_string(),
+        Filter::Markdown { allow_html, gfm, anchors, highlight } => {
+            let (parse, compile) = match gfm {
+                true => (markdown::ParseOptions::gfm(), markdown::CompileOptions::gfm()),
+                false => (markdown::ParseOptions::default(), markdown::CompileOptions::default()),
+            };
             markdown::to_html_with_options(&variable, &markdown::Options {
+                parse,
                 compile: markdown::CompileOptions {
-                    allow_dangerous_html: true,
+                    allow_dangerous_html: *allow_html,
                     allow_dangerous_protocol: false,
-                    ..Default::default()
+                    ..compile
                 },
-                ..Default::default()
-            }).unwrap_or_default()
+            })
+            .map(|html| if *anchors { add_heading_ids(&html) } else { html })
+            .map(|html| if *highlight { highlight_code_blocks(&html) } else { html })
+            .unwrap_or_default()
+        },
+        // As with `Replace`, there is nothing to find, rather than an empty
+        // match between every character.
+        Filter::RegexReplace { pattern, .. } if pattern.is_empty() => variable,
+        Filter::RegexReplace { pattern, replacement, limit, ignore_case } => match cached_regex(pattern, *ignore_case) {
+            Some(regex) => match limit {
+                // A limit of 0 means no replacements, rather than all of them.
+                Some(0) => variable,
+                Some(limit) => regex.replacen(&variable, *limit as usize, replacement.as_str()).to_string(),
+                None => regex.replace_all(&variable, replacement.as_str()).to_string(),
+            },
+            None => variable,
         },
-        Filter::Replace { find, replacement, limit } => {
-            if limit.is_none() {
-                variable.replace(find, replacement)
+        Filter::Remove { text, limit } => render_filter(variable, &Filter::Replace {
+            find: text.to_string(),
+            replacement: String::new(),
+            limit: *limit,
+            ignore_case: false,
+        }),
+        Filter::Replace { find, replacement, limit, ignore_case } => {
+            // There is nothing to find, so there is nothing to replace.
+            if find.is_empty() {
+                variable
+            } else if *ignore_case {
+                // Escape the find, so that it is matched literally, and don't
+                // expand any `$` in the replacement.
+                let regex = RegexBuilder::new(&regex::escape(find)).case_insensitive(true).build();
+                match (regex, limit) {
+                    (Ok(_), Some(0)) | (Err(_), _) => variable,
+                    (Ok(regex), Some(limit)) => regex.replacen(&variable, *limit as usize, NoExpand(replacement)).to_string(),
+                    (Ok(regex), None) => regex.replace_all(&variable, NoExpand(replacement)).to_string(),
+                }
             } else {
-                // Subtract 1 to account for the final iteration.
-                let segments = variable.split(find).count() - 1;
+                match limit {
+                    // Replace exactly `limit` matches from the left, leaving
+                    // the remainder of the string untouched.
+                    Some(limit) => variable.replacen(find.as_str(), replacement, *limit as usize),
+                    None => variable.replace(find.as_str(), replacement),
+                }
+            }
+        },
+        Filter::Reverse => reverse_text(&variable),
+        Filter::Slice { start, end } => variable
+            .chars()
+            .skip(*start)
+            .take(end.unwrap_or(usize::MAX).saturating_sub(*start))
+            .collect(),
+        Filter::Spaceless => {
+            static BETWEEN_TAGS: OnceLock<Regex> = OnceLock::new();
+            let between_tags = BETWEEN_TAGS.get_or_init(|| Regex::new(r">\s+<").expect("to be a valid pattern"));
+            between_tags.replace_all(&variable, "><").to_string()
+        },
+        Filter::Split { separator } => variable
+            .split(separator.as_str())
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .collect::<Vec<&str>>()
+            .join("\n"),
+        Filter::TrimLines => variable
+            .split_inclusive('\n')
+            .map(|line| {
+                let content = line.trim_end_matches(['\r', '\n']);
+                // Keep the line ending, whether it is `\n` or `\r\n`.
+                content.trim_end().to_string() + &line[content.len()..]
+            })
+            .collect(),
+        Filter::Truncate { characters, trail, safe: true } => {
+            let mut end = (*characters as usize).min(variable.len());
+            while !variable.is_char_boundary(end) {
+                end -= 1;
+            }
 
-                variable
-                .split(find)
-                .enumerate()
-                .map(|(count, part)| {
-                    // We can safely unwrap, because `limit.is_some()`.
-                    if (count as u8) < limit.unwrap() {
-                        format!("{}{}", part, replacement)
-                    } else {
-                        format!("{}{}", part, if count < segments { find } else { "" })
-                    }
-                })
-                .collect::<Vec<String>>()
-                .join("")
+            // Cut before a tag that hasn't been closed yet.
+            let before = &variable[..end];
+            if let Some(open) = before.rfind('<') {
+                if before.rfind('>').is_none_or(|close| close < open) {
+                    end = open;
+                }
+            }
+
+            // Cut before an entity whose `;` is at or after the cut.
+            if let Some(amp) = variable[..end].rfind('&') {
+                let is_entity = variable[amp + 1..].find(';').is_some_and(|semicolon| {
+                    let name = &variable[amp + 1..amp + 1 + semicolon];
+                    !name.is_empty()
+                        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '#')
+                        && amp + 1 + semicolon >= end
+                });
+                if is_entity {
+                    end = amp;
+                }
             }
+
+            let mut new_variable = variable[..end].to_string();
+            if variable.len() > *characters as usize {
+                new_variable.push_str(trail);
+            }
+            new_variable
         },
-        Filter::Reverse => variable.chars().rev().collect(),
-        Filter::Truncate { characters, trail } => {
+        Filter::Truncate { characters, trail, .. } => {
             let mut new_variable = variable.to_string();
             new_variable.truncate(*characters as usize);
             // Now truncate and append the trail.
@@ -1621,6 +5891,28 @@ pub fn render_filter(variable: String, filter: &Filter) -> String {
             }
             new_variable
         },
+        Filter::TruncateMiddle { characters, trail } => {
+            let length = variable.chars().count();
+            if length <= *characters as usize {
+                return variable;
+            }
+
+            // The start gets the extra character when it can't be split evenly.
+            let keep = (*characters as usize).saturating_sub(trail.chars().count());
+            let start = variable.chars().take(keep.div_ceil(2)).collect::<String>();
+            let end = variable.chars().skip(length - keep / 2).collect::<String>();
+            format!("{}{}{}", start, trail, end)
+        },
+        Filter::TimeAgo { from } => {
+            let now = match from {
+                Some(from) => parse_datetime(from),
+                None => Some(Utc::now().timestamp()),
+            };
+            match (parse_datetime(&variable), now) {
+                (Some(then), Some(now)) => describe_time_ago(now - then),
+                _ => variable,
+            }
+        },
         Filter::Text { case } => {
             let separators = &[' ', ',', '!', '-', '_'];
             match case {
@@ -1689,5 +5981,93 @@ pub fn render_filter(variable: String, filter: &Filter) -> String {
                 }),
             }
         },
+        Filter::Typography => smarten_typography(&variable),
+        Filter::UrlEncode => variable
+            .bytes()
+            .map(|byte| match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (byte as char).to_string(),
+                _ => format!("%{:02X}", byte),
+            })
+            .collect::<String>(),
+        Filter::UrlDecode => {
+            let bytes = variable.as_bytes();
+            let mut decoded = Vec::with_capacity(bytes.len());
+            let mut index = 0;
+            while index < bytes.len() {
+                let hex = bytes.get(index + 1..index + 3)
+                    .and_then(|hex| std::str::from_utf8(hex).ok())
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+                match (bytes[index], hex) {
+                    (b'%', Some(byte)) => {
+                        decoded.push(byte);
+                        index += 3;
+                    },
+                    (byte, _) => {
+                        decoded.push(byte);
+                        index += 1;
+                    },
+                }
+            }
+            String::from_utf8_lossy(&decoded).to_string()
+        },
+        Filter::UrlJoin { base } => {
+            // A scheme is only at the start of the value, such as `https:` or
+            // `mailto:`, rather than anywhere in it, as in `?next=https://`.
+            let scheme = variable.split_once(':').is_some_and(|(scheme, _)| {
+                scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                    && scheme.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '.' | '-'))
+            });
+            if base.is_empty() || scheme || variable.starts_with("//") {
+                variable
+            } else if variable.starts_with(['?', '#']) {
+                format!("{}{}", base, variable)
+            } else {
+                format!("{}/{}", base.trim_end_matches('/'), variable.trim_start_matches('/'))
+            }
+        },
+        Filter::Wrap { before, after } => format!("{}{}{}", before, variable, after),
+        Filter::WrapAt { width, break_long_words } => {
+            if *width == 0 {
+                return variable;
+            }
+            let width = *width as usize;
+
+            variable
+                .split('\n')
+                .map(|line| {
+                    // Keep a `\r\n` line ending on every wrapped line.
+                    let (line, ending) = line.strip_suffix('\r').map_or((line, ""), |line| (line, "\r"));
+
+                    let mut lines: Vec<String> = Vec::new();
+                    let mut current = String::new();
+                    for word in line.split(' ').filter(|word| !word.is_empty()) {
+                        let mut word = word.to_string();
+                        let current_length = current.chars().count();
+                        if current_length > 0 && current_length + 1 + word.chars().count() <= width {
+                            current.push(' ');
+                            current.push_str(&word);
+                            continue;
+                        }
+                        if current_length > 0 {
+                            lines.push(std::mem::take(&mut current));
+                        }
+
+                        while *break_long_words && word.chars().count() > width {
+                            let split = word.char_indices().nth(width).map_or(word.len(), |(index, _)| index);
+                            let rest = word.split_off(split);
+                            lines.push(word);
+                            word = rest;
+                        }
+                        current = word;
+                    }
+                    if !current.is_empty() || lines.is_empty() {
+                        lines.push(current);
+                    }
+
+                    lines.join(&format!("{}\n", ending)) + ending
+                })
+                .collect::<Vec<String>>()
+                .join("\n")
+        },
     }
 }
@@ -1,9 +1,18 @@
-use blogs_md_easy::{create_variables, parse_meta_section, parse_placeholder_locations, render_filter, replace_substring, Placeholder, Span};
+use blogs_md_easy::{create_variables, escape_html, find_placeholder_error, format_parse_error, minify_html, parse_conditional_locations, parse_include_locations, parse_loop_locations, parse_meta_after_title, parse_meta_line, parse_meta_section, parse_placeholder_locations, parse_placeholder_with_unknown_arguments, parse_title, reading_time, render_filter_with_variables, replace_substring, strip_html, Filter, Meta, FILTERS, Placeholder, Selection, Span};
 use clap::Parser;
-use std::{collections::HashMap, error::Error, ffi::OsStr, fs, path::PathBuf};
+use nom::{character::complete::multispace0, multi::many0, sequence::preceded};
+use notify::RecursiveMode;
+use notify_debouncer_mini::new_debouncer;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{collections::{HashMap, HashSet}, error::Error, ffi::OsStr, fs, io::{self, Read, Write}, path::{Component, Path, PathBuf}, sync::{atomic::{AtomicUsize, Ordering}, mpsc}, time::{Duration, Instant, SystemTime, UNIX_EPOCH}};
 
 ////////////////////////////////////////////////////////////////////////////////
 // Structs and types
+/// The reading speed used for the `reading_time` variable, in words per
+/// minute.
+const DEFAULT_WORDS_PER_MINUTE: u32 = 200;
+
 /// A list of the possible features that can be allowed.
 #[derive(Debug, PartialEq, Eq)]
 enum AllowList {
@@ -16,27 +25,265 @@ enum AllowList {
     UnusedVariables,
 }
 
+/// Each Markdown's path, paired with its contents.
+type Markdowns = Vec<(PathBuf, String)>;
+
+/// The result of rendering a single Markdown into a template.
+#[derive(Debug)]
+struct Rendered {
+    /// The populated template.
+    html_doc: String,
+    /// Every variable that was available to the template, sorted by name.
+    variables: Vec<String>,
+    /// Variables that were declared, but not used by any placeholder, sorted by
+    /// name.
+    unused_variables: Vec<String>,
+    /// Warnings to be reported once rendering has finished.
+    warnings: Vec<String>,
+    /// The Markdown's body, without the meta section.
+    content: String,
+}
+
+/// The settings that apply when rendering every Markdown.
+#[derive(Debug)]
+struct RenderOptions {
+    /// Meta values shared by every Markdown, which the Markdown's own values
+    /// take precedence over.
+    meta_defaults: Vec<Meta>,
+    allow_list: Vec<AllowList>,
+    /// Variables that every Markdown must have a value for.
+    required: Vec<String>,
+    /// The heading level that the title is parsed from.
+    title_level: u8,
+    /// Whether the title is HTML escaped, so that it is safe to use as text.
+    escape_title: bool,
+    /// The name of the variable that holds the Markdown's body.
+    body_name: String,
+    /// The reading speed that the `reading_time` variable is estimated with.
+    words_per_minute: u32,
+    /// Whether the body is also available, unrendered, with a `_raw` suffix.
+    raw_content: bool,
+    /// Whether the title's heading is removed from the body, so that it isn't
+    /// rendered twice.
+    strip_title_from_content: bool,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            meta_defaults: Vec::new(),
+            allow_list: Vec::new(),
+            required: Vec::new(),
+            title_level: 1,
+            escape_title: false,
+            body_name: "content".to_string(),
+            words_per_minute: DEFAULT_WORDS_PER_MINUTE,
+            raw_content: false,
+            strip_title_from_content: false,
+        }
+    }
+}
+
+/// The result of rendering a single Markdown into a template, which is only
+/// written and reported once every Markdown has been rendered.
+struct Outcome {
+    warnings: Vec<String>,
+    /// Printed to stdout, which is either the output itself when piping, or a
+    /// report of what was done.
+    report: Option<String>,
+    /// `None` when the Markdown was skipped, and has no previous entry.
+    entry: Option<ManifestEntry>,
+    /// Whether the Markdown was rendered, rather than skipped.
+    rendered: bool,
+    /// The paths to write, along with their contents.
+    files: Vec<(PathBuf, String)>,
+}
+
+/// A record of a single rendered file, as written to the `--manifest`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct ManifestEntry {
+    markdown: PathBuf,
+    template: PathBuf,
+    /// `None` when the output was written to stdout.
+    output: Option<PathBuf>,
+    bytes: usize,
+    /// The unique placeholder names, in the order they appear in the template.
+    placeholders: Vec<String>,
+    variables: Vec<String>,
+    unused_variables: Vec<String>,
+    /// How long the Markdown took to render, which isn't written to the manifest.
+    #[serde(skip)]
+    elapsed: Duration,
+}
+
 #[derive(Debug, Parser)]
 #[command(version, about, long_about = None)]
 struct Cli {
-    /// HTML template that the Markdowns will populate.
+    /// HTML templates that the Markdowns will populate, or glob patterns to
+    /// match them.
     #[arg(short, long, required = true, alias = "template", value_name = "FILES", num_args = 1..)]
     templates: Vec<PathBuf>,
 
     // num_args is required so that we don't have to specify the option before
     // each file...
     // `-m file.md file2.md`    rather than    `-m file.md -m file2.md`
-    /// List of Markdown files ending in .md.
-    #[arg(short, long, required = true, value_name = "FILES", num_args = 1..)]
+    /// List of Markdown files, or glob patterns to match them.
+    #[arg(short, long, required_unless_present = "stdin", value_name = "FILES", num_args = 1..)]
     markdowns: Vec<PathBuf>,
 
+    /// Extensions of the Markdown files to render, any other files are skipped.
+    #[arg(long, value_name = "EXTS", num_args = 1.., default_values = ["md", "markdown", "mdown", "mkd"])]
+    markdown_exts: Vec<String>,
+
     /// Output directory, defaults to the Markdown's directory.
     #[arg(short, long, value_name = "DIR")]
     output_dir: Option<PathBuf>,
 
+    /// Keep the Markdown's directories when writing to the output directory.
+    #[arg(short, long, requires = "output_dir")]
+    preserve_structure: bool,
+
+    /// Directory that preserved paths are relative to, implies
+    /// `--preserve-structure`.
+    #[arg(short, long, value_name = "DIR", requires = "output_dir")]
+    base_dir: Option<PathBuf>,
+
+    /// Extension of the output files, defaults to the template's extension.
+    #[arg(short, long, value_name = "EXT")]
+    ext: Option<String>,
+
+    /// Pattern for the output file names, using the tokens `{template}`,
+    /// `{markdown}`, and `{ext}`.
+    #[arg(short, long, value_name = "PATTERN")]
+    name_pattern: Option<String>,
+
+    /// Write each template's outputs into a directory named after the
+    /// template, rather than prefixing the file names, when there are
+    /// multiple templates.
+    #[arg(long)]
+    template_subdirs: bool,
+
+    /// File of meta values shared by every Markdown, which the Markdown's own
+    /// meta section takes precedence over.
+    #[arg(long, value_name = "FILE")]
+    meta_file: Option<PathBuf>,
+
     /// Define an allow list for features.
     #[arg(short, long, value_name = "RULES", num_args = 1..)]
     allow: Vec<String>,
+
+    /// Variables that every Markdown must have a value for, such as `author`.
+    #[arg(short, long, value_name = "KEYS", num_args = 1..)]
+    require: Vec<String>,
+
+    /// Heading level that the title is parsed from, such as 2 for `##` or `<h2>`.
+    #[arg(long, value_name = "LEVEL", default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..=6))]
+    title_level: u8,
+
+    /// HTML escape the title, so that characters such as `&` and `<` are safe.
+    #[arg(long)]
+    escape_title: bool,
+
+    /// Name of the variable that holds the Markdown's body, instead of `content`.
+    #[arg(long, value_name = "NAME", default_value = "content")]
+    body_name: String,
+
+    /// Reading speed that the `reading_time` variable is estimated with.
+    #[arg(long, value_name = "WPM", default_value_t = DEFAULT_WORDS_PER_MINUTE, value_parser = clap::value_parser!(u32).range(1..))]
+    words_per_minute: u32,
+
+    /// Add a variable such as `content_raw`, which holds the Markdown's body without rendering it.
+    #[arg(long)]
+    raw_content: bool,
+
+    /// Remove the title's heading from the body, so that it isn't rendered twice.
+    #[arg(long)]
+    strip_title: bool,
+
+    /// Exit with an error if any warnings were reported, once every file is written.
+    #[arg(long)]
+    fail_on_warning: bool,
+
+    /// Report a Markdown that fails to render and carry on with the rest,
+    /// exiting with an error once every other file is written.
+    #[arg(long)]
+    continue_on_error: bool,
+
+    /// Print a line for each rendered file, and a summary once finished.
+    #[arg(short, long)]
+    verbose: bool,
+
+    /// Keep running, and re-render whenever a template or Markdown changes.
+    #[arg(short, long, conflicts_with = "stdin")]
+    watch: bool,
+
+    /// Read a single Markdown from stdin, and write the output to stdout.
+    #[arg(long, conflicts_with = "markdowns")]
+    stdin: bool,
+
+    /// Write the output to stdout, rather than to files.
+    #[arg(long)]
+    stdout: bool,
+
+    /// Minify the output, collapsing whitespace outside of `<pre>` and `<code>`.
+    #[arg(long)]
+    minify: bool,
+
+    /// Leave the output as rendered, without adding newlines before headings.
+    #[arg(long)]
+    no_pretty: bool,
+
+    /// Also write a `.txt` file alongside each output, with the Markdown's
+    /// content as plain text.
+    #[arg(long, conflicts_with_all = ["stdin", "stdout"])]
+    also_text: bool,
+
+    /// Write a JSON manifest describing each rendered file.
+    #[arg(long, value_name = "FILE")]
+    manifest: Option<PathBuf>,
+
+    /// Render everything, but only report the files that would be written.
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Render everything without writing anything, and exit with an error if
+    /// any Markdown fails to render or any warning is reported.
+    #[arg(long, conflicts_with = "watch")]
+    check: bool,
+
+    /// Skip any Markdown whose output is newer than the Markdown, its template,
+    /// and the meta file.
+    #[arg(long)]
+    incremental: bool,
+
+    /// Maximum number of Markdowns to render at once, defaults to the number of
+    /// CPUs.
+    #[arg(short, long, value_name = "N")]
+    jobs: Option<usize>,
+
+    /// Print every filter that can be used in a template, with its arguments,
+    /// then exit.
+    #[arg(long, exclusive = true)]
+    list_filters: bool,
+}
+
+/// List every filter, along with its aliases, arguments, and their defaults.
+fn get_filter_list() -> String {
+    FILTERS.iter().map(|info| {
+        let mut entry = info.name.to_string();
+        if !info.aliases.is_empty() {
+            entry.push_str(&format!(" ({})", info.aliases.join(", ")));
+        }
+        entry.push_str(&format!(" - {}\n", info.description));
+
+        for (argument, default) in info.arguments {
+            match default.is_empty() {
+                true => entry.push_str(&format!("    * {}\n", argument)),
+                false => entry.push_str(&format!("    * {} - defaults to `{}`\n", argument, default)),
+            }
+        }
+        entry
+    }).collect()
 }
 
 /// Converts a Vector of Strings, into a Vector of `AllowList`.  \
@@ -51,163 +298,1030 @@ fn get_allow_list(allow_list: Vec<String>) -> Vec<AllowList>{
     }).collect()
 }
 
+/// Expand any glob patterns, such as `posts/**/*.md`, into the paths that they
+/// match.  \
+/// Paths that exist, or don't contain a pattern, are returned as they are.
+fn expand_globs(paths: Vec<PathBuf>) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut expanded = Vec::new();
+
+    for path in paths {
+        let pattern = path.to_string_lossy();
+        if path.exists() || !pattern.contains(['*', '?', '[']) {
+            expanded.push(path);
+            continue;
+        }
+
+        let matches = glob::glob(&pattern)?.filter_map(Result::ok).collect::<Vec<PathBuf>>();
+        if matches.is_empty() {
+            Err(format!("The pattern '{}' did not match any files.", pattern))?;
+        }
+        expanded.extend(matches);
+    }
+
+    Ok(expanded)
+}
+
 /// Take a Vector of paths, make sure they're Markdown files, then read the
 /// contents.
-fn get_markdowns(paths: Vec<PathBuf>) -> Vec<(PathBuf, String)> {
-    paths
-    .into_iter()
-    // Ensure the file exists and is a `.md` file.
-    .filter(|file| file.exists() && file.extension().unwrap_or_default() == "md")
-    // Now read the contents into a String and convert to tuple.
-    .filter_map(|path| fs::read_to_string(&path).ok().map(|content| (path, content)))
-    .collect()
+///
+/// Alongside the Markdowns, a warning is returned for each path that was given
+/// explicitly but skipped. Files matched by a glob pattern that aren't Markdown
+/// are skipped quietly, as the pattern is expected to match other files too.
+fn get_markdowns(paths: Vec<PathBuf>, extensions: &[String]) -> Result<(Markdowns, Vec<String>), Box<dyn Error>> {
+    let mut markdowns = Vec::new();
+    let mut skipped = Vec::new();
+
+    for path in paths {
+        let is_pattern = !path.exists() && path.to_string_lossy().contains(['*', '?', '[']);
+
+        for file in expand_globs(vec![path])? {
+            let is_markdown = extensions.iter().any(|ext| {
+                file.extension().unwrap_or_default().eq_ignore_ascii_case(ext.trim_start_matches('.'))
+            });
+
+            if !file.exists() {
+                skipped.push(format!("Warning: Skipped '{}', as it does not exist.", file.to_string_lossy()));
+            } else if !is_markdown {
+                if !is_pattern {
+                    skipped.push(format!(
+                        "Warning: Skipped '{}', as it does not have a Markdown extension ({}).",
+                        file.to_string_lossy(),
+                        extensions.join(", ")
+                    ));
+                }
+            } else {
+                match fs::read_to_string(&file) {
+                    Ok(content) => markdowns.push((file, content)),
+                    Err(e) => skipped.push(format!("Warning: Skipped '{}', as it could not be read: {}", file.to_string_lossy(), e)),
+                }
+            }
+        }
+    }
+
+    Ok((markdowns, skipped))
+}
+
+/// Read the shared meta values from a file. The file may either contain a
+/// `meta` section, as a Markdown would, or just the key-value lines.
+fn get_meta_defaults(path: &Path) -> Result<Vec<Meta>, Box<dyn Error>> {
+    let contents = fs::read_to_string(path)
+        .map_err(|_| format!("The meta file '{}' could not be read.", path.to_string_lossy()))?;
+    let input = Span::new(contents.trim_start());
+
+    if let Ok((_, meta_values)) = parse_meta_section(input) {
+        return Ok(meta_values);
+    }
+
+    // A line that isn't a key-value or a comment would otherwise end the list
+    // early, and silently drop every value after it.
+    let input = Span::new(&contents);
+    let parse_error = |_| format!("The meta file '{}' could not be parsed.", path.to_string_lossy());
+    let (rest, meta_values) = preceded(multispace0, many0(parse_meta_line))(input).map_err(parse_error)?;
+    if !rest.fragment().trim().is_empty() {
+        Err(format!(
+            "The meta file '{}' could not be parsed. {}",
+            path.to_string_lossy(),
+            format_parse_error(&contents, rest.location_offset(), "a `key = value` line or a comment")
+        ))?;
+    }
+    Ok(meta_values.into_iter().flatten().collect())
+}
+
+/// Separate the meta section from the rest of the Markdown.
+///
+/// The section may either come first, or directly after the title, in which
+/// case the title is kept at the start of the Markdown.
+fn split_meta_section(markdown: &str, title_level: u8) -> (String, Vec<Meta>) {
+    let input = Span::new(markdown);
+    if let Ok((markdown, meta_values)) = parse_meta_section(input) {
+        return (markdown.fragment().to_string(), meta_values);
+    }
+    if let Ok((markdown, (title, meta_values))) = parse_meta_after_title(input, title_level) {
+        return (format!("{}\n\n{}", title.fragment(), markdown.fragment()), meta_values);
+    }
+    (markdown.to_string(), Vec::new())
+}
+
+/// Get the template that a Markdown selected with its `template` meta key, from
+/// those given on the command line.
+///
+/// The value may be the template's file name, or any trailing part of its
+/// path, such as `post.html` or `templates/post.html`. When the Markdown
+/// doesn't select a template, `None` is returned and every template is used.
+fn get_selected_template<'a>(markdown_url: &Path, markdown: &str, options: &RenderOptions, templates: &'a [PathBuf]) -> Result<Option<&'a PathBuf>, Box<dyn Error>> {
+    let (_, meta_values) = split_meta_section(markdown, options.title_level);
+
+    // The Markdown's own value takes precedence over the shared default.
+    let Some(selected) = options.meta_defaults.iter().chain(&meta_values).rev().find(|meta| meta.key == "template") else {
+        return Ok(None);
+    };
+    let selected = selected.value.trim();
+
+    match templates.iter().find(|template| template.ends_with(selected)) {
+        Some(template) => Ok(Some(template)),
+        None => Err(format!(
+            "The template '{}' selected by markdown '{}' was not given with `--templates`.",
+            selected,
+            markdown_url.to_string_lossy()
+        ))?,
+    }
+}
+
+/// The deepest that includes can be nested, as a safeguard against runaway
+/// recursion.
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// Read a template, replacing each `{{ #include }}` with the contents of the
+/// file that it names.
+///
+/// Returns the template, along with the path of every file that was included.
+fn get_template(path: &Path) -> Result<(String, Vec<PathBuf>), Box<dyn Error>> {
+    let mut included = Vec::new();
+    let template = resolve_includes(path, &mut Vec::new(), &mut included)?;
+    Ok((template, included))
+}
+
+/// Read a file, and recursively replace each include with the contents of the
+/// file that it names, relative to the directory of the including file.
+///
+/// The `chain` holds each file that is currently being included, so that a
+/// file including itself can be reported rather than recursing forever.
+fn resolve_includes(path: &Path, chain: &mut Vec<PathBuf>, included: &mut Vec<PathBuf>) -> Result<String, Box<dyn Error>> {
+    let canonical = path.canonicalize()
+        .map_err(|_| format!("The template '{}' could not be found.", path.to_string_lossy()))?;
+    if chain.contains(&canonical) {
+        let cycle = chain.iter()
+            .chain([&canonical])
+            .map(|path| path.file_name().unwrap_or_default().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(" -> ");
+        Err(format!("The template '{}' includes itself: {}", path.to_string_lossy(), cycle))?;
+    }
+    if chain.len() > MAX_INCLUDE_DEPTH {
+        Err(format!("Includes are nested more than {} deep, at '{}'.", MAX_INCLUDE_DEPTH, path.to_string_lossy()))?;
+    }
+
+    let contents = fs::read_to_string(path)?;
+    let dir = path.parent().unwrap_or(Path::new(""));
+    let mut template = contents.clone();
+
+    chain.push(canonical);
+    for include in parse_include_locations(Span::new(&contents))? {
+        let include_path = dir.join(&include.path);
+        let partial = resolve_includes(&include_path, chain, included)?;
+        // Drop the final newline of the partial, so that the include directive
+        // can sit on its own line without adding a blank one.
+        let partial = partial.strip_suffix("\r\n").or(partial.strip_suffix('\n')).unwrap_or(&partial);
+        template = replace_substring(&template, include.selection.start.offset, include.selection.end.offset, partial);
+        included.push(include_path);
+    }
+    chain.pop();
+
+    Ok(template)
 }
 
 /// Locate all `Placeholder`s from the template.
 fn get_placeholders(template: Span) -> Result<Vec<Placeholder>, Box<dyn Error>> {
-    let mut placeholders = parse_placeholder_locations(template)?;
-    placeholders.sort_by(|a, b| b.selection.start.offset.cmp(&a.selection.start.offset));
-    Ok(placeholders)
+    // Malformed placeholders would otherwise be skipped without a word.
+    if let Some(error) = find_placeholder_error(template.fragment()) {
+        return Err(error.into());
+    }
+
+    // These are already in reverse order, ready to be replaced.
+    parse_placeholder_locations(template)
 }
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let cli = Cli::parse();
+/// Create a warning for each filter argument that a placeholder doesn't accept.
+///
+/// The `placeholders` are in reverse order, as from `get_placeholders`, so the
+/// warnings are reversed to follow the template. Each placeholder is parsed
+/// again from the `template`, to find the arguments.
+fn get_unknown_argument_warnings(template_path: &Path, template: Span, placeholders: &[Placeholder]) -> Vec<String> {
+    placeholders.iter().rev().flat_map(|placeholder| {
+        let text = &template.fragment()[placeholder.selection.start.offset..placeholder.selection.end.offset];
+        let unknown_arguments = parse_placeholder_with_unknown_arguments(Span::new(text))
+            .map(|(_, (_, unknown_arguments))| unknown_arguments)
+            .unwrap_or_default();
+        unknown_arguments.into_iter().map(|argument| format!(
+            "Warning: Unknown filter argument '{}' for '£{}' on line {} of '{}'.",
+            argument,
+            placeholder.name,
+            placeholder.selection.start.line,
+            template_path.to_string_lossy()
+        )).collect::<Vec<String>>()
+    }).collect()
+}
+
+/// Get the names of the variables used by every conditional and loop block in
+/// the template, including those that are nested.
+fn get_block_names(template: &str) -> Result<Vec<String>, Box<dyn Error>> {
+    let span = Span::new(template);
+    let mut names = Vec::new();
+    for conditional in parse_conditional_locations(span)? {
+        names.push(conditional.name);
+        names.extend(get_block_names(&template[conditional.body.start.offset..conditional.body.end.offset])?);
+    }
+    for block in parse_loop_locations(span)? {
+        names.push(block.name);
+        names.extend(get_block_names(&template[block.body.start.offset..block.body.end.offset])?);
+    }
+    Ok(names)
+}
+
+/// Render a template, replacing each placeholder with its variable, and each
+/// conditional and loop block with its rendered contents.
+///
+/// A conditional block is kept when its variable exists and isn't empty, or
+/// the opposite for an `#unless` block. A loop block is repeated for each item
+/// in its list, with the item variable shadowing any variable of the same name.
+fn render_template(template: &str, variables: &HashMap<String, String>, markdown_url: &Path) -> Result<String, Box<dyn Error>> {
+    let span = Span::new(template);
+    let conditionals = parse_conditional_locations(span)?;
+    let loops = parse_loop_locations(span)?;
+
+    // Anything within a block is rendered along with that block, so only the
+    // outermost blocks and placeholders are rendered here.
+    let blocks = conditionals.iter().map(|conditional| conditional.selection)
+        .chain(loops.iter().map(|block| block.selection))
+        .collect::<Vec<Selection>>();
+    let is_nested = |selection: &Selection| blocks.iter().any(|block| {
+        block != selection && block.start.offset <= selection.start.offset && selection.end.offset <= block.end.offset
+    });
+
+    let mut replacements: Vec<(Selection, String)> = Vec::new();
+    for conditional in conditionals.into_iter().filter(|conditional| !is_nested(&conditional.selection)) {
+        let has_value = variables.get(&conditional.name).is_some_and(|value| !value.trim().is_empty());
+        let body = match has_value != conditional.negated {
+            true => render_template(&template[conditional.body.start.offset..conditional.body.end.offset], variables, markdown_url)?,
+            false => String::new(),
+        };
+        replacements.push((conditional.selection, body));
+    }
+    for block in loops.into_iter().filter(|block| !is_nested(&block.selection)) {
+        let Some(list) = variables.get(&block.name) else {
+            let url = markdown_url.to_str().unwrap_or_default();
+            Err(format!("Missing variable '{}' in markdown '{}'.", &block.name, url))?
+        };
+        let list = block.filters.iter().fold(list.to_owned(), |list, filter| render_filter_with_variables(list, filter, variables));
+
+        let body = &template[block.body.start.offset..block.body.end.offset];
+        let mut scoped_variables = variables.clone();
+        let mut output = String::new();
+        for item in list.lines().map(str::trim).filter(|item| !item.is_empty()) {
+            scoped_variables.insert(block.item.clone(), item.to_string());
+            output.push_str(&render_template(body, &scoped_variables, markdown_url)?);
+        }
+        replacements.push((block.selection, output));
+    }
+    for placeholder in get_placeholders(span)?.into_iter().filter(|placeholder| !is_nested(&placeholder.selection)) {
+        // A missing variable is as good as empty, when there's a fallback.
+        let has_fallback = placeholder.filters.iter().any(|filter| matches!(filter, Filter::Fallback { .. }));
+        let variable = match variables.get(&placeholder.name) {
+            Some(variable) => variable.to_owned(),
+            None if has_fallback => String::new(),
+            None => {
+                let url = markdown_url.to_str().unwrap_or_default();
+                Err(format!("Missing variable '{}' in markdown '{}'.", &placeholder.name, url))?
+            },
+        };
+        let variable = placeholder.apply_with_variables(variable, variables);
+        replacements.push((placeholder.selection, variable));
+    }
+
+    // Replace from the end, so that the offsets of the earlier selections are
+    // unaffected.
+    replacements.sort_by_key(|(selection, _)| std::cmp::Reverse(selection.start.offset));
+    let mut html_doc = template.to_string();
+    for (selection, replacement) in replacements {
+        html_doc = replace_substring(&html_doc, selection.start.offset, selection.end.offset, &replacement);
+    }
+    Ok(html_doc)
+}
+
+/// The current time of day, in UTC, formatted as `HH:MM:SS`.
+fn timestamp() -> String {
+    let seconds = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs() % 86_400;
+    format!("{:02}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)
+}
+
+/// Get the path of the Markdown relative to the `base_dir`, so that it can be
+/// re-rooted under the output directory.
+fn get_relative_path(markdown_url: &Path, base_dir: Option<&Path>) -> PathBuf {
+    let relative = base_dir
+        .and_then(|base_dir| markdown_url.strip_prefix(base_dir).ok())
+        .unwrap_or(markdown_url);
+
+    // Drop any root or `..` components, so that the output can never escape
+    // the output directory.
+    relative.components()
+        .filter(|component| matches!(component, Component::Normal(_)))
+        .collect()
+}
+
+/// Get the path that the output of a Markdown and template will be written to.
+///
+/// When the Markdown is rendered into `multiple_templates`, the template's name
+/// is added to the output, so that each output has its own file. This is either
+/// a prefix of the file name, or with `--template-subdirs`, a directory.
+fn get_output_path(cli: &Cli, template_path: &Path, markdown_url: &Path, multiple_templates: bool) -> Result<PathBuf, Box<dyn Error>> {
+    // Get the template extension, because the user might be passing in
+    // something like an SVG. Unless the user has asked for another extension.
+    let ext = cli.ext.as_deref().map(|ext| ext.trim_start_matches('.'));
+    let template_ext = ext.map(OsStr::new).unwrap_or(template_path.extension().unwrap_or(OsStr::new("html")));
+
+    // Each template can have a directory of its own, which is named after it.
+    // This is at the root of the output directory, so that the Markdown's
+    // directories are preserved beneath it.
+    let template_dir = (multiple_templates && cli.template_subdirs)
+        .then(|| template_path.file_stem().unwrap_or_default());
+
+    // Get the output path where the `.md` is replaced with `.html`.
+    let mut output_path = match cli.output_dir.clone() {
+        Some(path) => {
+            let path = template_dir.map_or(path.clone(), |template_dir| path.join(template_dir));
+            if cli.preserve_structure || cli.base_dir.is_some() {
+                path.join(get_relative_path(markdown_url, cli.base_dir.as_deref()).with_extension(template_ext))
+            } else {
+                path.join(markdown_url.with_extension(template_ext).file_name().unwrap())
+            }
+        },
+        None => {
+            let path = markdown_url.with_extension(template_ext);
+            match (template_dir, path.file_name()) {
+                (Some(template_dir), Some(file_name)) => path.with_file_name(template_dir).join(file_name),
+                _ => path,
+            }
+        },
+    };
+
+    // A user provided pattern replaces the file name entirely.
+    if let Some(pattern) = &cli.name_pattern {
+        let file_name = pattern
+            .replace("{template}", template_path.file_stem().unwrap_or_default().to_str().unwrap_or_default())
+            .replace("{markdown}", markdown_url.file_stem().unwrap_or_default().to_str().unwrap_or_default())
+            .replace("{ext}", template_ext.to_str().unwrap_or_default());
 
-    let templates = cli.templates;
-    let allow_list = get_allow_list(cli.allow);
+        if file_name.trim().is_empty() || file_name.contains(['/', '\\']) {
+            Err(format!("The name pattern '{}' does not produce a valid file name.", pattern))?;
+        }
+
+        return Ok(output_path.with_file_name(file_name));
+    }
+
+    // If there are multiple templates, then add that to the output path
+    // to avoid overwriting issues.
+    if multiple_templates && !cli.template_subdirs {
+        output_path = output_path.with_file_name(format!(
+            "{}-{}",
+            &template_path.file_stem().unwrap_or_default().to_str().unwrap_or_default(),
+            output_path.file_stem().unwrap_or_default().to_str().unwrap_or_default()
+        )).with_extension(ext.unwrap_or("html"));
+    }
+
+    Ok(output_path)
+}
+
+/// Render a single Markdown document into the template.
+///
+/// The `markdown_url` is only used to identify the Markdown in any warnings or
+/// errors.
+fn render(template: Span, placeholders: &[Placeholder], markdown_url: &Path, markdown: &str, options: &RenderOptions) -> Result<Rendered, Box<dyn Error>> {
+    let RenderOptions { meta_defaults, allow_list, required, title_level, escape_title, body_name, words_per_minute, raw_content, strip_title_from_content } = options;
+    let mut warnings = Vec::new();
+
+    // Parse the meta values, and combine them with the title and content of
+    // the markdown file.
+    let (markdown, meta_values) = split_meta_section(markdown, *title_level);
+    let markdown = Span::new(&markdown);
+    let declared_keys = meta_values.iter().map(|meta| meta.key.clone()).collect::<Vec<String>>();
+
+    // Only the last value of a key is kept, so any earlier ones are likely a
+    // mistake.
+    let mut first_declared: HashMap<&str, &Meta> = HashMap::new();
+    for meta in &meta_values {
+        match first_declared.get(meta.key.as_str()) {
+            Some(first) => warnings.push(format!(
+                "Warning: Duplicate meta key '{}' in '{}', on lines {} and {}. The value on line {} is used.",
+                meta.key,
+                &markdown_url.to_string_lossy(),
+                first.selection.start.line,
+                meta.selection.start.line,
+                meta.selection.start.line
+            )),
+            None => {
+                first_declared.insert(&meta.key, meta);
+            },
+        }
+    }
+
+    // The defaults come first, so that the Markdown's own values take
+    // precedence when they are collected.
+    let meta_values = meta_defaults.iter().cloned().chain(meta_values).collect::<Vec<Meta>>();
+    let content_declared = meta_values.iter().any(|meta| meta.key == "content");
+    let reading_time_declared = meta_values.iter().any(|meta| meta.key == "reading_time");
+    // Variables used by a conditional or loop block, or as a fallback, are
+    // used, even if they are never output directly.
+    let block_names = get_block_names(template.fragment())?;
+    let fallback_keys = placeholders.iter()
+        .flat_map(|p| &p.filters)
+        .filter_map(|filter| match filter {
+            Filter::Fallback { key } => Some(key),
+            _ => None,
+        });
+    let placeholder_keys = placeholders.iter().map(|p| &p.name).chain(&block_names).chain(fallback_keys).collect::<Vec<&String>>();
+
+    let mut variables: HashMap<String, String> = create_variables(markdown, meta_values, *title_level)?;
+    // The title is still parsed from the heading, which is only then removed.
+    let body = if *strip_title_from_content {
+        parse_title(markdown, *title_level).map_or(markdown, |(body, _)| body)
+    } else {
+        markdown
+    };
+    if body_name != "content" {
+        // A `content` declared in the meta section is still a variable in its
+        // own right.
+        if !content_declared {
+            variables.remove("content");
+        }
+        variables.entry(body_name.to_owned()).or_insert_with(|| body.fragment().trim().to_string());
+    } else if !content_declared {
+        variables.insert("content".to_string(), body.fragment().trim().to_string());
+    }
+    // Only the body's own name is rendered as Markdown automatically, so the
+    // raw copy is left untouched.
+    if *raw_content {
+        variables.entry(format!("{}_raw", body_name)).or_insert_with(|| body.fragment().trim().to_string());
+    }
+    // The reading time is only estimated when it is used, from the words of
+    // the body after the title.
+    let uses_reading_time = placeholder_keys.iter().copied().chain(required).any(|key| key.as_str() == "reading_time");
+    if uses_reading_time && !reading_time_declared {
+        let words = parse_title(markdown, *title_level).map_or(markdown, |(body, _)| body);
+        variables.insert("reading_time".to_string(), reading_time(words.fragment(), *words_per_minute).to_string());
+    }
+    if *escape_title {
+        if let Some(title) = variables.get_mut("title") {
+            *title = escape_html(title);
+        }
+    }
+
+    // An empty value is as much of a mistake as a missing one.
+    let missing = required.iter()
+        .filter(|key| variables.get(*key).is_none_or(|value| value.trim().is_empty()))
+        .map(|key| format!("'{}'", key))
+        .collect::<Vec<String>>();
+    if !missing.is_empty() {
+        Err(format!(
+            "Missing required variable{} {} in markdown '{}'.",
+            if missing.len() == 1_usize { "" } else { "s" },
+            missing.join(", "),
+            markdown_url.to_string_lossy()
+        ))?;
+    }
+
+    // Sort the names, as the HashMap would otherwise report them in a random
+    // order.
+    // Shared defaults are not expected to be used by every template, so they
+    // are only reported when the Markdown declared them itself. The `template`
+    // key selects the template, so is used too.
+    let mut unused_variables = variables.keys()
+        .filter(|key| !placeholder_keys.contains(key) && key.as_str() != "template")
+        .filter(|key| declared_keys.contains(key) || !meta_defaults.iter().any(|meta| &&meta.key == key))
+        .cloned()
+        .collect::<Vec<String>>();
+    unused_variables.sort();
+
+    // Check for unused variables.
+    if !allow_list.contains(&AllowList::Unused) && !allow_list.contains(&AllowList::UnusedVariables) && !unused_variables.is_empty() {
+        warnings.push(format!(
+            "Warning: Unused variable{} in '{}': {}",
+            if unused_variables.len() == 1_usize { "" } else { "s" },
+            &markdown_url.to_string_lossy(),
+            unused_variables.join(", ")
+        ));
+    }
+
+    let html_doc = render_template(template.fragment(), &variables, markdown_url)?;
+    let content = variables.get(body_name).cloned().unwrap_or_default();
+
+    let mut variables = variables.into_keys().collect::<Vec<String>>();
+    variables.sort();
+
+    Ok(Rendered { html_doc, variables, unused_variables, warnings, content })
+}
+
+/// Check whether the output exists, and was modified no earlier than every
+/// input. An input that can't be read is assumed to have changed.
+fn is_up_to_date<'a>(output_path: &Path, inputs: impl IntoIterator<Item = &'a PathBuf>) -> bool {
+    let modified = |path: &Path| fs::metadata(path).and_then(|metadata| metadata.modified());
+    let Ok(output_modified) = modified(output_path) else {
+        return false;
+    };
+
+    inputs.into_iter().all(|input| modified(input).is_ok_and(|input_modified| input_modified <= output_modified))
+}
+
+/// Convert the Markdown's content into plain text, by rendering it as HTML and
+/// stripping the tags.
+///
+/// Each block, such as a paragraph or list, is followed by a blank line, and
+/// trailing whitespace is removed from each line.
+fn get_plain_text(content: &str) -> String {
+    let markdown = Filter::Markdown { allow_html: true, gfm: false, anchors: false, highlight: false };
+    let mut html = render_filter_with_variables(content.to_string(), &markdown, &HashMap::new());
+    for tag in ["</h1>", "</h2>", "</h3>", "</h4>", "</h5>", "</h6>", "</p>", "</ul>", "</ol>", "</pre>", "</blockquote>"] {
+        html = html.replace(tag, &format!("{tag}\n"));
+    }
+    let text = strip_html(&html);
+
+    let mut lines: Vec<&str> = Vec::new();
+    for line in text.lines().map(str::trim_end) {
+        if !(line.is_empty() && lines.last().is_none_or(|last| last.is_empty())) {
+            lines.push(line);
+        }
+    }
+    lines.join("\n").trim_end().to_string() + "\n"
+}
+
+/// Used to give each temporary file a unique name, as several can be written at
+/// once.
+static TEMPORARY_FILES: AtomicUsize = AtomicUsize::new(0);
+
+/// Write the contents to a temporary file alongside the path, then rename it
+/// over the path, so that anything reading the path never sees a partial file.
+fn write_atomically(path: &Path, contents: &str) -> io::Result<()> {
+    let temporary_path = path.with_file_name(format!(
+        ".{}.{}-{}.tmp",
+        path.file_name().unwrap_or_default().to_string_lossy(),
+        std::process::id(),
+        TEMPORARY_FILES.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    fs::write(&temporary_path, contents)
+        .and_then(|_| fs::rename(&temporary_path, path))
+        .inspect_err(|_| {
+            // Don't leave the temporary file behind.
+            let _ = fs::remove_file(&temporary_path);
+        })
+}
+
+/// Apply the final formatting to the rendered HTML, either minifying it, or
+/// making it easier to read.
+fn format_html(cli: &Cli, mut html_doc: String) -> String {
+    if cli.minify {
+        return minify_html(&html_doc);
+    }
+
+    if cli.no_pretty {
+        return html_doc;
+    }
+
+    // Add newlines before each heading element, because I'd like the HTML
+    // to be easy to read.
+    // Any newline that is already present is removed first, so that we don't
+    // accumulate blank lines.
+    for h in 2..=6 {
+        let h = format!("<h{h}>");
+        html_doc = html_doc.replace(&format!("\n{h}"), &h).replace(&h, &format!("\n{h}"));
+    };
+
+    html_doc
+}
+
+/// Describe a rendered file for `--verbose`, such as
+/// `posts/one.md -> posts/one.html (3 variables, 1.20ms)`.
+fn format_progress(entry: &ManifestEntry) -> String {
+    format!(
+        "{} -> {} ({} variable{}, {:.2?})",
+        entry.markdown.to_string_lossy(),
+        entry.output.as_ref().map_or("<stdout>".into(), |output| output.to_string_lossy()),
+        entry.variables.len(),
+        if entry.variables.len() == 1_usize { "" } else { "s" },
+        entry.elapsed
+    )
+}
+
+/// Render each Markdown into each template, and write the output files.
+fn build(cli: &Cli) -> Result<(), Box<dyn Error>> {
+    let started = Instant::now();
+    let templates = &expand_globs(cli.templates.clone())?;
+
+    // A single stream of output cannot be split across multiple templates.
+    if (cli.stdin || cli.stdout) && templates.len() > 1 {
+        Err("Only a single template can be used when writing to stdout.".to_string())?;
+    }
+
+    // Get only existing markdowns, or the single document piped through stdin.
+    let (markdowns, skipped) = if cli.stdin {
+        let mut markdown = String::new();
+        io::stdin().read_to_string(&mut markdown)?;
+        (vec![(PathBuf::from("<stdin>"), markdown)], Vec::new())
+    } else {
+        get_markdowns(cli.markdowns.clone(), &cli.markdown_exts)?
+    };
+    for warning in &skipped {
+        eprintln!("{}", warning);
+    }
+
+    let options = RenderOptions {
+        // Values shared by every Markdown.
+        meta_defaults: match &cli.meta_file {
+            Some(path) => get_meta_defaults(path)?,
+            None => Vec::new(),
+        },
+        allow_list: get_allow_list(cli.allow.clone()),
+        required: cli.require.clone(),
+        title_level: cli.title_level,
+        escape_title: cli.escape_title,
+        body_name: cli.body_name.clone(),
+        words_per_minute: cli.words_per_minute,
+        raw_content: cli.raw_content,
+        strip_title_from_content: cli.strip_title,
+    };
+
+    // The Markdowns that failed to render, when continuing on error.
+    let mut failed: Vec<PathBuf> = Vec::new();
+    let mut report_failure = |markdown_url: &PathBuf, error: String| -> Result<(), Box<dyn Error>> {
+        // A check reports every problem, rather than stopping at the first.
+        if !cli.continue_on_error && !cli.check {
+            return Err(error.into());
+        }
+        eprintln!("Error: Failed to render '{}': {}", markdown_url.to_string_lossy(), error);
+        if !failed.contains(markdown_url) {
+            failed.push(markdown_url.clone());
+        }
+        Ok(())
+    };
+
+    // The template that each Markdown selected for itself, if any. A Markdown
+    // that selected a missing template isn't rendered at all.
+    let mut selected_templates = Vec::new();
+    for (markdown_url, markdown) in &markdowns {
+        match get_selected_template(markdown_url, markdown, &options, templates) {
+            Ok(selected) => selected_templates.push(Some(selected)),
+            Err(e) => {
+                report_failure(markdown_url, e.to_string())?;
+                selected_templates.push(None);
+            },
+        }
+    }
 
-    // Get only existing markdowns.
-    let markdowns = get_markdowns(cli.markdowns);
+    // An incremental build keeps the entries of the outputs that it skips
+    // from the previous manifest.
+    let previous_manifest = match &cli.manifest {
+        Some(manifest_path) if cli.incremental => fs::read_to_string(manifest_path).ok()
+            .and_then(|manifest| serde_json::from_str::<Vec<ManifestEntry>>(&manifest).ok())
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    };
+    let previous_entry = |markdown_url: &PathBuf, template_path: &PathBuf, output_path: &PathBuf| {
+        previous_manifest.iter().find(|entry| {
+            &entry.markdown == markdown_url && &entry.template == template_path && entry.output.as_ref() == Some(output_path)
+        })
+    };
 
-    for template_path in &templates {
+    let mut manifest = Vec::new();
+    let mut rendered_count = 0;
+    let mut warning_count = skipped.len();
+
+    for template_path in templates {
         // Check that the actual template exists.
         if !template_path.try_exists().map_err(|_| "The template could not be found.".to_string())? {
             Err("The template file does not exist.".to_string())?;
         };
-        let template = std::fs::read_to_string(&template_path)?;
+        let (template, included) = get_template(template_path)?;
         let template = Span::new(&template);
 
         // All placeholders that are present in the template.
-        let placeholders = get_placeholders(template)?;
+        let placeholders = get_placeholders(template)
+            .map_err(|error| format!("Failed to parse template '{}': {}", template_path.display(), error))?;
+        for warning in get_unknown_argument_warnings(template_path, template, &placeholders) {
+            warning_count += 1;
+            eprintln!("{}", warning);
+        }
 
-        for (markdown_url, markdown) in &markdowns {
-            let markdown = Span::new(markdown);
-            let mut html_doc = template.fragment().to_string();
+        // The placeholders are sorted in reverse, so reverse them again to get
+        // the names in the order they appear in the template.
+        let mut placeholder_names = Vec::new();
+        for placeholder in placeholders.iter().rev() {
+            if !placeholder_names.contains(&placeholder.name) {
+                placeholder_names.push(placeholder.name.clone());
+            }
+        }
 
-            // Parse the meta values, and combine them with the title and content of
-            // the markdown file.
-            let (markdown, meta_values) = parse_meta_section(markdown).unwrap_or((markdown, vec![]));
-            let variables: HashMap<String, String> = create_variables(markdown, meta_values)?;
-
-            // Check for unused variables.
-            if !allow_list.contains(&AllowList::Unused) && !allow_list.contains(&AllowList::UnusedVariables) {
-                let placeholder_keys = placeholders.iter().map(|p| &p.name).collect::<Vec<&String>>();
-                let unused_variables = variables.keys().filter(|key| !placeholder_keys.contains(key)).collect::<Vec<&String>>();
-                if !unused_variables.is_empty() {
-                    println!(
-                        "Warning: Unused variable{} in '{}': {}",
-                        if unused_variables.len() == 1_usize { "" } else { "s" },
-                        &markdown_url.to_string_lossy(),
-                        unused_variables.iter().map(|v| v.to_string()).collect::<Vec<String>>().join(", ")
-                    );
+        // Each Markdown is independent, so render them in parallel, but
+        // collect the results so they can be reported in the original order.
+        let selected_markdowns = markdowns.iter().zip(&selected_templates)
+            .filter_map(|(markdown, selected)| selected.map(|selected| (markdown, selected)))
+            .filter(|(_, selected)| selected.is_none_or(|selected| selected == template_path))
+            .collect::<Vec<(&(PathBuf, String), Option<&PathBuf>)>>();
+        let results = selected_markdowns.par_iter().map(|((markdown_url, markdown), selected)| {
+            // Leave any output that is newer than everything it is rendered
+            // from. When writing a manifest, the output must also be in the
+            // previous one, so that its entry can be kept.
+            if cli.incremental && !cli.check && !(cli.stdin || cli.stdout) {
+                let multiple_templates = templates.len() > 1 && selected.is_none();
+                let output_path = get_output_path(cli, template_path, markdown_url, multiple_templates).map_err(|e| e.to_string())?;
+                let inputs = [template_path, markdown_url].into_iter().chain(&included).chain(&cli.meta_file);
+                let previous = previous_entry(markdown_url, template_path, &output_path);
+                if is_up_to_date(&output_path, inputs) && (cli.manifest.is_none() || previous.is_some()) {
+                    let report = match cli.dry_run {
+                        true => Some(format!("would skip {}, as it is up to date\n", output_path.to_string_lossy())),
+                        false if cli.verbose => Some(format!("{} -> {} (up to date)\n", markdown_url.to_string_lossy(), output_path.to_string_lossy())),
+                        false => None,
+                    };
+                    return Ok(Outcome { warnings: Vec::new(), report, entry: previous.cloned(), rendered: false, files: Vec::new() });
                 }
             }
 
-            for placeholder in &placeholders {
-                if let Some(variable) = variables.get(&placeholder.name) {
-                    // Used to deref the variable.
-                    let mut variable = variable.to_owned();
+            let rendering = Instant::now();
+            let Rendered { html_doc, variables, unused_variables, warnings, content } = render(template, &placeholders, markdown_url, markdown, &options)
+                .map_err(|e| e.to_string())?;
+            let html_doc = format_html(cli, html_doc);
 
-                    for filter in &placeholder.filters {
-                        variable = render_filter(variable, filter);
-                    }
+            let mut entry = ManifestEntry {
+                markdown: markdown_url.clone(),
+                template: template_path.clone(),
+                output: None,
+                bytes: html_doc.len(),
+                placeholders: placeholder_names.clone(),
+                variables,
+                unused_variables,
+                elapsed: rendering.elapsed(),
+            };
 
-                    html_doc = replace_substring(&html_doc, placeholder.selection.start.offset, placeholder.selection.end.offset, &variable);
-                } else {
-                    let url = markdown_url.to_str().unwrap_or_default();
-                    return Err(format!("Missing variable '{}' in markdown '{}'.", &placeholder.name, url))?;
-                }
+            // A check only needs to know that the Markdown could be rendered.
+            if cli.check {
+                return Ok(Outcome { warnings, report: None, entry: Some(entry), rendered: true, files: Vec::new() });
             }
 
-            // Add newlines before each heading element, because I'd like the HTML
-            // to be easy to read.
-            for h in 2..6 {
-                let h = format!("<h{h}>");
-                html_doc = html_doc.replace(&h, &format!("\n{h}"));
-            };
+            // When piping, there is no output file to write to.
+            if cli.stdin || cli.stdout {
+                return Ok(Outcome { warnings, report: Some(html_doc), entry: Some(entry), rendered: true, files: Vec::new() });
+            }
 
-            // Get the template extension, because the user might be passing in
-            // something like an SVG.
-            let template_ext = template_path.extension().unwrap_or(OsStr::new("html"));
+            let multiple_templates = templates.len() > 1 && selected.is_none();
+            let output_path = get_output_path(cli, template_path, markdown_url, multiple_templates).map_err(|e| e.to_string())?;
+            entry.output = Some(output_path.clone());
 
-            // Get the output path where the `.md` is replaced with `.html`.
-            let mut output_path = match cli.output_dir.clone() {
-                Some(path) => path.join(markdown_url.with_extension(template_ext).file_name().unwrap()),
-                None => markdown_url.with_extension(template_ext),
+            // The plain text version sits alongside the output.
+            let mut files = vec![(output_path.clone(), html_doc)];
+            match cli.also_text {
+                true if output_path.extension() == Some(OsStr::new("txt")) => {
+                    Err(format!("The plain text version of '{}' would overwrite it.", output_path.to_string_lossy()))?
+                },
+                true => files.push((output_path.with_extension("txt"), get_plain_text(&content))),
+                false => (),
             };
 
-            // If there are multiple templates, then add that to the output path
-            // to avoid overwriting issues.
-            if templates.len() > 1 {
-                output_path = output_path.with_file_name(format!(
-                    "{}-{}",
-                    &template_path.file_stem().unwrap_or_default().to_str().unwrap_or_default(),
-                    output_path.file_stem().unwrap_or_default().to_str().unwrap_or_default()
-                )).with_extension("html");
+            // Report what would happen, without touching the disk.
+            if cli.dry_run {
+                let report = files.iter()
+                    .map(|(path, contents)| format!("would write {} ({} bytes)\n", path.to_string_lossy(), contents.len()))
+                    .collect::<String>();
+                return Ok(Outcome { warnings, report: Some(report), entry: Some(entry), rendered: true, files: Vec::new() });
+            }
+
+            Ok(Outcome { warnings, report: None, entry: Some(entry), rendered: true, files })
+        }).collect::<Vec<Result<Outcome, String>>>();
+
+        // Nothing is written until every Markdown has rendered, so that the
+        // first failure stops the build before any output is touched, unless
+        // continuing on error.
+        let mut outcomes = Vec::new();
+        for (((markdown_url, _), _), result) in selected_markdowns.iter().zip(results) {
+            match result {
+                Ok(outcome) => outcomes.push((markdown_url, outcome)),
+                Err(e) => report_failure(markdown_url, e)?,
             }
+        }
 
-            // Create all folders from the path.
-            if let Some(path) = output_path.parent() {
-                if !path.exists() {
-                    fs::create_dir_all(path)?;
+        let written = outcomes.par_iter().map(|(_, outcome)| {
+            for (path, contents) in &outcome.files {
+                // Create all folders from the path. This is safe to race, as
+                // `create_dir_all` does not fail if another thread created it
+                // first.
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent).map_err(|e| e.to_string())?;
                 }
+                write_atomically(path, contents).map_err(|e| e.to_string())?;
             }
+            Ok(())
+        }).collect::<Vec<Result<(), String>>>();
 
-            fs::write(output_path, html_doc)?;
+        for ((markdown_url, outcome), written) in outcomes.into_iter().zip(written) {
+            if let Err(e) = written {
+                report_failure(markdown_url, e)?;
+                continue;
+            }
+            let Outcome { warnings, report, entry, rendered, .. } = outcome;
+            warning_count += warnings.len();
+            for warning in warnings {
+                eprintln!("{}", warning);
+            }
+            if let Some(report) = report {
+                io::stdout().write_all(report.as_bytes())?;
+            }
+            let Some(entry) = entry else {
+                continue;
+            };
+            // A skipped Markdown has nothing more to report, other than its
+            // entry in the manifest.
+            if !rendered {
+                manifest.push(entry);
+                continue;
+            }
+            rendered_count += 1;
+            if cli.verbose {
+                // Keep stdout clean when the output itself is written there.
+                if cli.stdin || cli.stdout {
+                    eprintln!("{}", format_progress(&entry));
+                } else {
+                    println!("{}", format_progress(&entry));
+                }
+            }
+            manifest.push(entry);
         }
     }
 
-    Ok(())
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
+    if let Some(manifest_path) = &cli.manifest {
+        if !cli.dry_run && !cli.check {
+            fs::write(manifest_path, serde_json::to_string_pretty(&manifest)?)?;
+        }
+    }
 
-    #[test]
-    fn can_convert_html() {
-        let template = PathBuf::from("tests/template.html");
-        let template = fs::read_to_string(template).expect("template to exist");
-        let template = Span::new(&template);
+    if cli.verbose {
+        let summary = format!(
+            "Rendered {} file{} with {} warning{} in {:.2?}.",
+            rendered_count,
+            if rendered_count == 1_usize { "" } else { "s" },
+            warning_count,
+            if warning_count == 1_usize { "" } else { "s" },
+            started.elapsed()
+        );
+        if cli.stdin || cli.stdout {
+            eprintln!("{}", summary);
+        } else {
+            println!("{}", summary);
+        }
+    }
 
-        let markdown = PathBuf::from("tests/one.md");
-        let output = &markdown.with_file_name("one_output").with_extension("html");
-        let markdowns = get_markdowns(vec![markdown]);
+    if cli.check && (!failed.is_empty() || warning_count > 0) {
+        Err(format!(
+            "The check found {} Markdown{} that failed to render, and {} warning{}.",
+            failed.len(),
+            if failed.len() == 1_usize { "" } else { "s" },
+            warning_count,
+            if warning_count == 1_usize { "" } else { "s" }
+        ))?;
+    }
 
-        let placeholders = get_placeholders(Span::new(&template)).expect("to parse placeholders");
+    if !failed.is_empty() {
+        Err(format!(
+            "{} Markdown{} failed to render.",
+            failed.len(),
+            if failed.len() == 1_usize { "" } else { "s" }
+        ))?;
+    }
 
-        for (_markdown_url, markdown) in &markdowns {
-            let markdown = Span::new(markdown);
-            let mut html_doc = template.fragment().to_string();
+    if cli.fail_on_warning && warning_count > 0 {
+        Err(format!(
+            "{} warning{} reported, and `--fail-on-warning` is set.",
+            warning_count,
+            if warning_count == 1_usize { " was" } else { "s were" }
+        ))?;
+    }
 
-            let (markdown, meta_values) = parse_meta_section(markdown).unwrap_or((markdown, vec![]));
-            let variables: HashMap<String, String> = create_variables(markdown, meta_values).expect("to create variables");
+    Ok(())
+}
 
-            for placeholder in &placeholders {
-                let mut variable = variables.get(&placeholder.name).expect("placeholder to be present in template.").to_owned();
+/// Get every file that a build reads, each canonicalised so that it can be
+/// compared to the paths of watch events.
+///
+/// Any files that the templates include are sources too.
+fn get_sources(cli: &Cli) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let templates = expand_globs(cli.templates.clone())?;
+    let includes = templates.iter()
+        .filter_map(|path| get_template(path).ok())
+        .flat_map(|(_, included)| included)
+        .collect::<Vec<PathBuf>>();
+    Ok(templates.into_iter()
+        .chain(includes)
+        .chain(expand_globs(cli.markdowns.clone())?)
+        .chain(cli.meta_file.clone())
+        .filter_map(|path| path.canonicalize().ok())
+        .collect())
+}
 
-                for filter in &placeholder.filters {
-                    variable = render_filter(variable, filter);
-                }
+/// Watch the templates, Markdowns, and meta file, rebuilding whenever any of
+/// them change.
+///
+/// The parent directories are watched rather than the files themselves,
+/// because many editors save by replacing the file, which would otherwise
+/// drop the watch.
+fn watch(cli: &Cli) -> Result<(), Box<dyn Error>> {
+    let (tx, rx) = mpsc::channel();
+    let mut debouncer = new_debouncer(Duration::from_millis(250), tx)?;
+    let mut watched_dirs = HashSet::new();
+    let mut sources = Vec::new();
 
-                html_doc = replace_substring(&html_doc, placeholder.selection.start.offset, placeholder.selection.end.offset, &variable);
+    println!("Watching for changes, press Ctrl-C to stop.");
+    loop {
+        // The sources can change with each rebuild, such as when a template
+        // includes another file, so the watched directories are updated.
+        match get_sources(cli) {
+            Ok(new_sources) => sources = new_sources,
+            Err(e) => eprintln!("[{}] Error: {}", timestamp(), e),
+        }
+        for dir in sources.iter().filter_map(|source| source.parent()) {
+            if !watched_dirs.contains(dir) {
+                debouncer.watcher().watch(dir, RecursiveMode::NonRecursive)?;
+                watched_dirs.insert(dir.to_path_buf());
             }
+        }
 
-            fs::write(output, html_doc).expect("to write html");
+        let Ok(events) = rx.recv() else {
+            break;
+        };
+        match events {
+            Ok(events) if events.iter().any(|event| sources.contains(&event.path)) => {
+                match build(cli) {
+                    Ok(()) => println!("[{}] Rebuilt.", timestamp()),
+                    Err(e) => eprintln!("[{}] Error: {}", timestamp(), e),
+                }
+            },
+            Ok(_) => {},
+            Err(e) => eprintln!("[{}] Watch error: {}", timestamp(), e),
         }
+    }
 
-        let output = fs::read_to_string(PathBuf::from(output)).expect("to read output");
-        let output = output.replace("\r", "");
+    Ok(())
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let cli = Cli::parse();
+
+    if cli.list_filters {
+        print!("{}", get_filter_list());
+        return Ok(());
+    }
+
+    if let Some(jobs) = cli.jobs {
+        rayon::ThreadPoolBuilder::new().num_threads(jobs).build_global()?;
+    }
+
+    match build(&cli) {
+        // A failed first build can be fixed while watching, like any other.
+        Err(e) if cli.watch => eprintln!("[{}] Error: {}", timestamp(), e),
+        result => result?,
+    }
+
+    if cli.watch {
+        watch(&cli)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Create a unique directory for a test, which is removed once dropped.
+    fn temp_dir() -> tempfile::TempDir {
+        tempfile::Builder::new().prefix("blogs-md-easy-").tempdir().expect("to create directory")
+    }
+
+    #[test]
+    fn can_convert_html() {
+        let template = PathBuf::from("tests/template.html");
+        let template = fs::read_to_string(template).expect("template to exist");
+        let template = Span::new(&template);
+
+        let markdown = PathBuf::from("tests/one.md");
+        let output = &markdown.with_file_name("one_output").with_extension("html");
+        let (markdowns, _) = get_markdowns(vec![markdown], &["md".to_string()]).expect("to read markdowns");
+
+        let placeholders = get_placeholders(Span::new(&template)).expect("to parse placeholders");
+
+        for (_markdown_url, markdown) in &markdowns {
+            let markdown = Span::new(markdown);
+            let mut html_doc = template.fragment().to_string();
+
+            let (markdown, meta_values) = parse_meta_section(markdown).unwrap_or((markdown, vec![]));
+            let variables: HashMap<String, String> = create_variables(markdown, meta_values, 1).expect("to create variables");
+
+            for placeholder in &placeholders {
+                let variable = placeholder.apply(variables.get(&placeholder.name).expect("placeholder to be present in template.").to_owned());
+
+                html_doc = replace_substring(&html_doc, placeholder.selection.start.offset, placeholder.selection.end.offset, &variable);
+            }
+
+            fs::write(output, html_doc).expect("to write html");
+        }
+
+        let output = fs::read_to_string(PathBuf::from(output)).expect("to read output");
+        let output = output.replace("\r", "");
         assert_eq!(output, r#"<head>
     <title>MARKDOWN TITLE | 2 | 1</title>
 </head>
@@ -222,4 +1336,977 @@ is a newline</p></footer>
 </body>
 "#);
     }
+
+    #[test]
+    fn can_render_markdown_from_string() {
+        let template = Span::new("<title>{{ £title }}</title>\n<main>{{ £content }}</main>");
+        let placeholders = get_placeholders(template).expect("to parse placeholders");
+        let markdown = ":meta\ntitle = Piped Title\n:meta\nPiped through **stdin**.";
+
+        let rendered = render(template, &placeholders, Path::new("<stdin>"), markdown, &RenderOptions::default()).expect("to render markdown");
+        assert!(rendered.warnings.is_empty());
+        assert_eq!(rendered.html_doc, "<title>Piped Title</title>\n<main><p>Piped through <strong>stdin</strong>.</p></main>");
+    }
+
+    #[test]
+    fn conditional_blocks_depend_on_variables() {
+        let template = Span::new("<h1>{{ £title }}</h1>{{ #if £subtitle }}<h2>{{ £subtitle }}</h2>{{ /if }}{{ #unless £subtitle }}<hr>{{ /unless }}{{ £content }}");
+        let placeholders = get_placeholders(template).expect("to parse placeholders");
+
+        // Present.
+        let markdown = ":meta\nsubtitle = Second\n:meta\n# First\nBody";
+        let rendered = render(template, &placeholders, Path::new("post.md"), markdown, &RenderOptions::default()).expect("to render markdown");
+        assert!(rendered.warnings.is_empty());
+        assert_eq!(rendered.html_doc, "<h1>First</h1><h2>Second</h2><h1>First</h1>\n<p>Body</p>");
+
+        // Absent, so the placeholder within the block is not required.
+        let markdown = "# First\nBody";
+        let rendered = render(template, &placeholders, Path::new("post.md"), markdown, &RenderOptions::default()).expect("to render markdown");
+        assert!(rendered.warnings.is_empty());
+        assert_eq!(rendered.html_doc, "<h1>First</h1><hr><h1>First</h1>\n<p>Body</p>");
+
+        // Empty.
+        let markdown = ":meta\nsubtitle =\n:meta\n# First\nBody";
+        let rendered = render(template, &placeholders, Path::new("post.md"), markdown, &RenderOptions::default()).expect("to render markdown");
+        assert!(rendered.warnings.is_empty());
+        assert_eq!(rendered.html_doc, "<h1>First</h1><hr><h1>First</h1>\n<p>Body</p>");
+    }
+
+    #[test]
+    fn conditional_blocks_can_be_nested() {
+        let template = Span::new("{{ #if £a }}A{{ #if £b }}B{{ /if }}{{ /if }}{{ £title }}");
+        let placeholders = get_placeholders(template).expect("to parse placeholders");
+
+        let markdown = ":meta\na = 1\n:meta\n# Title";
+        let rendered = render(template, &placeholders, Path::new("post.md"), markdown, &RenderOptions::default()).expect("to render markdown");
+        assert_eq!(rendered.html_doc, "ATitle");
+
+        let markdown = ":meta\na = 1\nb = 2\n:meta\n# Title";
+        let rendered = render(template, &placeholders, Path::new("post.md"), markdown, &RenderOptions::default()).expect("to render markdown");
+        assert_eq!(rendered.html_doc, "ABTitle");
+        assert_eq!(rendered.unused_variables, vec!["content".to_string()]);
+    }
+
+    #[test]
+    fn loop_blocks_repeat_for_each_item() {
+        let template = Span::new("<ul>{{ #for £tag in £tags }}<li>{{ £tag | uppercase }}</li>{{ /for }}</ul>{{ £title }}");
+        let placeholders = get_placeholders(template).expect("to parse placeholders");
+
+        let markdown = ":meta\ntags = rust, cli, markdown\n:meta\n# Title";
+        let rendered = render(template, &placeholders, Path::new("post.md"), markdown, &RenderOptions { allow_list: vec![AllowList::UnusedVariables], ..Default::default() }).expect("to render markdown");
+        assert_eq!(rendered.html_doc, "<ul><li>RUST</li><li>CLI</li><li>MARKDOWN</li></ul>Title");
+
+        // An empty list produces no output.
+        let markdown = ":meta\ntags =\n:meta\n# Title";
+        let rendered = render(template, &placeholders, Path::new("post.md"), markdown, &RenderOptions { allow_list: vec![AllowList::UnusedVariables], ..Default::default() }).expect("to render markdown");
+        assert_eq!(rendered.html_doc, "<ul></ul>Title");
+
+        // A missing list is an error, just as a missing placeholder is.
+        let error = render(template, &placeholders, Path::new("post.md"), "# Title", &RenderOptions::default()).expect_err("to fail to render");
+        assert_eq!(error.to_string(), "Missing variable 'tags' in markdown 'post.md'.");
+    }
+
+    #[test]
+    fn loop_item_shadows_outer_variables() {
+        let template = Span::new("{{ £tag }}:{{ #for £tag in £tags | split = ';' }}{{ #if £tag }}[{{ £tag }}]{{ /if }}{{ /for }}:{{ £tag }}");
+        let placeholders = get_placeholders(template).expect("to parse placeholders");
+
+        let markdown = ":meta\ntag = outer\ntags = a; b\ntitle = Title\n:meta\nBody";
+        let rendered = render(template, &placeholders, Path::new("post.md"), markdown, &RenderOptions::default()).expect("to render markdown");
+        assert_eq!(rendered.html_doc, "outer:[a][b]:outer");
+        assert_eq!(rendered.unused_variables, vec!["content".to_string(), "title".to_string()]);
+    }
+
+    #[test]
+    fn loop_blocks_can_be_nested() {
+        let template = Span::new("{{ #for £row in £rows }}{{ #for £cell in £columns }}{{ £row }}{{ £cell }} {{ /for }}{{ /for }}");
+        let placeholders = get_placeholders(template).expect("to parse placeholders");
+
+        let markdown = ":meta\nrows = 1, 2\ncolumns = a, b\n:meta\n# Title";
+        let rendered = render(template, &placeholders, Path::new("post.md"), markdown, &RenderOptions { allow_list: vec![AllowList::UnusedVariables], ..Default::default() }).expect("to render markdown");
+        assert_eq!(rendered.html_doc, "1a 1b 2a 2b ");
+    }
+
+    #[test]
+    fn unclosed_conditional_blocks_are_errors() {
+        let template = Span::new("{{ #if £subtitle }}{{ £title }}");
+        let placeholders = get_placeholders(template).expect("to parse placeholders");
+        let error = render(template, &placeholders, Path::new("post.md"), "# Title", &RenderOptions::default()).expect_err("to fail to render");
+        assert_eq!(error.to_string(), "The conditional block on line 1 is not closed.");
+    }
+
+    #[test]
+    fn can_include_partials() {
+        let tmp = temp_dir();
+        let dir = tmp.path();
+        fs::create_dir_all(dir.join("partials")).expect("to create directories");
+        fs::write(dir.join("page.html"), "{{ #include \"partials/header.html\" }}\n<main>{{ £content }}</main>\n{{ #include partials/footer.html }}\n").expect("to write template");
+        fs::write(dir.join("partials/header.html"), "<header>{{ #include 'title.html' }}</header>\n").expect("to write partial");
+        fs::write(dir.join("partials/title.html"), "<h1>{{ £title }}</h1>\n").expect("to write partial");
+        fs::write(dir.join("partials/footer.html"), "<footer></footer>\r\n").expect("to write partial");
+
+        let (template, included) = get_template(&dir.join("page.html")).expect("to include partials");
+        assert_eq!(template, "<header><h1>{{ £title }}</h1></header>\n<main>{{ £content }}</main>\n<footer></footer>\n");
+        assert_eq!(included.len(), 3);
+
+        // The same partial can be included more than once.
+        fs::write(dir.join("twice.html"), "{{ #include partials/footer.html }}{{ #include partials/footer.html }}").expect("to write template");
+        let (template, _) = get_template(&dir.join("twice.html")).expect("to include partials");
+        assert_eq!(template, "<footer></footer><footer></footer>");
+
+    }
+
+    #[test]
+    fn recursive_includes_are_errors() {
+        let tmp = temp_dir();
+        let dir = tmp.path();
+        fs::write(dir.join("a.html"), "A {{ #include b.html }}").expect("to write template");
+        fs::write(dir.join("b.html"), "B {{ #include a.html }}").expect("to write template");
+        fs::write(dir.join("missing.html"), "{{ #include nowhere.html }}").expect("to write template");
+
+        let error = get_template(&dir.join("a.html")).expect_err("to find the cycle");
+        assert!(error.to_string().ends_with("includes itself: a.html -> b.html -> a.html"));
+
+        let error = get_template(&dir.join("missing.html")).expect_err("to find the missing include");
+        assert!(error.to_string().contains("nowhere.html' could not be found."));
+
+    }
+
+    #[test]
+    fn can_expand_markdown_globs() {
+        let tmp = temp_dir();
+        let dir = tmp.path();
+        fs::create_dir_all(dir.join("nested")).expect("to create directories");
+        fs::write(dir.join("one.md"), "# One").expect("to write markdown");
+        fs::write(dir.join("nested/two.md"), "# Two").expect("to write markdown");
+        fs::write(dir.join("nested/three.txt"), "Three").expect("to write text");
+
+        let (mut markdowns, skipped) = get_markdowns(vec![dir.join("**/*.md")], &["md".to_string()]).expect("to expand glob");
+        assert!(skipped.is_empty());
+        markdowns.sort();
+        assert_eq!(markdowns, vec![
+            (dir.join("nested/two.md"), "# Two".to_string()),
+            (dir.join("one.md"), "# One".to_string()),
+        ]);
+
+        // Literal paths are still accepted.
+        let (markdowns, _) = get_markdowns(vec![dir.join("one.md")], &["md".to_string()]).expect("to read literal path");
+        assert_eq!(markdowns, vec![(dir.join("one.md"), "# One".to_string())]);
+
+        // A pattern that matches nothing is an error.
+        assert!(get_markdowns(vec![dir.join("missing/*.md")], &["md".to_string()]).is_err());
+    }
+
+    #[test]
+    fn can_preserve_structure_in_output_dir() {
+        let tmp = temp_dir();
+        let dir = tmp.path();
+        fs::create_dir_all(dir.join("posts/a")).expect("to create directories");
+        fs::create_dir_all(dir.join("posts/b")).expect("to create directories");
+        fs::write(dir.join("template.html"), "<h1>{{ £title }}</h1>{{ £content }}").expect("to write template");
+        fs::write(dir.join("posts/a/one.md"), "# One").expect("to write markdown");
+        fs::write(dir.join("posts/b/two.md"), "# Two").expect("to write markdown");
+
+        let cli = Cli::parse_from([
+            "blogs-md-easy",
+            "-t", dir.join("template.html").to_str().unwrap(),
+            "-m", dir.join("posts/a/one.md").to_str().unwrap(), dir.join("posts/b/two.md").to_str().unwrap(),
+            "-o", dir.join("dist").to_str().unwrap(),
+            "--base-dir", dir.to_str().unwrap(),
+        ]);
+        build(&cli).expect("to build");
+
+        assert!(dir.join("dist/posts/a/one.html").exists());
+        assert!(dir.join("dist/posts/b/two.html").exists());
+    }
+
+    #[test]
+    fn can_get_relative_path() {
+        assert_eq!(get_relative_path(Path::new("posts/a/one.md"), None), PathBuf::from("posts/a/one.md"));
+        assert_eq!(get_relative_path(Path::new("posts/a/one.md"), Some(Path::new("posts"))), PathBuf::from("a/one.md"));
+        assert_eq!(get_relative_path(Path::new("../posts/one.md"), None), PathBuf::from("posts/one.md"));
+    }
+
+    #[test]
+    fn can_override_output_extension() {
+        let tmp = temp_dir();
+        let dir = tmp.path();
+        fs::write(dir.join("feed.xml"), "<title>{{ £title }}</title>{{ £content }}").expect("to write template");
+        fs::write(dir.join("one.md"), "# One").expect("to write markdown");
+
+        let cli = Cli::parse_from([
+            "blogs-md-easy",
+            "-t", dir.join("feed.xml").to_str().unwrap(),
+            "-m", dir.join("one.md").to_str().unwrap(),
+            "--ext", "rss",
+        ]);
+        assert_eq!(get_output_path(&cli, &dir.join("feed.xml"), &dir.join("one.md"), false).expect("to get output path"), dir.join("one.rss"));
+
+        build(&cli).expect("to build");
+        assert!(dir.join("one.rss").exists());
+        assert!(!dir.join("one.xml").exists());
+    }
+
+    #[test]
+    fn can_name_output_with_pattern() {
+        let cli = Cli::parse_from([
+            "blogs-md-easy",
+            "-t", "templates/page.html", "templates/card.html",
+            "-m", "posts/one.md",
+            "--name-pattern", "{markdown}.{template}.{ext}",
+        ]);
+        let output_path = get_output_path(&cli, Path::new("templates/card.html"), Path::new("posts/one.md"), true).expect("to get output path");
+        assert_eq!(output_path, PathBuf::from("posts/one.card.html"));
+
+        // Without a pattern, the template stem is prefixed.
+        let cli = Cli::parse_from(["blogs-md-easy", "-t", "page.html", "card.html", "-m", "posts/one.md"]);
+        let output_path = get_output_path(&cli, Path::new("card.html"), Path::new("posts/one.md"), true).expect("to get output path");
+        assert_eq!(output_path, PathBuf::from("posts/card-one.html"));
+    }
+
+    #[test]
+    fn cannot_name_output_with_empty_pattern() {
+        let cli = Cli::parse_from(["blogs-md-easy", "-t", "page.html", "-m", "one.md", "--name-pattern", " "]);
+        assert!(get_output_path(&cli, Path::new("page.html"), Path::new("one.md"), false).is_err());
+
+        let cli = Cli::parse_from(["blogs-md-easy", "-t", "page.html", "-m", "one.md", "--name-pattern", "{template}/{markdown}"]);
+        assert!(get_output_path(&cli, Path::new("page.html"), Path::new("one.md"), false).is_err());
+    }
+
+    #[test]
+    fn dry_run_does_not_write() {
+        let tmp = temp_dir();
+        let dir = tmp.path();
+        fs::write(dir.join("template.html"), "<title>{{ £title }}</title>{{ £content }}").expect("to write template");
+        fs::write(dir.join("one.md"), "# One").expect("to write markdown");
+
+        let cli = Cli::parse_from([
+            "blogs-md-easy",
+            "-t", dir.join("template.html").to_str().unwrap(),
+            "-m", dir.join("one.md").to_str().unwrap(),
+            "-o", dir.join("dist").to_str().unwrap(),
+            "--dry-run",
+        ]);
+        build(&cli).expect("to build");
+        assert!(!dir.join("dist").exists());
+
+        // Errors are still reported.
+        fs::write(dir.join("template.html"), "{{ £missing }}").expect("to write template");
+        assert!(build(&cli).is_err());
+    }
+
+    #[test]
+    fn can_write_manifest() {
+        let tmp = temp_dir();
+        let dir = tmp.path();
+        fs::write(dir.join("template.html"), "<title>{{ £title }}</title>{{ £content }}{{ £title }}").expect("to write template");
+        fs::write(dir.join("one.md"), ":meta\nauthor = John Doe\n:meta\n# One").expect("to write markdown");
+
+        let cli = Cli::parse_from([
+            "blogs-md-easy",
+            "-t", dir.join("template.html").to_str().unwrap(),
+            "-m", dir.join("one.md").to_str().unwrap(),
+            "--manifest", dir.join("manifest.json").to_str().unwrap(),
+            "--allow", "unused",
+        ]);
+        build(&cli).expect("to build");
+
+        let manifest = fs::read_to_string(dir.join("manifest.json")).expect("to read manifest");
+        let manifest: serde_json::Value = serde_json::from_str(&manifest).expect("to parse manifest");
+        let entries = manifest.as_array().expect("manifest to be an array");
+        assert_eq!(entries.len(), 1);
+
+        let entry = &entries[0];
+        assert_eq!(entry["markdown"], dir.join("one.md").to_str().unwrap());
+        assert_eq!(entry["template"], dir.join("template.html").to_str().unwrap());
+        assert_eq!(entry["output"], dir.join("one.html").to_str().unwrap());
+        assert_eq!(entry["bytes"], fs::read_to_string(dir.join("one.html")).unwrap().len());
+        assert_eq!(entry["placeholders"], serde_json::json!(["title", "content"]));
+        assert_eq!(entry["variables"], serde_json::json!(["author", "content", "title"]));
+        assert_eq!(entry["unused_variables"], serde_json::json!(["author"]));
+    }
+
+    #[test]
+    fn no_pretty_skips_heading_newlines() {
+        let html_doc = "<h1>One</h1><h2>Two</h2><h6>Six</h6>".to_string();
+
+        let cli = Cli::parse_from(["blogs-md-easy", "-t", "template.html", "-m", "one.md"]);
+        assert_eq!(format_html(&cli, html_doc.clone()), "<h1>One</h1>\n<h2>Two</h2>\n<h6>Six</h6>");
+
+        let cli = Cli::parse_from(["blogs-md-easy", "-t", "template.html", "-m", "one.md", "--no-pretty"]);
+        assert_eq!(format_html(&cli, html_doc.clone()), html_doc);
+    }
+
+    #[test]
+    fn heading_newlines_include_h6() {
+        let cli = Cli::parse_from(["blogs-md-easy", "-t", "template.html", "-m", "one.md"]);
+        assert_eq!(format_html(&cli, "<p>a</p><h2>Two</h2>".to_string()), "<p>a</p>\n<h2>Two</h2>");
+        assert_eq!(format_html(&cli, "<p>a</p><h6>Six</h6>".to_string()), "<p>a</p>\n<h6>Six</h6>");
+
+        // An existing newline is not duplicated.
+        assert_eq!(format_html(&cli, "<p>a</p>\n<h6>Six</h6>".to_string()), "<p>a</p>\n<h6>Six</h6>");
+    }
+
+    #[test]
+    fn document_meta_overrides_meta_file() {
+        let tmp = temp_dir();
+        let dir = tmp.path();
+        fs::write(dir.join("defaults.md"), ":meta\nsite = My Blog\nauthor = Site Owner\nunused = Nothing\n:meta").expect("to write meta file");
+        fs::write(dir.join("defaults.txt"), "site = My Blog\nauthor = Site Owner\n").expect("to write meta file");
+
+        let template = Span::new("{{ £title }} by {{ £author }} on {{ £site }}\n{{ £content }}");
+        let placeholders = get_placeholders(template).expect("to parse placeholders");
+        let markdown = ":meta\nauthor = Guest Writer\n:meta\n# Post";
+
+        for meta_file in ["defaults.md", "defaults.txt"] {
+            let meta_defaults = get_meta_defaults(&dir.join(meta_file)).expect("to read meta file");
+            let options = RenderOptions { meta_defaults, ..Default::default() };
+            let rendered = render(template, &placeholders, Path::new("post.md"), markdown, &options).expect("to render markdown");
+            assert_eq!(rendered.html_doc, "Post by Guest Writer on My Blog\n<h1>Post</h1>");
+            // Unused defaults are not reported.
+            assert!(rendered.warnings.is_empty());
+        }
+
+        // A line that can't be parsed is an error, rather than dropping the
+        // values after it.
+        fs::write(dir.join("broken.txt"), "\nsite = My Blog\nnot a meta line\nauthor = Site Owner\n").expect("to write meta file");
+        let error = get_meta_defaults(&dir.join("broken.txt")).expect_err("to fail to parse");
+        assert_eq!(error.to_string(), format!(
+            "The meta file '{}' could not be parsed. Expected a `key = value` line or a comment on line 3, column 1:\nnot a meta line\n^",
+            dir.join("broken.txt").to_string_lossy()
+        ));
+
+    }
+
+    #[test]
+    fn missing_required_variables_are_errors() {
+        let template = Span::new("{{ £title }} by {{ £author }}\n{{ £content }}");
+        let placeholders = get_placeholders(template).expect("to parse placeholders");
+        let options = RenderOptions {
+            required: vec!["author".to_string(), "publish_date".to_string()],
+            ..Default::default()
+        };
+
+        let markdown = ":meta\nauthor = Jane\npublish_date = 2024-01-01\n:meta\n# Post";
+        assert!(render(template, &placeholders, Path::new("post.md"), markdown, &options).is_ok());
+
+        let markdown = ":meta\nauthor = Jane\n:meta\n# Post";
+        let error = render(template, &placeholders, Path::new("post.md"), markdown, &options).expect_err("to be missing a variable");
+        assert_eq!(error.to_string(), "Missing required variable 'publish_date' in markdown 'post.md'.");
+
+        // Empty values are treated as missing.
+        let markdown = ":meta\nauthor =\n:meta\n# Post";
+        let error = render(template, &placeholders, Path::new("post.md"), markdown, &options).expect_err("to be missing variables");
+        assert_eq!(error.to_string(), "Missing required variables 'author', 'publish_date' in markdown 'post.md'.");
+    }
+
+    #[test]
+    fn title_can_come_from_other_heading_levels() {
+        let template = Span::new("<title>{{ £title }}</title>\n{{ £content }}");
+        let placeholders = get_placeholders(template).expect("to parse placeholders");
+        let markdown = "## Sub\nContent";
+
+        let options = RenderOptions { title_level: 2, ..Default::default() };
+        let rendered = render(template, &placeholders, Path::new("post.md"), markdown, &options).expect("to render markdown");
+        assert_eq!(rendered.html_doc, "<title>Sub</title>\n<h2>Sub</h2>\n<p>Content</p>");
+
+        // The default level is 1, which this Markdown doesn't have.
+        assert!(render(template, &placeholders, Path::new("post.md"), markdown, &RenderOptions::default()).is_err());
+    }
+
+    #[test]
+    fn title_can_be_escaped() {
+        let template = Span::new("<title>{{ £title }}</title>");
+        let placeholders = get_placeholders(template).expect("to parse placeholders");
+        let markdown = "# Tom & Jerry";
+
+        let options = RenderOptions { allow_list: vec![AllowList::UnusedVariables], escape_title: true, ..Default::default() };
+        let rendered = render(template, &placeholders, Path::new("post.md"), markdown, &options).expect("to render markdown");
+        assert_eq!(rendered.html_doc, "<title>Tom &amp; Jerry</title>");
+
+        // By default, the title is left as it is.
+        let options = RenderOptions { allow_list: vec![AllowList::UnusedVariables], ..Default::default() };
+        let rendered = render(template, &placeholders, Path::new("post.md"), markdown, &options).expect("to render markdown");
+        assert_eq!(rendered.html_doc, "<title>Tom & Jerry</title>");
+    }
+
+    #[test]
+    fn duplicate_meta_keys_are_warnings() {
+        let template = Span::new("{{ £title }} by {{ £author }}\n{{ £content }}");
+        let placeholders = get_placeholders(template).expect("to parse placeholders");
+        let markdown = ":meta\nauthor = Jane\npublish_date = 2024-01-01\nauthor = John\n:meta\n# Post";
+        let options = RenderOptions { allow_list: vec![AllowList::UnusedVariables], ..Default::default() };
+
+        let rendered = render(template, &placeholders, Path::new("post.md"), markdown, &options).expect("to render markdown");
+        assert_eq!(rendered.html_doc, "Post by John\n<h1>Post</h1>");
+        assert_eq!(rendered.warnings, vec![
+            "Warning: Duplicate meta key 'author' in 'post.md', on lines 2 and 4. The value on line 4 is used.".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn body_can_use_another_name() {
+        let template = Span::new("<h1>{{ £title }}</h1>\n{{ £body | markdown }}");
+        let placeholders = get_placeholders(template).expect("to parse placeholders");
+        let markdown = "# Post\nContent";
+        let options = RenderOptions { body_name: "body".to_string(), ..Default::default() };
+
+        let rendered = render(template, &placeholders, Path::new("post.md"), markdown, &options).expect("to render markdown");
+        assert_eq!(rendered.html_doc, "<h1>Post</h1>\n<h1>Post</h1>\n<p>Content</p>");
+        // `content` is no longer a variable, so isn't reported as unused.
+        assert_eq!(rendered.variables, vec!["body", "title"]);
+        assert!(rendered.warnings.is_empty());
+
+        // A template expecting `content` is now missing a variable.
+        let template = Span::new("{{ £content }}");
+        let placeholders = get_placeholders(template).expect("to parse placeholders");
+        let options = RenderOptions { allow_list: vec![AllowList::UnusedVariables], ..options };
+        assert!(render(template, &placeholders, Path::new("post.md"), markdown, &options).is_err());
+    }
+
+    #[test]
+    fn warnings_can_fail_the_build() {
+        let tmp = temp_dir();
+        let dir = tmp.path();
+        fs::write(dir.join("template.html"), "<h1>{{ £title }}</h1>{{ £content }}").expect("to write template");
+        fs::write(dir.join("one.md"), ":meta\nauthor = Jane\n:meta\n# One").expect("to write markdown");
+
+        let (template, markdown) = (dir.join("template.html"), dir.join("one.md"));
+        let args = ["blogs-md-easy", "-t", template.to_str().unwrap(), "-m", markdown.to_str().unwrap()];
+
+        // The unused variable is only a warning by default.
+        build(&Cli::parse_from(args)).expect("to build");
+
+        let cli = Cli::parse_from(args.iter().chain(&["--fail-on-warning"]));
+        let error = build(&cli).expect_err("to fail on the unused variable");
+        assert_eq!(error.to_string(), "1 warning was reported, and `--fail-on-warning` is set.");
+        // The output is still written.
+        assert!(dir.join("one.html").exists());
+
+        // Allowed warnings are never reported, so can't fail the build.
+        let cli = Cli::parse_from(args.iter().chain(&["--fail-on-warning", "--allow", "unused-variables"]));
+        build(&cli).expect("to build");
+    }
+
+    #[test]
+    fn can_render_other_markdown_extensions() {
+        let tmp = temp_dir();
+        let dir = tmp.path();
+        fs::write(dir.join("template.html"), "<h1>{{ £title }}</h1>{{ £content }}").expect("to write template");
+        fs::write(dir.join("one.markdown"), "# One").expect("to write markdown");
+        fs::write(dir.join("two.MDOWN"), "# Two").expect("to write markdown");
+        fs::write(dir.join("notes.txt"), "# Notes").expect("to write text");
+
+        let template = dir.join("template.html");
+        let pattern = dir.join("*");
+        let cli = Cli::parse_from(["blogs-md-easy", "-t", template.to_str().unwrap(), "-m", pattern.to_str().unwrap()]);
+        build(&cli).expect("to build");
+        assert_eq!(fs::read_to_string(dir.join("one.html")).expect("to read output"), "<h1>One</h1><h1>One</h1>");
+        assert!(dir.join("two.html").exists());
+        assert!(!dir.join("notes.html").exists());
+
+        // The extensions can be replaced entirely.
+        let (exts, _) = get_markdowns(vec![pattern], &[".txt".to_string()]).expect("to read markdowns");
+        assert_eq!(exts, vec![(dir.join("notes.txt"), "# Notes".to_string())]);
+    }
+
+    #[test]
+    fn skipped_markdowns_are_reported() {
+        let tmp = temp_dir();
+        let dir = tmp.path();
+        fs::write(dir.join("post.md"), "# Post").expect("to write markdown");
+        fs::write(dir.join("notes.txt"), "# Notes").expect("to write text");
+
+        let extensions = ["md".to_string()];
+        let (markdowns, skipped) = get_markdowns(vec![dir.join("post.md"), dir.join("psot.md"), dir.join("notes.txt")], &extensions).expect("to read markdowns");
+        assert_eq!(markdowns, vec![(dir.join("post.md"), "# Post".to_string())]);
+        assert_eq!(skipped, vec![
+            format!("Warning: Skipped '{}', as it does not exist.", dir.join("psot.md").to_string_lossy()),
+            format!("Warning: Skipped '{}', as it does not have a Markdown extension (md).", dir.join("notes.txt").to_string_lossy()),
+        ]);
+
+        // Files that a pattern matches are expected to include other files.
+        let (markdowns, skipped) = get_markdowns(vec![dir.join("*")], &extensions).expect("to read markdowns");
+        assert_eq!(markdowns.len(), 1);
+        assert!(skipped.is_empty());
+    }
+
+    #[test]
+    fn can_format_progress() {
+        let mut entry = ManifestEntry {
+            markdown: PathBuf::from("posts/one.md"),
+            template: PathBuf::from("template.html"),
+            output: Some(PathBuf::from("posts/one.html")),
+            bytes: 42,
+            placeholders: vec!["title".to_string(), "content".to_string()],
+            variables: vec!["content".to_string(), "title".to_string()],
+            unused_variables: vec![],
+            elapsed: Duration::from_micros(1500),
+        };
+        assert_eq!(format_progress(&entry), "posts/one.md -> posts/one.html (2 variables, 1.50ms)");
+
+        entry.output = None;
+        entry.variables.pop();
+        assert_eq!(format_progress(&entry), "posts/one.md -> <stdout> (1 variable, 1.50ms)");
+    }
+
+    #[test]
+    fn placeholders_can_fall_back_to_other_variables() {
+        let template = Span::new("<h1>{{ £title }}</h1><h2>{{ £subtitle | fallback = title }}</h2><p>{{ £summary | coalesce = description }}</p>");
+        let placeholders = get_placeholders(template).expect("to parse placeholders");
+        let options = RenderOptions::default();
+
+        let markdown = ":meta\nsubtitle = A Subtitle\ndescription = About\n:meta\n# Post";
+        let rendered = render(template, &placeholders, Path::new("post.md"), markdown, &options).expect("to render markdown");
+        assert_eq!(rendered.html_doc, "<h1>Post</h1><h2>A Subtitle</h2><p>About</p>");
+        // The description is only used as a fallback, but isn't unused.
+        assert_eq!(rendered.unused_variables, vec!["content"]);
+
+        // An empty or missing variable uses the fallback.
+        let markdown = ":meta\nsubtitle =\n:meta\n# Post";
+        let rendered = render(template, &placeholders, Path::new("post.md"), markdown, &options).expect("to render markdown");
+        assert_eq!(rendered.html_doc, "<h1>Post</h1><h2>Post</h2><p></p>");
+    }
+
+    #[test]
+    fn unknown_filter_arguments_are_warnings() {
+        let template = Span::new("<h1>{{ £title | truncate = charcters: 7 }}</h1>\n{{ £content | markdown = gfm: true, anchor: true }}");
+        let placeholders = get_placeholders(template).expect("to parse placeholders");
+
+        assert_eq!(get_unknown_argument_warnings(Path::new("page.html"), template, &placeholders), vec![
+            "Warning: Unknown filter argument 'charcters' for '£title' on line 1 of 'page.html'.".to_string(),
+            "Warning: Unknown filter argument 'anchor' for '£content' on line 2 of 'page.html'.".to_string(),
+        ]);
+
+        // Known arguments, and their aliases, are fine.
+        let template = Span::new("{{ £title | truncate = chars: 7, trail: '!' | replace = find: a, regex: true, ignore_case: true }}");
+        let placeholders = get_placeholders(template).expect("to parse placeholders");
+        assert!(get_unknown_argument_warnings(Path::new("page.html"), template, &placeholders).is_empty());
+    }
+
+    #[test]
+    fn markdowns_can_select_their_template() {
+        let tmp = temp_dir();
+        let dir = tmp.path();
+        fs::create_dir_all(dir.join("templates")).expect("to create directory");
+        fs::write(dir.join("templates/post.html"), "<article>{{ £title }}</article>").expect("to write template");
+        fs::write(dir.join("templates/page.html"), "<main>{{ £title }}</main>").expect("to write template");
+        fs::write(dir.join("one.md"), ":meta\ntemplate = post.html\n:meta\n# One").expect("to write markdown");
+        fs::write(dir.join("two.md"), ":meta\ntemplate = templates/page.html\n:meta\n# Two").expect("to write markdown");
+        fs::write(dir.join("three.md"), "# Three").expect("to write markdown");
+
+        let templates = dir.join("templates/*.html");
+        let markdowns = dir.join("*.md");
+        let cli = Cli::parse_from([
+            "blogs-md-easy",
+            "-t", templates.to_str().unwrap(),
+            "-m", markdowns.to_str().unwrap(),
+            "--allow", "unused",
+        ]);
+        build(&cli).expect("to build");
+
+        // A selected template is the only one used, so no prefix is needed.
+        assert_eq!(fs::read_to_string(dir.join("one.html")).expect("to read output"), "<article>One</article>");
+        assert_eq!(fs::read_to_string(dir.join("two.html")).expect("to read output"), "<main>Two</main>");
+        assert!(!dir.join("page-one.html").exists());
+        assert!(!dir.join("post-two.html").exists());
+
+        // Without a selection, every template is used.
+        assert_eq!(fs::read_to_string(dir.join("post-three.html")).expect("to read output"), "<article>Three</article>");
+        assert_eq!(fs::read_to_string(dir.join("page-three.html")).expect("to read output"), "<main>Three</main>");
+
+        // A template that wasn't given can't be selected.
+        fs::write(dir.join("four.md"), ":meta\ntemplate = missing.html\n:meta\n# Four").expect("to write markdown");
+        let error = build(&cli).expect_err("to fail on the missing template");
+        assert_eq!(error.to_string(), format!(
+            "The template 'missing.html' selected by markdown '{}' was not given with `--templates`.",
+            dir.join("four.md").to_string_lossy()
+        ));
+    }
+
+    #[test]
+    fn can_continue_after_a_failed_markdown() {
+        let tmp = temp_dir();
+        let dir = tmp.path();
+        fs::write(dir.join("template.html"), "<h1>{{ £title }}</h1><p>{{ £author }}</p>").expect("to write template");
+        fs::write(dir.join("broken.md"), "# Broken").expect("to write markdown");
+        fs::write(dir.join("good.md"), ":meta\nauthor = Jane\n:meta\n# Good").expect("to write markdown");
+
+        let (template, broken, good) = (dir.join("template.html"), dir.join("broken.md"), dir.join("good.md"));
+        let args = ["blogs-md-easy", "-t", template.to_str().unwrap(), "-m", broken.to_str().unwrap(), good.to_str().unwrap(), "--allow", "unused"];
+
+        // By default, the first error stops the build.
+        let error = build(&Cli::parse_from(args)).expect_err("to fail on the missing variable");
+        assert_eq!(error.to_string(), format!("Missing variable 'author' in markdown '{}'.", broken.to_string_lossy()));
+        assert!(!dir.join("good.html").exists());
+
+        // Otherwise, the remaining files are still written.
+        let cli = Cli::parse_from(args.iter().chain(&["--continue-on-error"]));
+        let error = build(&cli).expect_err("to fail once finished");
+        assert_eq!(error.to_string(), "1 Markdown failed to render.");
+        assert_eq!(fs::read_to_string(dir.join("good.html")).expect("to read output"), "<h1>Good</h1><p>Jane</p>");
+        assert!(!dir.join("broken.html").exists());
+    }
+
+    #[test]
+    fn output_is_written_atomically() {
+        let tmp = temp_dir();
+        let dir = tmp.path();
+
+        let contents = "<p>Hello, World!</p>\n".repeat(10_000);
+        fs::write(dir.join("page.html"), "Old, and much shorter.").expect("to write output");
+        write_atomically(&dir.join("page.html"), &contents).expect("to write output");
+        assert_eq!(fs::read_to_string(dir.join("page.html")).expect("to read output"), contents);
+
+        // The temporary file is renamed, so only the output remains.
+        let files = fs::read_dir(dir).expect("to read directory")
+            .map(|entry| entry.expect("to read entry").file_name())
+            .collect::<Vec<_>>();
+        assert_eq!(files, vec!["page.html"]);
+
+        // A failed write doesn't leave a temporary file behind either.
+        assert!(write_atomically(&dir.join("missing").join("page.html"), &contents).is_err());
+        assert_eq!(fs::read_dir(dir).expect("to read directory").count(), 1);
+    }
+
+    #[test]
+    fn incremental_builds_skip_up_to_date_outputs() {
+        let tmp = temp_dir();
+        let dir = tmp.path();
+        fs::write(dir.join("template.html"), "<h1>{{ £title }}</h1>").expect("to write template");
+        fs::write(dir.join("one.md"), "# One").expect("to write markdown");
+
+        let (template, markdown) = (dir.join("template.html"), dir.join("one.md"));
+        let args = ["blogs-md-easy", "-t", template.to_str().unwrap(), "-m", markdown.to_str().unwrap(), "--allow", "unused", "--incremental"];
+        build(&Cli::parse_from(args)).expect("to build");
+        assert_eq!(fs::read_to_string(dir.join("one.html")).expect("to read output"), "<h1>One</h1>");
+
+        // The output is newer than its inputs, so it is left as it is.
+        fs::write(dir.join("one.html"), "Left alone").expect("to write output");
+        build(&Cli::parse_from(args)).expect("to build");
+        assert_eq!(fs::read_to_string(dir.join("one.html")).expect("to read output"), "Left alone");
+        assert!(is_up_to_date(&dir.join("one.html"), [&template, &markdown]));
+
+        // Touching the Markdown makes it newer than the output.
+        let touched = SystemTime::now() + Duration::from_secs(60);
+        fs::File::options().write(true).open(&markdown).expect("to open markdown")
+            .set_modified(touched).expect("to touch markdown");
+        assert!(!is_up_to_date(&dir.join("one.html"), [&template, &markdown]));
+
+        // A dry run reports the render, without writing it.
+        build(&Cli::parse_from(args.iter().chain(&["--dry-run"]))).expect("to build");
+        assert_eq!(fs::read_to_string(dir.join("one.html")).expect("to read output"), "Left alone");
+
+        build(&Cli::parse_from(args)).expect("to build");
+        assert_eq!(fs::read_to_string(dir.join("one.html")).expect("to read output"), "<h1>One</h1>");
+
+        // A missing output is never up to date.
+        assert!(!is_up_to_date(&dir.join("two.html"), [&template]));
+    }
+
+    #[test]
+    fn meta_section_can_follow_the_title() {
+        let template = Span::new("<h1>{{ £title }}</h1><small>{{ £author }}</small>{{ £content }}");
+        let placeholders = get_placeholders(template).expect("to parse placeholders");
+        let options = RenderOptions::default();
+
+        let markdown = "# Post\n:meta\nauthor = Jane\n:meta\nSome content.";
+        let rendered = render(template, &placeholders, Path::new("post.md"), markdown, &options).expect("to render markdown");
+        assert_eq!(rendered.html_doc, "<h1>Post</h1><small>Jane</small><h1>Post</h1>\n<p>Some content.</p>");
+
+        // The same as when the meta section comes first.
+        let markdown = ":meta\nauthor = Jane\n:meta\n# Post\n\nSome content.";
+        let first = render(template, &placeholders, Path::new("post.md"), markdown, &options).expect("to render markdown");
+        assert_eq!(rendered.html_doc, first.html_doc);
+
+        // A setext title keeps its underline.
+        assert_eq!(split_meta_section("Post\n====\n<meta>\nauthor = Jane\n</meta>\nText", 1), (
+            "Post\n====\n\nText".to_string(),
+            vec![Meta::new("author", "Jane")],
+        ));
+    }
+
+    #[test]
+    fn can_also_write_plain_text() {
+        let tmp = temp_dir();
+        let dir = tmp.path();
+        fs::write(dir.join("template.html"), "<title>{{ £title }}</title>{{ £content }}").expect("to write template");
+        fs::write(dir.join("one.md"), ":meta\nauthor = Jane\n:meta\n# Tom & Jerry\n\nSome *important* text.\n\n* One\n* Two").expect("to write markdown");
+
+        let (template, markdown) = (dir.join("template.html"), dir.join("one.md"));
+        let cli = Cli::parse_from(["blogs-md-easy", "-t", template.to_str().unwrap(), "-m", markdown.to_str().unwrap(), "--allow", "unused", "--also-text"]);
+        build(&cli).expect("to build");
+
+        assert!(fs::read_to_string(dir.join("one.html")).expect("to read output").starts_with("<title>Tom & Jerry</title><h1>Tom &amp; Jerry</h1>"));
+        assert_eq!(fs::read_to_string(dir.join("one.txt")).expect("to read text"), "Tom & Jerry\n\nSome important text.\n\nOne\nTwo\n");
+
+        // A text template would be overwritten by its plain text version.
+        fs::write(dir.join("template.txt"), "{{ £title }}").expect("to write template");
+        let template = dir.join("template.txt");
+        let cli = Cli::parse_from(["blogs-md-easy", "-t", template.to_str().unwrap(), "-m", markdown.to_str().unwrap(), "--allow", "unused", "--also-text"]);
+        assert!(build(&cli).is_err());
+    }
+
+    #[test]
+    fn check_fails_without_writing() {
+        let tmp = temp_dir();
+        let dir = tmp.path();
+        fs::write(dir.join("template.html"), "<h1>{{ £title }}</h1>{{ £content }}").expect("to write template");
+        fs::write(dir.join("one.md"), "# One").expect("to write markdown");
+
+        let (template, markdown) = (dir.join("template.html"), dir.join("one.md"));
+        let args = ["blogs-md-easy", "-t", template.to_str().unwrap(), "-m", markdown.to_str().unwrap(), "--check"];
+
+        // A valid Markdown and template pass, but nothing is written.
+        build(&Cli::parse_from(args)).expect("to pass the check");
+        assert!(!dir.join("one.html").exists());
+
+        // A template referencing an undefined variable fails.
+        fs::write(dir.join("template.html"), "<h1>{{ £title }}</h1><p>{{ £author }}</p>{{ £content }}").expect("to write template");
+        let error = build(&Cli::parse_from(args)).expect_err("to fail the check");
+        assert_eq!(error.to_string(), "The check found 1 Markdown that failed to render, and 0 warnings.");
+
+        // As do warnings, such as an unused variable.
+        fs::write(dir.join("template.html"), "<h1>{{ £title }}</h1>").expect("to write template");
+        let error = build(&Cli::parse_from(args)).expect_err("to fail the check");
+        assert_eq!(error.to_string(), "The check found 0 Markdowns that failed to render, and 1 warning.");
+        assert!(!dir.join("one.html").exists());
+    }
+
+    #[test]
+    fn malformed_placeholder_points_at_the_error() {
+        let template = Span::new("<h1>{{ £x | }}</h1>");
+        let error = get_placeholders(template).expect_err("the filter is empty").to_string();
+        assert_eq!(error, "Expected a filter name on line 1, column 13:\n<h1>{{ £x | }}</h1>\n            ^");
+    }
+
+    #[test]
+    fn trailing_pipe_fails_the_build() {
+        let tmp = temp_dir();
+        let dir = tmp.path();
+        fs::write(dir.join("template.html"), "<h1>{{ £title | uppercase | }}</h1>").expect("to write template");
+        fs::write(dir.join("one.md"), "# One").expect("to write markdown");
+
+        let cli = Cli::parse_from([
+            "blogs-md-easy",
+            "-t", dir.join("template.html").to_str().unwrap(),
+            "-m", dir.join("one.md").to_str().unwrap(),
+        ]);
+        let error = build(&cli).expect_err("the template has an empty filter").to_string();
+
+        assert!(error.contains("Expected a filter name on line 1, column 29"), "{}", error);
+        assert!(!dir.join("one.html").exists());
+    }
+
+    #[test]
+    fn placeholders_are_in_replacement_order() {
+        // Rendering replaces from the end of the template, so the offsets of
+        // earlier placeholders stay valid. Only `parse_placeholder_locations`
+        // orders them, so this pins that contract.
+        let template = Span::new("{{ £a }}{{ £b }}\n{{ £a | uppercase }}");
+        let placeholders = get_placeholders(template).expect("to parse placeholders");
+        let offsets = placeholders.iter().map(|placeholder| placeholder.selection.start.offset).collect::<Vec<usize>>();
+
+        assert_eq!(offsets, vec![19, 9, 0]);
+    }
+
+    #[test]
+    fn can_list_filters() {
+        let cli = Cli::try_parse_from(["blogs-md-easy", "--list-filters"]).expect("to not need a template");
+        assert!(cli.list_filters);
+        assert!(Cli::try_parse_from(["blogs-md-easy", "--list-filters", "-t", "template.html"]).is_err());
+
+        let list = get_filter_list();
+        for info in FILTERS {
+            assert!(list.lines().any(|line| line.starts_with(&format!("{} ", info.name))), "'{}' to be listed", info.name);
+        }
+        assert!(list.contains("truncate - Truncate the value to a number of characters.\n    * characters - defaults to `100`\n    * trail - defaults to `...`\n"));
+        assert!(list.contains("url_encode (urlencode) - "));
+    }
+
+    #[test]
+    fn reading_time_uses_the_words_per_minute() {
+        let template = Span::new("{{ £reading_time }} min");
+        let placeholders = get_placeholders(template).unwrap();
+        let markdown = "# Title\n\n".to_string() + &"word ".repeat(450);
+
+        let rendered = render(template, &placeholders, Path::new("test.md"), &markdown, &RenderOptions::default()).unwrap();
+        assert_eq!(rendered.html_doc, "3 min");
+
+        let options = RenderOptions { words_per_minute: 100, ..Default::default() };
+        let rendered = render(template, &placeholders, Path::new("test.md"), &markdown, &options).unwrap();
+        assert_eq!(rendered.html_doc, "5 min");
+
+        // A declared value is left alone.
+        let markdown = ":meta\nreading_time = 10\n:meta\n".to_string() + &markdown;
+        let rendered = render(template, &placeholders, Path::new("test.md"), &markdown, &options).unwrap();
+        assert_eq!(rendered.html_doc, "10 min");
+    }
+
+    #[test]
+    fn raw_content_is_opt_in() {
+        let template = Span::new("{{ £content }}|{{ £content_raw }}");
+        let placeholders = get_placeholders(template).unwrap();
+        let markdown = "# Title\n\nSome **bold** text.";
+
+        let error = render(template, &placeholders, Path::new("test.md"), markdown, &RenderOptions::default()).unwrap_err();
+        assert!(error.to_string().contains("Missing variable 'content_raw'"));
+
+        let options = RenderOptions { raw_content: true, ..Default::default() };
+        let rendered = render(template, &placeholders, Path::new("test.md"), markdown, &options).unwrap();
+        let (content, content_raw) = rendered.html_doc.split_once('|').unwrap();
+        assert!(content.contains("<strong>bold</strong>"));
+        assert_eq!(content_raw, markdown);
+        assert_eq!(rendered.variables, vec!["content", "content_raw", "title"]);
+
+        // The raw copy follows the body's name.
+        let template = Span::new("{{ £body_raw }}");
+        let placeholders = get_placeholders(template).unwrap();
+        let options = RenderOptions { raw_content: true, body_name: "body".to_string(), ..Default::default() };
+        let rendered = render(template, &placeholders, Path::new("test.md"), markdown, &options).unwrap();
+        assert_eq!(rendered.html_doc, markdown);
+    }
+
+    #[test]
+    fn can_write_templates_into_subdirs() {
+        let tmp = temp_dir();
+        let dir = tmp.path();
+        fs::write(dir.join("default.html"), "<h1>{{ £title }}</h1>{{ £content }}").expect("to write template");
+        fs::write(dir.join("amp.html"), "<amp>{{ £title }}</amp>").expect("to write template");
+        fs::write(dir.join("one.md"), "# One").expect("to write markdown");
+
+        let cli = Cli::parse_from([
+            "blogs-md-easy",
+            "-t", dir.join("default.html").to_str().unwrap(), dir.join("amp.html").to_str().unwrap(),
+            "-m", dir.join("one.md").to_str().unwrap(),
+            "-o", dir.join("dist").to_str().unwrap(),
+            "--template-subdirs",
+        ]);
+        build(&cli).expect("to build");
+
+        assert_eq!(fs::read_to_string(dir.join("dist/amp/one.html")).expect("to read output"), "<amp>One</amp>");
+        assert!(fs::read_to_string(dir.join("dist/default/one.html")).expect("to read output").starts_with("<h1>One</h1>"));
+        assert!(!dir.join("dist/amp-one.html").exists());
+
+        // The template's directory is at the root of the output directory, with
+        // the Markdown's directories beneath it.
+        let cli = Cli::parse_from(["blogs-md-easy", "-t", "amp.html", "-m", "posts/2024/one.md", "-o", "dist", "-b", "posts", "--template-subdirs"]);
+        let output_path = get_output_path(&cli, Path::new("amp.html"), Path::new("posts/2024/one.md"), true).expect("to get output path");
+        assert_eq!(output_path, PathBuf::from("dist/amp/2024/one.html"));
+
+        let cli = Cli::parse_from(["blogs-md-easy", "-t", "amp.html", "-m", "posts/one.md", "-o", "dist", "-p", "--template-subdirs"]);
+        let output_path = get_output_path(&cli, Path::new("amp.html"), Path::new("posts/one.md"), true).expect("to get output path");
+        assert_eq!(output_path, PathBuf::from("dist/amp/posts/one.html"));
+
+        // Without an output directory, it is next to the Markdown.
+        let cli = Cli::parse_from(["blogs-md-easy", "-t", "amp.html", "-m", "posts/one.md", "--template-subdirs"]);
+        let output_path = get_output_path(&cli, Path::new("amp.html"), Path::new("posts/one.md"), true).expect("to get output path");
+        assert_eq!(output_path, PathBuf::from("posts/amp/one.html"));
+
+        // A single template has nothing to separate.
+        let cli = Cli::parse_from(["blogs-md-easy", "-t", "page.html", "-m", "posts/one.md", "--template-subdirs"]);
+        let output_path = get_output_path(&cli, Path::new("page.html"), Path::new("posts/one.md"), false).expect("to get output path");
+        assert_eq!(output_path, PathBuf::from("posts/one.html"));
+    }
+
+    #[test]
+    fn can_strip_title_from_content() {
+        let template = Span::new("<title>{{ £title }}</title>{{ £content }}");
+        let placeholders = get_placeholders(template).unwrap();
+        let markdown = "# Markdown Title\n\nSome content.";
+
+        let rendered = render(template, &placeholders, Path::new("test.md"), markdown, &RenderOptions::default()).unwrap();
+        assert_eq!(rendered.content, "# Markdown Title\n\nSome content.");
+        assert!(rendered.html_doc.contains("<h1>Markdown Title</h1>"));
+
+        let options = RenderOptions { strip_title_from_content: true, ..Default::default() };
+        let rendered = render(template, &placeholders, Path::new("test.md"), markdown, &options).unwrap();
+        assert_eq!(rendered.content, "Some content.");
+        assert!(rendered.html_doc.starts_with("<title>Markdown Title</title>"));
+        assert!(!rendered.html_doc.contains("<h1>"));
+
+        // The heading of another level, or in HTML, is stripped too.
+        let options = RenderOptions { strip_title_from_content: true, title_level: 2, ..Default::default() };
+        let rendered = render(template, &placeholders, Path::new("test.md"), "<h2>HTML Title</h2>\nBody", &options).unwrap();
+        assert_eq!(rendered.content, "Body");
+    }
+
+    #[test]
+    fn incremental_builds_keep_the_manifest() {
+        let tmp = temp_dir();
+        let dir = tmp.path();
+        fs::write(dir.join("template.html"), "<h1>{{ £title }}</h1>").expect("to write template");
+        fs::write(dir.join("one.md"), "# One").expect("to write markdown");
+        fs::write(dir.join("two.md"), "# Two").expect("to write markdown");
+
+        let (template, manifest) = (dir.join("template.html"), dir.join("manifest.json"));
+        let (one, two) = (dir.join("one.md"), dir.join("two.md"));
+        let args = [
+            "blogs-md-easy",
+            "-t", template.to_str().unwrap(),
+            "-m", one.to_str().unwrap(), two.to_str().unwrap(),
+            "--allow", "unused",
+            "--incremental",
+            "--manifest", manifest.to_str().unwrap(),
+        ];
+        let outputs = || {
+            let manifest: serde_json::Value = serde_json::from_str(&fs::read_to_string(&manifest).expect("to read manifest")).expect("to parse manifest");
+            manifest.as_array().expect("an array of entries").iter()
+                .map(|entry| entry["output"].as_str().expect("an output").to_string())
+                .collect::<Vec<String>>()
+        };
+        let expected = vec![dir.join("one.html").to_string_lossy().to_string(), dir.join("two.html").to_string_lossy().to_string()];
+
+        build(&Cli::parse_from(args)).expect("to build");
+        assert_eq!(outputs(), expected);
+
+        // Both outputs are skipped, but are still listed.
+        fs::write(dir.join("one.html"), "Left alone").expect("to write output");
+        build(&Cli::parse_from(args)).expect("to build");
+        assert_eq!(fs::read_to_string(dir.join("one.html")).expect("to read output"), "Left alone");
+        assert_eq!(outputs(), expected);
+
+        // Only one is rendered again, and the other is kept.
+        let touched = SystemTime::now() + Duration::from_secs(60);
+        fs::File::options().write(true).open(&two).expect("to open markdown")
+            .set_modified(touched).expect("to touch markdown");
+        build(&Cli::parse_from(args)).expect("to build");
+        assert_eq!(outputs(), expected);
+
+        // Without a previous entry, an up to date output is rendered again, so
+        // that it can be listed.
+        fs::remove_file(&manifest).expect("to remove manifest");
+        build(&Cli::parse_from(args)).expect("to build");
+        assert_eq!(fs::read_to_string(dir.join("one.html")).expect("to read output"), "<h1>One</h1>");
+        assert_eq!(outputs(), expected);
+
+    }
+
+    #[test]
+    fn sources_follow_the_template_includes() {
+        let tmp = temp_dir();
+        let dir = tmp.path();
+        fs::create_dir_all(dir.join("partials")).expect("to create directories");
+        fs::write(dir.join("page.html"), "{{ #include partials/header.html }}{{ £content }}").expect("to write template");
+        fs::write(dir.join("partials/header.html"), "<h1>{{ £title }}</h1>").expect("to write partial");
+        fs::write(dir.join("partials/footer.html"), "<footer></footer>").expect("to write partial");
+        fs::write(dir.join("one.md"), "# One").expect("to write markdown");
+
+        let cli = Cli::parse_from([
+            "blogs-md-easy",
+            "-t", dir.join("page.html").to_str().unwrap(),
+            "-m", dir.join("one.md").to_str().unwrap(),
+        ]);
+        let footer = dir.join("partials/footer.html").canonicalize().expect("to canonicalize");
+        let sources = get_sources(&cli).expect("to get sources");
+        assert_eq!(sources.len(), 3);
+        assert!(sources.contains(&dir.join("partials/header.html").canonicalize().expect("to canonicalize")));
+        assert!(!sources.contains(&footer));
+
+        // A newly included file is a source once the template is read again.
+        fs::write(dir.join("page.html"), "{{ £content }}{{ #include partials/footer.html }}").expect("to write template");
+        let sources = get_sources(&cli).expect("to get sources");
+        assert_eq!(sources.len(), 3);
+        assert!(sources.contains(&footer));
+    }
 }
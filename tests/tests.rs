@@ -1,6 +1,6 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, fs, process::Command};
 
-use blogs_md_easy::{create_variables, parse_filter, parse_filter_args, parse_filter_key_value, parse_filters, parse_meta_comment, parse_meta_key_value, parse_meta_section, parse_placeholder, parse_placeholder_locations, parse_title, parse_until_eol, parse_variable, render_filter, replace_substring, Filter, Marker, Meta, Selection, Span, TextCase};
+use blogs_md_easy::{add_heading_ids, create_variables, escape_html, escape_xml, find_placeholder_error, format_number, highlight_code_blocks, minify_html, parse_conditional_locations, parse_filter, parse_filter_args, parse_filter_key_value, parse_filters, parse_loop_locations, parse_meta_after_title, parse_meta_comment, parse_meta_key_value, parse_meta_section, parse_placeholder, parse_placeholder_locations, parse_placeholder_locations_in_order, parse_placeholder_with_unknown_arguments, parse_title, parse_until_eol, parse_variable, reading_time, render_filter, render_filter_with_variables, replace_substring, slugify, strip_markdown, template_placeholders, template_variables, Filter, Marker, Meta, FILTERS, RoundingMode, Selection, Span, TextCase};
 use nom::combinator::opt;
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -20,6 +20,48 @@ fn can_parse_until_eol() {
     assert_eq!(input.fragment(), &"");
 }
 
+#[test]
+fn can_parse_until_crlf() {
+    let input = Span::new("First line\r\nSecond\rline\r\n\r\nFourth line\r");
+    let (input, line) = parse_until_eol(input).expect("to parse line");
+    assert_eq!(line.fragment(), &"First line");
+    assert_eq!(input.fragment(), &"Second\rline\r\n\r\nFourth line\r");
+
+    // A lone carriage return is kept, as it isn't a line ending.
+    let (input, line) = parse_until_eol(input).expect("to parse line");
+    assert_eq!(line.fragment(), &"Second\rline");
+
+    let (input, line) = parse_until_eol(input).expect("to parse line");
+    assert_eq!(line.fragment(), &"");
+
+    // Nor is one at the very end.
+    let (input, line) = parse_until_eol(input).expect("to parse line");
+    assert_eq!(line.fragment(), &"Fourth line");
+    assert_eq!(input.fragment(), &"");
+}
+
+#[test]
+fn can_parse_meta_with_crlf() {
+    let input = Span::new(":meta\r\n// A comment\r\nauthor = John Doe\r\ntags = \"one\r\ntwo\"\r\n:meta\r\n# Markdown title\r\nContent");
+
+    let (_, comment) = parse_meta_comment(Span::new("// A comment\r\n")).expect("to parse comment");
+    assert_eq!(comment.fragment(), &"A comment");
+
+    let (_, meta) = parse_meta_key_value(Span::new("author = John Doe\r\nnext")).expect("to parse key value");
+    assert_eq!(meta.value, "John Doe");
+    assert_eq!(meta.selection.end.offset, 17);
+
+    let (markdown, meta_values) = parse_meta_section(input).expect("to parse meta section");
+    assert_eq!(meta_values, vec![
+        Meta::new("author", "John Doe"),
+        Meta::new("tags", "one\r\ntwo"),
+    ]);
+    assert!(meta_values.iter().all(|meta| !meta.value.ends_with('\r')));
+
+    let (_, title) = parse_title(markdown, 1).expect("to parse title");
+    assert_eq!(title.fragment(), &"Markdown title");
+}
+
 #[test]
 fn can_parse_variable() {
     let input = Span::new("£content }}");
@@ -111,7 +153,7 @@ fn can_parse_placeholder() {
 #[test]
 fn can_parse_md_title() {
     let markdown = Span::new("# My Title\nMy content");
-    let parsed_title = parse_title(markdown);
+    let parsed_title = parse_title(markdown, 1);
 
     assert!(parsed_title.is_ok());
 
@@ -124,7 +166,7 @@ fn can_parse_md_title() {
 fn can_parse_html_title() {
     // Deliberately include spaces at the start of this line.
     let markdown = Span::new("    <h1>My Title</h1>\nMy content");
-    let parsed_title = parse_title(markdown);
+    let parsed_title = parse_title(markdown, 1);
 
     assert!(parsed_title.is_ok());
 
@@ -133,6 +175,63 @@ fn can_parse_html_title() {
     assert_eq!(input.fragment(), &"\nMy content");
 }
 
+#[test]
+fn can_parse_setext_title() {
+    let markdown = Span::new("My Title\n===\nMy content");
+    let parsed_title = parse_title(markdown, 1);
+
+    assert!(parsed_title.is_ok());
+
+    let (input, title) = parsed_title.unwrap();
+    assert_eq!(title.fragment(), &"My Title");
+    assert_eq!(input.fragment(), &"\nMy content");
+
+    // The underline may be indented, followed by spaces, or end the document.
+    let (_, title) = parse_title(Span::new("\r\nMy Title\r\n  ====  "), 1).expect("to parse title");
+    assert_eq!(title.fragment(), &"My Title");
+}
+
+#[test]
+fn cannot_parse_setext_title_from_other_lines() {
+    // A level two setext heading, or a paragraph followed by a rule.
+    assert!(parse_title(Span::new("My Title\n---\nMy content"), 1).is_err());
+    // Horizontal rules are not titles.
+    assert!(parse_title(Span::new("***\n==="), 1).is_err());
+    assert!(parse_title(Span::new("- - -\n==="), 1).is_err());
+    // The underline must only contain `=`.
+    assert!(parse_title(Span::new("My Title\n=== not a title"), 1).is_err());
+    // The underline must directly follow the title.
+    assert!(parse_title(Span::new("My Title\n\n==="), 1).is_err());
+}
+
+#[test]
+fn can_parse_title_from_other_levels() {
+    let markdown = Span::new("## Sub\nMy content");
+    let (input, title) = parse_title(markdown, 2).expect("to parse title");
+    assert_eq!(title.fragment(), &"Sub");
+    assert_eq!(input.fragment(), &"\nMy content");
+
+    let (_, title) = parse_title(Span::new("<h2>Sub</h2>"), 2).expect("to parse title");
+    assert_eq!(title.fragment(), &"Sub");
+    let (_, title) = parse_title(Span::new("Sub\n---"), 2).expect("to parse title");
+    assert_eq!(title.fragment(), &"Sub");
+    let (_, title) = parse_title(Span::new("###### Sub"), 6).expect("to parse title");
+    assert_eq!(title.fragment(), &"Sub");
+
+    // Only the requested level is the title.
+    assert!(parse_title(Span::new("# Sub"), 2).is_err());
+    assert!(parse_title(Span::new("### Sub"), 2).is_err());
+    assert!(parse_title(Span::new("Sub\n==="), 2).is_err());
+    assert!(parse_title(Span::new("<h1>Sub</h1>"), 2).is_err());
+    // There is no seventh heading level.
+    assert!(parse_title(Span::new("####### Sub"), 7).is_err());
+
+    let markdown = Span::new("## Sub\nMy content");
+    let variables = create_variables(markdown, vec![], 2).expect("to create variables");
+    assert_eq!(variables.get("title").unwrap(), "Sub");
+    assert!(create_variables(markdown, vec![], 1).is_err());
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // Meta Section
 
@@ -262,6 +361,25 @@ here""#);
     assert_eq!(meta, Meta::new("key", r#"I said \"John Doe\""#));
 }
 
+#[test]
+fn meta_values_record_their_selection() {
+    let markdown = Span::new(":meta\nauthor = John Doe\n£description = \"Two\nlines\"\n:meta\n# Title");
+    let (_, meta) = parse_meta_section(markdown).expect("to parse meta section");
+
+    assert_eq!(meta.len(), 2);
+    assert_eq!(meta[0].key, "author");
+    assert_eq!(meta[0].selection, Selection {
+        start: Marker { line: 2, offset: 6 },
+        end: Marker { line: 2, offset: 23 },
+    });
+    // A quoted value can end on a later line.
+    assert_eq!(meta[1].key, "description");
+    assert_eq!(meta[1].selection, Selection {
+        start: Marker { line: 3, offset: 24 },
+        end: Marker { line: 4, offset: 51 },
+    });
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // Placeholders
 
@@ -358,6 +476,36 @@ fn can_parse_two_placeholder_filters() {
     assert_eq!(placeholders[0].filters, vec![Filter::Text { case: TextCase::Upper }, Filter::Text { case: TextCase::Lower }]);
 }
 
+#[test]
+fn can_list_template_variables() {
+    let template = "<title>{{ £title }}</title>\n<h1>{{ £title | uppercase }}</h1>\n{{ £content }}";
+    assert_eq!(template_variables(template), vec!["title".to_string(), "content".to_string()]);
+
+    // Every placeholder is kept, along with its filters.
+    let placeholders = template_placeholders(template);
+    assert_eq!(placeholders.iter().map(|p| p.name.as_str()).collect::<Vec<&str>>(), vec!["title", "title", "content"]);
+    assert_eq!(placeholders[0].filters, vec![]);
+    assert_eq!(placeholders[1].filters, vec![Filter::Text { case: TextCase::Upper }]);
+    assert_eq!(placeholders[1].selection.start.line, 2);
+
+    assert!(template_variables("<p>No placeholders</p>").is_empty());
+}
+
+#[test]
+fn placeholder_applies_filters_in_order() {
+    let input = Span::new("{{ £tags | split = \",\" | join = \" and \" | uppercase }}");
+    let (_, placeholder) = parse_placeholder(input).expect("to parse placeholder");
+    let value = "rust,cli".to_string();
+
+    let folded = placeholder.filters.iter().fold(value.clone(), render_filter);
+    assert_eq!(placeholder.apply(value), folded);
+    assert_eq!(folded, "RUST AND CLI");
+
+    // A placeholder without filters leaves the value unchanged.
+    let (_, placeholder) = parse_placeholder(Span::new("{{ £tags }}")).expect("to parse placeholder");
+    assert_eq!(placeholder.apply("rust,cli".to_string()), "rust,cli");
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // Filters
 
@@ -400,7 +548,7 @@ fn can_parse_filter_with_args() {
     assert_eq!(input.fragment(), &"");
     assert!(matches!(filter, Filter::Truncate { .. }));
 
-    if let Filter::Truncate { characters, trail } = filter {
+    if let Filter::Truncate { characters, trail, .. } = filter {
         assert_eq!(characters, 15);
         assert_eq!(trail, "...");
     }
@@ -414,7 +562,7 @@ fn can_parse_filter_with_defaults() {
     assert_eq!(input.fragment(), &"");
     assert!(matches!(filter, Filter::Truncate { .. }));
 
-    if let Filter::Truncate { characters, trail } = filter {
+    if let Filter::Truncate { characters, trail, .. } = filter {
         assert_eq!(characters, 100);
         assert_eq!(trail, "...");
     }
@@ -428,7 +576,7 @@ fn can_parse_filter_with_just_value() {
     assert_eq!(input.fragment(), &"");
     assert!(matches!(filter, Filter::Truncate { .. }));
 
-    if let Filter::Truncate { characters, trail } = filter {
+    if let Filter::Truncate { characters, trail, .. } = filter {
         assert_eq!(characters, 15);
         assert_eq!(trail, "...");
     }
@@ -442,7 +590,7 @@ fn can_parse_filter_with_args_not_provided() {
     assert_eq!(input.fragment(), &"");
     assert!(matches!(filter, Filter::Truncate { .. }));
 
-    if let Filter::Truncate { characters, trail } = filter {
+    if let Filter::Truncate { characters, trail, .. } = filter {
         assert_eq!(characters, 100);
         assert_eq!(trail, "...");
     }
@@ -460,7 +608,7 @@ fn can_parse_two_filters() {
     assert!(matches!(filters[0], Filter::Truncate { .. }));
     assert!(matches!(filters[1], Filter::Text { case: TextCase::Lower }));
 
-    if let Filter::Truncate { characters, trail } = &filters[0] {
+    if let Filter::Truncate { characters, trail, .. } = &filters[0] {
         assert_eq!(characters, &20);
         assert_eq!(trail, "...");
     }
@@ -471,9 +619,17 @@ fn can_parse_all_filters() {
     // We need this test that we don't forget to create match the string to the
     // filter.
     let filters: Vec<(Filter, Filter)> = vec![
+        (Filter::Bool { truthy: "Yes".to_string(), falsy: "No".to_string() }, parse_filter(Span::new("bool = truthy: Yes, falsy: No")).expect("bool").1),
+        (Filter::IsEmpty, parse_filter(Span::new("is_empty")).expect("is_empty").1),
+        (Filter::IsNumeric, parse_filter(Span::new("is_numeric")).expect("is_numeric").1),
+
         (Filter::Ceil, parse_filter(Span::new("ceil")).expect("ceil").1),
+        (Filter::FileSize { binary: true }, parse_filter(Span::new("filesize = binary: true")).expect("filesize").1),
         (Filter::Floor, parse_filter(Span::new("floor")).expect("floor").1),
-        (Filter::Round { precision: 3 }, parse_filter(Span::new("round = 3")).expect("round").1),
+        (Filter::Max { value: 100.0 }, parse_filter(Span::new("max = 100")).expect("max").1),
+        (Filter::Min { value: -2.5 }, parse_filter(Span::new("min = value: -2.5")).expect("min").1),
+        (Filter::Ordinal, parse_filter(Span::new("ordinal")).expect("ordinal").1),
+        (Filter::Round { precision: 3, mode: RoundingMode::HalfUp }, parse_filter(Span::new("round = 3")).expect("round").1),
 
         // Lower case and uppercase have aliased filters...
         (Filter::Text { case: TextCase::Lower }, parse_filter(Span::new("lowercase")).expect("lower").1),
@@ -485,20 +641,62 @@ fn can_parse_all_filters() {
         (Filter::Text { case: TextCase::Pascal }, parse_filter(Span::new("text = PascalCase")).expect("pascal").1),
         (Filter::Text { case: TextCase::Camel }, parse_filter(Span::new("text = camelCase")).expect("camel").1),
         (Filter::Text { case: TextCase::Invert }, parse_filter(Span::new("text = invert")).expect("invert").1),
-        (Filter::Markdown, parse_filter(Span::new("markdown")).expect("markdown").1),
-        (Filter::Replace { find: "".to_string(), replacement: "".to_string(), limit: None }, parse_filter(Span::new("replace")).expect("replace").1),
+        (Filter::AfterMore { marker: "<!-- more -->".to_string() }, parse_filter(Span::new("after_more")).expect("after_more").1),
+        (Filter::Base64Encode, parse_filter(Span::new("base64_encode")).expect("base64_encode").1),
+        (Filter::Base64Decode, parse_filter(Span::new("base64_decode")).expect("base64_decode").1),
+        (Filter::Fallback { key: "title".to_string() }, parse_filter(Span::new("fallback = £title")).expect("fallback").1),
+        (Filter::First { separator: "\n".to_string() }, parse_filter(Span::new("first")).expect("first").1),
+        (Filter::Join { separator: ", ".to_string() }, parse_filter(Span::new("join")).expect("join").1),
+        (Filter::Last { separator: "\n".to_string() }, parse_filter(Span::new("last")).expect("last").1),
+        (Filter::Markdown { allow_html: true, gfm: false, anchors: false, highlight: false }, parse_filter(Span::new("markdown")).expect("markdown").1),
+        (Filter::RegexReplace { pattern: "a".to_string(), replacement: "".to_string(), limit: None, ignore_case: false }, parse_filter(Span::new("replace = a, regex: true")).expect("regex replace").1),
+        (Filter::Replace { find: "".to_string(), replacement: "".to_string(), limit: None, ignore_case: false }, parse_filter(Span::new("replace")).expect("replace").1),
         (Filter::Reverse, parse_filter(Span::new("reverse")).expect("reverse").1),
-        (Filter::Truncate { characters: 100, trail: "...".to_string() }, parse_filter(Span::new("truncate")).expect("truncate").1),
+        (Filter::Slice { start: 0, end: Some(8) }, parse_filter(Span::new("slice = start: 0, end: 8")).expect("slice").1),
+        (Filter::Split { separator: ",".to_string() }, parse_filter(Span::new("split")).expect("split").1),
+        (Filter::FirstParagraph, parse_filter(Span::new("excerpt")).expect("excerpt").1),
+        (Filter::Format { template: "Hello, {}!".to_string() }, parse_filter(Span::new("fmt = \"Hello, {}!\"")).expect("fmt").1),
+        (Filter::Highlight { term: "rust".to_string(), tag: "mark".to_string() }, parse_filter(Span::new("highlight = term: rust, tag: mark")).expect("highlight").1),
+        (Filter::Indent { spaces: 4, first: true }, parse_filter(Span::new("indent")).expect("indent").1),
+        (Filter::Truncate { characters: 100, trail: "...".to_string(), safe: false }, parse_filter(Span::new("truncate")).expect("truncate").1),
+        (Filter::Unformat { separator: " ".to_string() }, parse_filter(Span::new("unformat = \" \"")).expect("unformat").1),
+        (Filter::UrlEncode, parse_filter(Span::new("url_encode")).expect("url_encode").1),
+        (Filter::UrlDecode, parse_filter(Span::new("url_decode")).expect("url_decode").1),
+        (Filter::JsonEscape, parse_filter(Span::new("json_escape")).expect("json_escape").1),
+        (Filter::CollapseWhitespace, parse_filter(Span::new("collapse")).expect("collapse").1),
+        (Filter::Count { needle: "".to_string() }, parse_filter(Span::new("count")).expect("count").1),
+        (Filter::Remove { text: "".to_string(), limit: None }, parse_filter(Span::new("remove")).expect("remove").1),
+        (Filter::TimeAgo { from: Some("2024-03-10".to_string()) }, parse_filter(Span::new("timeago = 2024-03-10")).expect("timeago").1),
+        (Filter::TrimLines, parse_filter(Span::new("trim_lines")).expect("trim_lines").1),
+        (Filter::Spaceless, parse_filter(Span::new("spaceless")).expect("spaceless").1),
+        (Filter::Dedent, parse_filter(Span::new("dedent")).expect("dedent").1),
+        (Filter::EscapeXml, parse_filter(Span::new("escape_xml")).expect("escape_xml").1),
+        (Filter::BeforeMore { marker: "<!--cut-->".to_string() }, parse_filter(Span::new("before_more = marker: <!--cut-->")).expect("before_more").1),
+        (Filter::Cdata, parse_filter(Span::new("cdata")).expect("cdata").1),
+        (Filter::TruncateMiddle { characters: 20, trail: "…".to_string() }, parse_filter(Span::new("truncate_middle = characters: 20")).expect("truncate_middle").1),
+        (Filter::Typography, parse_filter(Span::new("typography")).expect("typography").1),
+        (Filter::UrlJoin { base: "https://example.com".to_string() }, parse_filter(Span::new("url_join = https://example.com")).expect("url_join").1),
+        (Filter::Wrap { before: "".to_string(), after: "".to_string() }, parse_filter(Span::new("wrap")).expect("wrap").1),
+        (Filter::WrapAt { width: 72, break_long_words: true }, parse_filter(Span::new("wrap_at = width: 72, break_long_words: true")).expect("wrap_at").1),
     ];
 
     // Maybe a bit verbose, but this ensures that the compiler will catch new
     // filters immediately.
     for (expected_filter, actual_filter) in filters {
         match actual_filter {
+            // Boolean filters.
+            Filter::Bool { truthy, falsy } => assert_eq!(expected_filter, Filter::Bool { truthy, falsy }),
+            Filter::IsEmpty => assert_eq!(expected_filter, Filter::IsEmpty),
+            Filter::IsNumeric => assert_eq!(expected_filter, Filter::IsNumeric),
+
             // Maths filters.
             Filter::Ceil => assert_eq!(expected_filter, Filter::Ceil),
+            Filter::FileSize { binary } => assert_eq!(expected_filter, Filter::FileSize { binary }),
             Filter::Floor => assert_eq!(expected_filter, Filter::Floor),
-            Filter::Round { precision } => assert_eq!(expected_filter, Filter::Round { precision }),
+            Filter::Max { value } => assert_eq!(expected_filter, Filter::Max { value }),
+            Filter::Min { value } => assert_eq!(expected_filter, Filter::Min { value }),
+            Filter::Ordinal => assert_eq!(expected_filter, Filter::Ordinal),
+            Filter::Round { precision, mode } => assert_eq!(expected_filter, Filter::Round { precision, mode }),
 
             // String filters.
             Filter::Text { case: TextCase::Lower } => assert_eq!(expected_filter, Filter::Text { case: TextCase::Lower }),
@@ -509,10 +707,43 @@ fn can_parse_all_filters() {
             Filter::Text { case: TextCase::Pascal } => assert_eq!(expected_filter, Filter::Text { case: TextCase::Pascal }),
             Filter::Text { case: TextCase::Camel } => assert_eq!(expected_filter, Filter::Text { case: TextCase::Camel }),
             Filter::Text { case: TextCase::Invert } => assert_eq!(expected_filter, Filter::Text { case: TextCase::Invert }),
-            Filter::Markdown => assert_eq!(expected_filter, Filter::Markdown),
-            Filter::Replace { find, replacement, limit } => assert_eq!(expected_filter, Filter::Replace { find, replacement, limit }),
+            Filter::AfterMore { marker } => assert_eq!(expected_filter, Filter::AfterMore { marker }),
+            Filter::Base64Encode => assert_eq!(expected_filter, Filter::Base64Encode),
+            Filter::Base64Decode => assert_eq!(expected_filter, Filter::Base64Decode),
+            Filter::Fallback { key } => assert_eq!(expected_filter, Filter::Fallback { key }),
+            Filter::First { separator } => assert_eq!(expected_filter, Filter::First { separator }),
+            Filter::Join { separator } => assert_eq!(expected_filter, Filter::Join { separator }),
+            Filter::Last { separator } => assert_eq!(expected_filter, Filter::Last { separator }),
+            Filter::Markdown { .. } => assert_eq!(expected_filter, Filter::Markdown { allow_html: true, gfm: false, anchors: false, highlight: false }),
+            Filter::RegexReplace { pattern, replacement, limit, ignore_case } => assert_eq!(expected_filter, Filter::RegexReplace { pattern, replacement, limit, ignore_case }),
+            Filter::Replace { find, replacement, limit, ignore_case } => assert_eq!(expected_filter, Filter::Replace { find, replacement, limit, ignore_case }),
             Filter::Reverse => assert_eq!(expected_filter, Filter::Reverse),
-            Filter::Truncate { characters, trail } => assert_eq!(expected_filter, Filter::Truncate { characters, trail })
+            Filter::Slice { start, end } => assert_eq!(expected_filter, Filter::Slice { start, end }),
+            Filter::Split { separator } => assert_eq!(expected_filter, Filter::Split { separator }),
+            Filter::FirstParagraph => assert_eq!(expected_filter, Filter::FirstParagraph),
+            Filter::Format { template } => assert_eq!(expected_filter, Filter::Format { template }),
+            Filter::Highlight { term, tag } => assert_eq!(expected_filter, Filter::Highlight { term, tag }),
+            Filter::Indent { .. } => assert_eq!(expected_filter, Filter::Indent { spaces: 4, first: true }),
+            Filter::Truncate { characters, trail, safe } => assert_eq!(expected_filter, Filter::Truncate { characters, trail, safe }),
+            Filter::Unformat { separator } => assert_eq!(expected_filter, Filter::Unformat { separator }),
+            Filter::UrlEncode => assert_eq!(expected_filter, Filter::UrlEncode),
+            Filter::UrlDecode => assert_eq!(expected_filter, Filter::UrlDecode),
+            Filter::JsonEscape => assert_eq!(expected_filter, Filter::JsonEscape),
+            Filter::CollapseWhitespace => assert_eq!(expected_filter, Filter::CollapseWhitespace),
+            Filter::Count { needle } => assert_eq!(expected_filter, Filter::Count { needle }),
+            Filter::Remove { text, limit } => assert_eq!(expected_filter, Filter::Remove { text, limit }),
+            Filter::TimeAgo { from } => assert_eq!(expected_filter, Filter::TimeAgo { from }),
+            Filter::TrimLines => assert_eq!(expected_filter, Filter::TrimLines),
+            Filter::Spaceless => assert_eq!(expected_filter, Filter::Spaceless),
+            Filter::Dedent => assert_eq!(expected_filter, Filter::Dedent),
+            Filter::EscapeXml => assert_eq!(expected_filter, Filter::EscapeXml),
+            Filter::BeforeMore { marker } => assert_eq!(expected_filter, Filter::BeforeMore { marker }),
+            Filter::Cdata => assert_eq!(expected_filter, Filter::Cdata),
+            Filter::TruncateMiddle { characters, trail } => assert_eq!(expected_filter, Filter::TruncateMiddle { characters, trail }),
+            Filter::Typography => assert_eq!(expected_filter, Filter::Typography),
+            Filter::UrlJoin { base } => assert_eq!(expected_filter, Filter::UrlJoin { base }),
+            Filter::Wrap { before, after } => assert_eq!(expected_filter, Filter::Wrap { before, after }),
+            Filter::WrapAt { width, break_long_words } => assert_eq!(expected_filter, Filter::WrapAt { width, break_long_words }),
         }
     }
 }
@@ -539,23 +770,82 @@ fn filter_floor_works() {
     assert_eq!(output, "1");
 }
 
+#[test]
+fn maths_filters_format_numbers_consistently() {
+    let round = Filter::Round { precision: 0, mode: RoundingMode::HalfUp };
+    let round_two = Filter::Round { precision: 2, mode: RoundingMode::HalfUp };
+
+    // Whole results never have a decimal point.
+    assert_eq!(render_filter("4".to_string(), &Filter::Ceil), "4");
+    assert_eq!(render_filter("4.0".to_string(), &Filter::Ceil), "4");
+    assert_eq!(render_filter("4.0".to_string(), &Filter::Floor), "4");
+    assert_eq!(render_filter("4.0".to_string(), &round), "4");
+    assert_eq!(render_filter("4.001".to_string(), &round_two), "4");
+
+    // Fractional results use the fewest decimal places.
+    assert_eq!(render_filter("1.50".to_string(), &round_two), "1.5");
+    assert_eq!(render_filter("1.255".to_string(), &round_two), "1.25");
+
+    // Negative zero is not printed with a sign.
+    assert_eq!(render_filter("-0.4".to_string(), &Filter::Ceil), "0");
+    assert_eq!(render_filter("-0.4".to_string(), &round), "0");
+
+    assert_eq!(format_number(12.0), "12");
+    assert_eq!(format_number(0.25), "0.25");
+}
+
 #[test]
 fn filter_round_works() {
     let input = "-1.23456789".to_string();
-    let output = render_filter(input, &Filter::Round { precision: 3 });
+    let output = render_filter(input, &Filter::Round { precision: 3, mode: RoundingMode::HalfUp });
     assert_eq!(output, "-1.235");
 
     let input = "1.23456789".to_string();
-    let output = render_filter(input, &Filter::Round { precision: 3 });
+    let output = render_filter(input, &Filter::Round { precision: 3, mode: RoundingMode::HalfUp });
     assert_eq!(output, "1.235");
 
     let input = "1.23456789".to_string();
-    let output = render_filter(input, &Filter::Round { precision: 0 });
+    let output = render_filter(input, &Filter::Round { precision: 0, mode: RoundingMode::HalfUp });
     assert_eq!(output, "1");
 
     let input = "9.87654321".to_string();
-    let output = render_filter(input, &Filter::Round { precision: 0 });
+    let output = render_filter(input, &Filter::Round { precision: 0, mode: RoundingMode::HalfUp });
     assert_eq!(output, "10");
+
+    let input = "1234".to_string();
+    let output = render_filter(input, &Filter::Round { precision: -2, mode: RoundingMode::HalfUp });
+    assert_eq!(output, "1200");
+
+    let input = "-1250.5".to_string();
+    let output = render_filter(input, &Filter::Round { precision: -1, mode: RoundingMode::HalfUp });
+    assert_eq!(output, "-1250");
+}
+
+#[test]
+fn filter_round_modes_work() {
+    let round = |input: &str, mode: RoundingMode| {
+        render_filter(input.to_string(), &Filter::Round { precision: 0, mode })
+    };
+
+    assert_eq!(round("0.5", RoundingMode::HalfUp), "1");
+    assert_eq!(round("1.5", RoundingMode::HalfUp), "2");
+    assert_eq!(round("2.5", RoundingMode::HalfUp), "3");
+
+    assert_eq!(round("0.5", RoundingMode::HalfDown), "0");
+    assert_eq!(round("1.5", RoundingMode::HalfDown), "1");
+    assert_eq!(round("2.5", RoundingMode::HalfDown), "2");
+
+    assert_eq!(round("0.5", RoundingMode::HalfEven), "0");
+    assert_eq!(round("1.5", RoundingMode::HalfEven), "2");
+    assert_eq!(round("2.5", RoundingMode::HalfEven), "2");
+
+    // Values that are not a half are rounded to the nearest, in every mode.
+    assert_eq!(round("2.6", RoundingMode::HalfDown), "3");
+    assert_eq!(round("2.4", RoundingMode::HalfEven), "2");
+
+    // Modes also apply before the decimal point.
+    let filter = Filter::Round { precision: -1, mode: RoundingMode::HalfEven };
+    assert_eq!(render_filter("25".to_string(), &filter), "20");
 }
 
 #[test]
@@ -564,19 +854,31 @@ fn can_parse_round_filter() {
     let input = Span::new("| round");
     let (_, filters) = parse_filters(input).expect("parse no arguments");
     assert_eq!(filters.len(), 1);
-    assert_eq!(filters[0], Filter::Round { precision: 0 });
+    assert_eq!(filters[0], Filter::Round { precision: 0, mode: RoundingMode::HalfUp });
 
     // Providing the default argument.
     let input = Span::new("| round = 3");
     let (_, filters) = parse_filters(input).expect("parse no arguments");
     assert_eq!(filters.len(), 1);
-    assert_eq!(filters[0], Filter::Round { precision: 3 });
+    assert_eq!(filters[0], Filter::Round { precision: 3, mode: RoundingMode::HalfUp });
 
     // Providing the named argument.
     let input = Span::new("| round = precision: 42");
     let (_, filters) = parse_filters(input).expect("parse no arguments");
     assert_eq!(filters.len(), 1);
-    assert_eq!(filters[0], Filter::Round { precision: 42 });
+    assert_eq!(filters[0], Filter::Round { precision: 42, mode: RoundingMode::HalfUp });
+
+    // Providing a negative precision.
+    let input = Span::new("| round = precision: -1");
+    let (_, filters) = parse_filters(input).expect("parse negative argument");
+    assert_eq!(filters.len(), 1);
+    assert_eq!(filters[0], Filter::Round { precision: -1, mode: RoundingMode::HalfUp });
+
+    // Providing a rounding mode.
+    let input = Span::new("| round = 2, mode: half_down");
+    let (_, filters) = parse_filters(input).expect("parse mode argument");
+    assert_eq!(filters.len(), 1);
+    assert_eq!(filters[0], Filter::Round { precision: 2, mode: RoundingMode::HalfDown });
 }
 
 #[test]
@@ -617,23 +919,141 @@ fn filter_uppercase_works() {
 #[test]
 fn filter_markdown_works() {
     let input = "# Title\nFirst _paragraph_.  \nNewline.\n\nSecond paragraph with [link](https://example.com).\n\n* Unordered list.\n\n1. Ordered list.".to_string();
-    let output = render_filter(input, &Filter::Markdown);
+    let output = render_filter(input, &Filter::Markdown { allow_html: true, gfm: false, anchors: false, highlight: false });
     assert_eq!(output, "<h1>Title</h1>\n<p>First <em>paragraph</em>.<br />\nNewline.</p>\n<p>Second paragraph with <a href=\"https://example.com\">link</a>.</p>\n<ul>\n<li>Unordered list.</li>\n</ul>\n<ol>\n<li>Ordered list.</li>\n</ol>");
 }
 
+#[test]
+fn filter_markdown_html_can_be_disabled() {
+    let input = "Hello, <em>World</em>!\n\n<div>Block</div>".to_string();
+    let output = render_filter(input.clone(), &Filter::Markdown { allow_html: true, gfm: false, anchors: false, highlight: false });
+    assert_eq!(output, "<p>Hello, <em>World</em>!</p>\n<div>Block</div>");
+
+    let output = render_filter(input, &Filter::Markdown { allow_html: false, gfm: false, anchors: false, highlight: false });
+    assert_eq!(output, "<p>Hello, &lt;em&gt;World&lt;/em&gt;!</p>\n&lt;div&gt;Block&lt;/div&gt;");
+}
+
+#[test]
+fn filter_markdown_gfm_renders_tables() {
+    let input = "| Name | Value |\n| ---- | ----- |\n| a | 1 |".to_string();
+    let output = render_filter(input.clone(), &Filter::Markdown { allow_html: true, gfm: false, anchors: false, highlight: false });
+    assert_eq!(output, "<p>| Name | Value |\n| ---- | ----- |\n| a | 1 |</p>");
+
+    let output = render_filter(input, &Filter::Markdown { allow_html: true, gfm: true, anchors: false, highlight: false });
+    assert_eq!(output, "<table>\n<thead>\n<tr>\n<th>Name</th>\n<th>Value</th>\n</tr>\n</thead>\n<tbody>\n<tr>\n<td>a</td>\n<td>1</td>\n</tr>\n</tbody>\n</table>");
+}
+
+#[test]
+fn filter_markdown_gfm_renders_task_lists_and_autolinks() {
+    let input = "* [x] Done\n* [ ] Todo\n\nSee www.example.com".to_string();
+    let output = render_filter(input, &Filter::Markdown { allow_html: true, gfm: true, anchors: false, highlight: false });
+    assert_eq!(output, "<ul>\n<li><input type=\"checkbox\" disabled=\"\" checked=\"\" /> Done</li>\n<li><input type=\"checkbox\" disabled=\"\" /> Todo</li>\n</ul>\n<p>See <a href=\"http://www.example.com\">www.example.com</a></p>");
+}
+
+#[test]
+fn filter_markdown_anchors_are_unique() {
+    let input = "# Title\n\n## Title\n\n### Tom &amp; Jerry's `code`".to_string();
+    let output = render_filter(input, &Filter::Markdown { allow_html: true, gfm: false, anchors: true, highlight: false });
+    assert_eq!(output, "<h1 id=\"title\">Title</h1>\n<h2 id=\"title-1\">Title</h2>\n<h3 id=\"tom-jerry-s-code\">Tom &amp; Jerry's <code>code</code></h3>");
+}
+
+#[test]
+fn filter_markdown_anchors_are_off_by_default() {
+    let (_, filter) = parse_filter(Span::new("markdown")).unwrap();
+    let output = render_filter("# Title".to_string(), &filter);
+    assert_eq!(output, "<h1>Title</h1>");
+}
+
+#[test]
+fn can_slugify() {
+    assert_eq!(slugify("Hello, World!"), "hello-world");
+    assert_eq!(slugify("Ünïcödé Títle"), "ünïcödé-títle");
+    assert_eq!(slugify(""), "");
+}
+
+#[test]
+fn heading_ids_skip_existing_ids() {
+    let html = "<h1 id=\"custom\">Title</h1><h2>Title</h2><header>Title</header>";
+    assert_eq!(add_heading_ids(html), "<h1 id=\"custom\">Title</h1><h2 id=\"title\">Title</h2><header>Title</header>");
+
+    // An id used anywhere in the document, even after the heading, is not
+    // reused.
+    let html = "<H2>Title</H2><h2>Custom</h2><p id='title'></p><H3 ID=\"custom\">Other</H3>";
+    assert_eq!(add_heading_ids(html), "<H2 id=\"title-1\">Title</H2><h2 id=\"custom-1\">Custom</h2><p id='title'></p><H3 ID=\"custom\">Other</H3>");
+}
+
+#[test]
+fn heading_ids_separate_words_around_tags() {
+    assert_eq!(add_heading_ids("<h2>Hello<br>World</h2>"), "<h2 id=\"hello-world\">Hello<br>World</h2>");
+    assert_eq!(add_heading_ids("<h2>Hello<br />World</h2>"), "<h2 id=\"hello-world\">Hello<br />World</h2>");
+    assert_eq!(add_heading_ids("<h2>Hello <em>big</em> World</h2>"), "<h2 id=\"hello-big-world\">Hello <em>big</em> World</h2>");
+    assert_eq!(add_heading_ids("<h2><code>Tom</code> &amp; Jerry</h2>"), "<h2 id=\"tom-jerry\"><code>Tom</code> &amp; Jerry</h2>");
+}
+
+#[test]
+#[cfg(feature = "highlight")]
+fn filter_markdown_highlights_code_blocks() {
+    let input = "```rust\nfn main() {\n    let s = \"<a>\";\n}\n```\n\n```\nplain <text>\n```".to_string();
+    let output = render_filter(input, &Filter::Markdown { allow_html: true, gfm: false, anchors: false, highlight: true });
+    assert!(output.starts_with("<pre><code class=\"language-rust\"><span class=\"source rust\">"));
+    assert!(output.contains("<span class=\"storage type function rust\">fn</span>"));
+    assert!(output.contains("&lt;a&gt;"));
+    assert!(output.ends_with("<pre><code>plain &lt;text&gt;\n</code></pre>"));
+}
+
+#[test]
+#[cfg(feature = "highlight")]
+fn unknown_languages_are_not_highlighted() {
+    let html = "<pre><code class=\"language-unknown\">a &amp; b\n</code></pre>";
+    assert_eq!(highlight_code_blocks(html), html);
+}
+
+#[test]
+#[cfg(not(feature = "highlight"))]
+fn filter_markdown_highlight_requires_feature() {
+    let input = "```rust\nlet x = 1;\n```".to_string();
+    let output = render_filter(input, &Filter::Markdown { allow_html: true, gfm: false, anchors: false, highlight: true });
+    assert_eq!(output, "<pre><code class=\"language-rust\">let x = 1;\n</code></pre>");
+    assert_eq!(highlight_code_blocks(&output), output);
+}
+
+#[test]
+fn can_parse_markdown_filter_html() {
+    let (_, filter) = parse_filter(Span::new("markdown")).unwrap();
+    assert_eq!(filter, Filter::Markdown { allow_html: true, gfm: false, anchors: false, highlight: false });
+
+    let (_, filter) = parse_filter(Span::new("markdown = html: true")).unwrap();
+    assert_eq!(filter, Filter::Markdown { allow_html: true, gfm: false, anchors: false, highlight: false });
+
+    let (_, filter) = parse_filter(Span::new("markdown = html: false")).unwrap();
+    assert_eq!(filter, Filter::Markdown { allow_html: false, gfm: false, anchors: false, highlight: false });
+
+    let (_, filter) = parse_filter(Span::new("markdown = html: false, gfm: true")).unwrap();
+    assert_eq!(filter, Filter::Markdown { allow_html: false, gfm: true, anchors: false, highlight: false });
+}
+
+#[test]
+fn content_markdown_filter_is_not_duplicated() {
+    let (_, placeholder) = parse_placeholder(Span::new("{{ £content | markdown = html: false }}")).unwrap();
+    assert_eq!(placeholder.filters, vec![Filter::Markdown { allow_html: false, gfm: false, anchors: false, highlight: false }]);
+
+    let (_, placeholder) = parse_placeholder(Span::new("{{ £content }}")).unwrap();
+    assert_eq!(placeholder.filters, vec![Filter::Markdown { allow_html: true, gfm: false, anchors: false, highlight: false }]);
+}
+
 #[test]
 fn filter_replace_works() {
     let input = "Hello, World! Hello, World!".to_string();
-    let output = render_filter(input, &Filter::Replace { find: "World".to_string(), replacement: "Rust".to_string(), limit: None });
+    let output = render_filter(input, &Filter::Replace { find: "World".to_string(), replacement: "Rust".to_string(), limit: None, ignore_case: false });
     assert_eq!(output, "Hello, Rust! Hello, Rust!");
 
     let input = "Hello, World! Hello, World!".to_string();
-    let output = render_filter(input, &Filter::Replace { find: "World".to_string(), replacement: "Rust".to_string(), limit: Some(1) });
+    let output = render_filter(input, &Filter::Replace { find: "World".to_string(), replacement: "Rust".to_string(), limit: Some(1), ignore_case: false });
     assert_eq!(output, "Hello, Rust! Hello, World!");
 
     // Replacing is a walk in the park!
     let input = "pawalkrk".to_string();
-    let output = render_filter(input, &Filter::Replace { find: "walk".to_string(), replacement: "".to_string(), limit: None });
+    let output = render_filter(input, &Filter::Replace { find: "walk".to_string(), replacement: "".to_string(), limit: None, ignore_case: false });
     assert_eq!(output, "park");
 }
 
@@ -643,26 +1063,26 @@ fn can_parse_replace_filter() {
     let input = Span::new("| replace = find: World, replacement: Rust, limit: 1");
     let (_, filters) = parse_filters(input).expect("parse replace");
     assert_eq!(filters.len(), 1);
-    assert_eq!(filters[0], Filter::Replace { find: "World".to_string(), replacement: "Rust".to_string(), limit: Some(1) });
+    assert_eq!(filters[0], Filter::Replace { find: "World".to_string(), replacement: "Rust".to_string(), limit: Some(1), ignore_case: false });
 
     // Using the default argument.
     let input = Span::new("| replace = World, replacement: Rust, limit: 1");
     let (_, filters) = parse_filters(input).expect("parse replace");
     assert_eq!(filters.len(), 1);
-    assert_eq!(filters[0], Filter::Replace { find: "World".to_string(), replacement: "Rust".to_string(), limit: Some(1) });
+    assert_eq!(filters[0], Filter::Replace { find: "World".to_string(), replacement: "Rust".to_string(), limit: Some(1), ignore_case: false });
 
     // Removing a word by not providing a replacement.
     let input = Span::new("| replace = World, limit: 1");
     let (_, filters) = parse_filters(input).expect("parse replace");
     assert_eq!(filters.len(), 1);
-    assert_eq!(filters[0], Filter::Replace { find: "World".to_string(), replacement: "".to_string(), limit: Some(1) });
+    assert_eq!(filters[0], Filter::Replace { find: "World".to_string(), replacement: "".to_string(), limit: Some(1), ignore_case: false });
 
     // Removing all words by not providing a replacement and limit.
     // It is also possible to include `replacement` here to replace them all.
     let input = Span::new("| replace = World");
     let (_, filters) = parse_filters(input).expect("parse replace");
     assert_eq!(filters.len(), 1);
-    assert_eq!(filters[0], Filter::Replace { find: "World".to_string(), replacement: "".to_string(), limit: None });
+    assert_eq!(filters[0], Filter::Replace { find: "World".to_string(), replacement: "".to_string(), limit: None, ignore_case: false });
 }
 
 #[test]
@@ -760,7 +1180,7 @@ fn can_render_text_filter() {
 #[test]
 fn filter_truncate_works() {
     let input = "Hello, World!".to_string();
-    let output = render_filter(input, &Filter::Truncate { characters: 7, trail: "--".to_string() });
+    let output = render_filter(input, &Filter::Truncate { characters: 7, trail: "--".to_string(), safe: false });
     assert_eq!(output, "Hello, --");
 }
 
@@ -770,31 +1190,31 @@ fn can_parse_truncate_filter() {
     let input = Span::new("| truncate = characters: 7, trail: --");
     let (_, filters) = parse_filters(input).expect("parse both arguments");
     assert_eq!(filters.len(), 1);
-    assert_eq!(filters[0], Filter::Truncate { characters: 7, trail: "--".to_string() });
+    assert_eq!(filters[0], Filter::Truncate { characters: 7, trail: "--".to_string(), safe: false });
 
     // Providing just characters.
     let input = Span::new("| truncate = characters: 7");
     let (_, filters) = parse_filters(input).expect("parse just characters");
     assert_eq!(filters.len(), 1);
-    assert_eq!(filters[0], Filter::Truncate { characters: 7, trail: "...".to_string() });
+    assert_eq!(filters[0], Filter::Truncate { characters: 7, trail: "...".to_string(), safe: false });
 
     // Providing just trail.
     let input = Span::new("| truncate = trail: --");
     let (_, filters) = parse_filters(input).expect("parse just trail");
     assert_eq!(filters.len(), 1);
-    assert_eq!(filters[0], Filter::Truncate { characters: 100, trail: "--".to_string() });
+    assert_eq!(filters[0], Filter::Truncate { characters: 100, trail: "--".to_string(), safe: false });
 
     // Providing just default value.
     let input = Span::new("| truncate = 42");
     let (_, filters) = parse_filters(input).expect("parse default value");
     assert_eq!(filters.len(), 1);
-    assert_eq!(filters[0], Filter::Truncate { characters: 42, trail: "...".to_string() });
+    assert_eq!(filters[0], Filter::Truncate { characters: 42, trail: "...".to_string(), safe: false });
 
     // Providing no arguments.
     let input = Span::new("| truncate");
     let (_, filters) = parse_filters(input).expect("parse no arguments");
     assert_eq!(filters.len(), 1);
-    assert_eq!(filters[0], Filter::Truncate { characters: 100, trail: "...".to_string() });
+    assert_eq!(filters[0], Filter::Truncate { characters: 100, trail: "...".to_string(), safe: false });
 }
 
 #[test]
@@ -836,6 +1256,1095 @@ fn can_render_truncate_filter() {
     assert_eq!(render_filter(title, &placeholder.filters[0]), "Hello, World! Hello, World! Hello, World! Hello, World! Hello, World! Hello, World! Hello, World! He...".to_string());
 }
 
+#[test]
+fn filter_split_works() {
+    let input = "rust, cli ,, markdown".to_string();
+    let output = render_filter(input, &Filter::Split { separator: ",".to_string() });
+    assert_eq!(output, "rust\ncli\nmarkdown");
+
+    let input = "rust".to_string();
+    let output = render_filter(input, &Filter::Split { separator: ",".to_string() });
+    assert_eq!(output, "rust");
+}
+
+#[test]
+fn filter_join_works() {
+    let input = "rust\ncli\nmarkdown".to_string();
+    let output = render_filter(input, &Filter::Join { separator: " / ".to_string() });
+    assert_eq!(output, "rust / cli / markdown");
+}
+
+#[test]
+fn can_parse_split_and_join_filters() {
+    let input = Span::new("| split = ';' | join = ', '");
+    let (_, filters) = parse_filters(input).expect("to parse filters");
+    assert_eq!(filters, vec![
+        Filter::Split { separator: ";".to_string() },
+        Filter::Join { separator: ", ".to_string() },
+    ]);
+
+    // Both have defaults.
+    let input = Span::new("| split | join");
+    let (_, filters) = parse_filters(input).expect("to parse filters");
+    assert_eq!(filters, vec![
+        Filter::Split { separator: ",".to_string() },
+        Filter::Join { separator: ", ".to_string() },
+    ]);
+}
+
+#[test]
+fn can_round_trip_split_and_join() {
+    let input = Span::new("{{ £tags | split = ',' | join = ', ' }}");
+    let (_, placeholder) = parse_placeholder(input).expect("to parse placeholder");
+    let tags = placeholder.apply("rust,cli,  markdown".to_string());
+    assert_eq!(tags, "rust, cli, markdown");
+
+    let input = Span::new("{{ £tags | split = \" | \" | join = \" | \" }}");
+    let (_, placeholder) = parse_placeholder(input).expect("to parse placeholder");
+    let tags = placeholder.apply("rust | cli | markdown".to_string());
+    assert_eq!(tags, "rust | cli | markdown");
+}
+
+#[test]
+fn can_render_first_and_last_filters() {
+    let input = Span::new("{{ £tags | first = ',' }} {{ £tags | last = ',' }}");
+    let placeholders = parse_placeholder_locations(input).expect("to parse placeholders");
+    // Placeholders are sorted in reverse.
+    assert_eq!(render_filter("rust, cli".to_string(), &placeholders[1].filters[0]), "rust");
+    assert_eq!(render_filter("rust, cli".to_string(), &placeholders[0].filters[0]), "cli");
+
+    // Empty items are ignored.
+    assert_eq!(render_filter(", rust, cli, ".to_string(), &placeholders[1].filters[0]), "rust");
+    assert_eq!(render_filter(", rust, cli, ".to_string(), &placeholders[0].filters[0]), "cli");
+
+    // By default, they take from a list, such as one from `split`.
+    let input = Span::new("{{ £tags | split | first }} {{ £tags | split | last }}");
+    let (_, placeholder) = parse_placeholder(input).expect("to parse placeholder");
+    assert_eq!(placeholder.apply("rust, cli, markdown".to_string()), "rust");
+
+    // An empty separator falls back to the default, rather than splitting on
+    // every character.
+    let (_, filter) = parse_filter(Span::new("first = ''")).expect("to parse filter");
+    assert_eq!(filter, Filter::First { separator: "\n".to_string() });
+    let (_, filter) = parse_filter(Span::new("last = separator: \"\"")).expect("to parse filter");
+    assert_eq!(filter, Filter::Last { separator: "\n".to_string() });
+}
+
+#[test]
+fn filter_regex_replace_works() {
+    let input = "Call 0123 or 4567 or 89".to_string();
+    let output = render_filter(input, &Filter::RegexReplace { pattern: r"\d+".to_string(), replacement: "#".to_string(), limit: Some(2), ignore_case: false });
+    assert_eq!(output, "Call # or # or 89");
+
+    // Capture groups can be referenced in the replacement.
+    let input = "John Doe".to_string();
+    let output = render_filter(input, &Filter::RegexReplace { pattern: r"(\w+) (\w+)".to_string(), replacement: "$2, $1".to_string(), limit: None, ignore_case: false });
+    assert_eq!(output, "Doe, John");
+
+    // Invalid patterns leave the value unchanged.
+    let input = "Hello, (World!".to_string();
+    let output = render_filter(input, &Filter::RegexReplace { pattern: "(".to_string(), replacement: "".to_string(), limit: None, ignore_case: false });
+    assert_eq!(output, "Hello, (World!");
+    // Including when rendered again, after the invalid pattern is cached.
+    let output = render_filter("(".to_string(), &Filter::RegexReplace { pattern: "(".to_string(), replacement: "".to_string(), limit: None, ignore_case: false });
+    assert_eq!(output, "(");
+
+    // An empty pattern has nothing to find, as with a literal replace.
+    let (_, filter) = parse_filter(Span::new("replace = regex: true, replacement: x")).expect("to parse filter");
+    assert_eq!(filter, Filter::RegexReplace { pattern: "".to_string(), replacement: "x".to_string(), limit: None, ignore_case: false });
+    assert_eq!(render_filter("Hi".to_string(), &filter), "Hi");
+}
+
+#[test]
+fn can_render_regex_replace_filter() {
+    let input = Span::new(r"{{ £date | replace = find: (\d+)-(\d+)-(\d+), replacement: $3/$2/$1, regex: true }}");
+    let (_, placeholder) = parse_placeholder(input).expect("to parse placeholder");
+    assert_eq!(render_filter("2024-01-31".to_string(), &placeholder.filters[0]), "31/01/2024");
+
+    // The case of the pattern can be ignored, which is compiled separately
+    // from the same pattern without it.
+    let input = Span::new(r"{{ £name | replace = find: [a-z]+, replacement: _, regex: true, ignore_case: true }} {{ £name | replace = find: [a-z]+, replacement: _, regex: true }}");
+    let placeholders = parse_placeholder_locations(input).expect("to parse placeholders");
+    // Placeholders are sorted in reverse.
+    assert_eq!(placeholders[1].filters[0], Filter::RegexReplace { pattern: "[a-z]+".to_string(), replacement: "_".to_string(), limit: None, ignore_case: true });
+    assert_eq!(render_filter("John Smith".to_string(), &placeholders[1].filters[0]), "_ _");
+    assert_eq!(render_filter("John Smith".to_string(), &placeholders[0].filters[0]), "J_ S_");
+
+    // Without the regex argument, the find is literal.
+    let input = Span::new(r"{{ £date | replace = find: \d+, replacement: # }}");
+    let (_, placeholder) = parse_placeholder(input).expect("to parse placeholder");
+    assert_eq!(render_filter(r"1 \d+".to_string(), &placeholder.filters[0]), "1 #");
+}
+
+#[test]
+fn filter_replace_with_empty_find_does_nothing() {
+    let input = Span::new(r#"{{ £greeting | replace = find: "", replacement: !, limit: 2 }}"#);
+    let (_, placeholder) = parse_placeholder(input).expect("to parse placeholder");
+    assert_eq!(placeholder.filters[0], Filter::Replace { find: "".to_string(), replacement: "!".to_string(), limit: Some(2), ignore_case: false });
+    assert_eq!(render_filter("Hello".to_string(), &placeholder.filters[0]), "Hello");
+
+    // The same is true without a limit.
+    let output = render_filter("Hello".to_string(), &Filter::Replace { find: "".to_string(), replacement: "!".to_string(), limit: None, ignore_case: false });
+    assert_eq!(output, "Hello");
+}
+
+#[test]
+fn filter_replace_limit_with_trailing_find() {
+    let filter = Filter::Replace { find: "!".to_string(), replacement: "?".to_string(), limit: Some(5), ignore_case: false };
+    assert_eq!(render_filter("Hello!".to_string(), &filter), "Hello?");
+
+    let filter = Filter::Replace { find: "!".to_string(), replacement: "?".to_string(), limit: Some(1), ignore_case: false };
+    assert_eq!(render_filter("Hi! Hello!".to_string(), &filter), "Hi? Hello!");
+}
+
+#[test]
+fn filter_replace_limit_with_consecutive_finds() {
+    let filter = Filter::Replace { find: "!".to_string(), replacement: "?".to_string(), limit: Some(2), ignore_case: false };
+    assert_eq!(render_filter("Hello!!!".to_string(), &filter), "Hello??!");
+
+    let filter = Filter::Replace { find: "ab".to_string(), replacement: "".to_string(), limit: Some(2), ignore_case: false };
+    assert_eq!(render_filter("abababab".to_string(), &filter), "abab");
+
+    let filter = Filter::Replace { find: "!".to_string(), replacement: "?".to_string(), limit: Some(0), ignore_case: false };
+    assert_eq!(render_filter("Hello!!!".to_string(), &filter), "Hello!!!");
+}
+
+#[test]
+fn filter_replace_ignore_case_works() {
+    let filter = Filter::Replace { find: "world".to_string(), replacement: "Rust".to_string(), limit: None, ignore_case: true };
+    assert_eq!(render_filter("World, world, WORLD!".to_string(), &filter), "Rust, Rust, Rust!");
+
+    let filter = Filter::Replace { find: "world".to_string(), replacement: "$1".to_string(), limit: Some(2), ignore_case: true };
+    assert_eq!(render_filter("World, world, WORLD!".to_string(), &filter), "$1, $1, WORLD!");
+
+    // By default, the find is case sensitive.
+    let input = Span::new("{{ £greeting | replace = find: world, replacement: Rust }}");
+    let (_, placeholder) = parse_placeholder(input).expect("to parse placeholder");
+    assert_eq!(render_filter("World, world, WORLD!".to_string(), &placeholder.filters[0]), "World, Rust, WORLD!");
+
+    let input = Span::new("{{ £greeting | replace = find: world, replacement: Rust, ignore_case: true }}");
+    let (_, placeholder) = parse_placeholder(input).expect("to parse placeholder");
+    assert_eq!(render_filter("World, world, WORLD!".to_string(), &placeholder.filters[0]), "Rust, Rust, Rust!");
+}
+
+#[test]
+fn filter_indent_works() {
+    let input = "<p>One</p>\n\n<p>Two</p>\n".to_string();
+    let output = render_filter(input.clone(), &Filter::Indent { spaces: 4, first: true });
+    assert_eq!(output, "    <p>One</p>\n\n    <p>Two</p>\n");
+
+    let output = render_filter(input, &Filter::Indent { spaces: 2, first: false });
+    assert_eq!(output, "<p>One</p>\n\n  <p>Two</p>\n");
+
+    let output = render_filter("   \nline".to_string(), &Filter::Indent { spaces: 2, first: true });
+    assert_eq!(output, "   \n  line");
+}
+
+#[test]
+fn can_parse_indent_filter() {
+    let (_, filter) = parse_filter(Span::new("indent")).unwrap();
+    assert_eq!(filter, Filter::Indent { spaces: 4, first: true });
+
+    let (_, filter) = parse_filter(Span::new("indent = spaces: 8, first: false")).unwrap();
+    assert_eq!(filter, Filter::Indent { spaces: 8, first: false });
+}
+
+#[test]
+fn filter_wrap_works() {
+    let input = "Hello".to_string();
+    let output = render_filter(input.clone(), &Filter::Wrap { before: "'".to_string(), after: "'".to_string() });
+    assert_eq!(output, "'Hello'");
+
+    let output = render_filter(input, &Filter::Wrap { before: "<strong>".to_string(), after: "</strong>".to_string() });
+    assert_eq!(output, "<strong>Hello</strong>");
+}
+
+#[test]
+fn can_parse_wrap_filter() {
+    let (_, filter) = parse_filter(Span::new("wrap = *")).unwrap();
+    assert_eq!(filter, Filter::Wrap { before: "*".to_string(), after: "*".to_string() });
+
+    let (_, filter) = parse_filter(Span::new("wrap = '[ ', after: ' ]'")).unwrap();
+    assert_eq!(filter, Filter::Wrap { before: "[ ".to_string(), after: " ]".to_string() });
+
+    let (_, filter) = parse_filter(Span::new("wrap = before: <em>, after: </em>")).unwrap();
+    assert_eq!(filter, Filter::Wrap { before: "<em>".to_string(), after: "</em>".to_string() });
+}
+
+#[test]
+fn filter_url_encode_works() {
+    let output = render_filter("a b&c".to_string(), &Filter::UrlEncode);
+    assert_eq!(output, "a%20b%26c");
+
+    let output = render_filter("café-1_2.3~".to_string(), &Filter::UrlEncode);
+    assert_eq!(output, "caf%C3%A9-1_2.3~");
+}
+
+#[test]
+fn filter_url_decode_works() {
+    let output = render_filter("caf%C3%A9+%zz%".to_string(), &Filter::UrlDecode);
+    assert_eq!(output, "café+%zz%");
+}
+
+#[test]
+fn filter_url_encode_round_trips() {
+    let encoded = render_filter("a b&c".to_string(), &Filter::UrlEncode);
+    let decoded = render_filter(encoded, &Filter::UrlDecode);
+    assert_eq!(decoded, "a b&c");
+
+    let (_, filters) = parse_filters(Span::new("| urlencode | url_decode")).unwrap();
+    assert_eq!(filters, vec![Filter::UrlEncode, Filter::UrlDecode]);
+}
+
+#[test]
+fn filter_base64_works() {
+    let encoded = render_filter("hello".to_string(), &Filter::Base64Encode);
+    assert_eq!(encoded, "aGVsbG8=");
+    assert_eq!(render_filter(encoded, &Filter::Base64Decode), "hello");
+
+    let encoded = render_filter("£content".to_string(), &Filter::Base64Encode);
+    assert_eq!(render_filter(encoded, &Filter::Base64Decode), "£content");
+}
+
+#[test]
+fn filter_base64_decode_keeps_invalid_values() {
+    assert_eq!(render_filter("aGVsbG8".to_string(), &Filter::Base64Decode), "aGVsbG8");
+    // Valid Base64, but not valid UTF-8.
+    assert_eq!(render_filter("/w==".to_string(), &Filter::Base64Decode), "/w==");
+}
+
+#[test]
+fn filter_json_escape_works() {
+    let input = "Say \"hi\"\n\tC:\\path\u{01}".to_string();
+    let output = render_filter(input, &Filter::JsonEscape);
+    assert_eq!(output, r#"Say \"hi\"\n\tC:\\path\u0001"#);
+
+    let (_, filter) = parse_filter(Span::new("json")).unwrap();
+    assert_eq!(filter, Filter::JsonEscape);
+}
+
+#[test]
+fn filter_count_works() {
+    let input = "aaaa, banana".to_string();
+    assert_eq!(render_filter(input.clone(), &Filter::Count { needle: "aa".to_string() }), "2");
+    assert_eq!(render_filter(input.clone(), &Filter::Count { needle: "an".to_string() }), "2");
+    assert_eq!(render_filter(input.clone(), &Filter::Count { needle: "x".to_string() }), "0");
+    assert_eq!(render_filter(input, &Filter::Count { needle: "".to_string() }), "0");
+}
+
+#[test]
+fn filter_remove_works() {
+    let input = "a-b-c-d".to_string();
+    assert_eq!(render_filter(input.clone(), &Filter::Remove { text: "-".to_string(), limit: None }), "abcd");
+    assert_eq!(render_filter(input.clone(), &Filter::Remove { text: "-".to_string(), limit: Some(2) }), "abc-d");
+    assert_eq!(render_filter(input.clone(), &Filter::Remove { text: "-".to_string(), limit: Some(0) }), "a-b-c-d");
+    assert_eq!(render_filter(input, &Filter::Remove { text: "".to_string(), limit: None }), "a-b-c-d");
+}
+
+#[test]
+fn filter_collapse_whitespace_works() {
+    let input = "\n\tOne\r\n\r\ntwo   three\u{a0}four \n".to_string();
+    assert_eq!(render_filter(input, &Filter::CollapseWhitespace), "One two three four");
+    assert_eq!(render_filter(" \n\t ".to_string(), &Filter::CollapseWhitespace), "");
+
+    let (_, filter) = parse_filter(Span::new("squish")).unwrap();
+    assert_eq!(filter, Filter::CollapseWhitespace);
+}
+
+#[test]
+fn filter_ordinal_works() {
+    let ordinal = |input: &str| render_filter(input.to_string(), &Filter::Ordinal);
+    assert_eq!(ordinal("0"), "0th");
+    assert_eq!(ordinal("101"), "101st");
+    assert_eq!(ordinal("111"), "111th");
+    assert_eq!(ordinal("112"), "112th");
+    assert_eq!(ordinal("1013"), "1013th");
+    assert_eq!(ordinal("-2"), "-2nd");
+    assert_eq!(ordinal(" 3 "), "3rd");
+    assert_eq!(ordinal("3.5"), "3.5");
+    assert_eq!(ordinal(""), "");
+}
+
+#[test]
+fn title_is_stripped_of_markdown() {
+    let markdown = Span::new("# My *great* post\nWith `code`");
+    let variables = create_variables(markdown, vec![], 1).expect("to create variables");
+    assert_eq!(variables.get("title").unwrap(), "My great post");
+    // The content is left as it was written.
+    assert_eq!(variables.get("content").unwrap(), "# My *great* post\nWith `code`");
+
+    // A title declared in the meta section is used exactly as written.
+    let markdown = Span::new(":meta\ntitle = My *great* post\n:meta\nContent");
+    let (markdown, meta_values) = parse_meta_section(markdown).unwrap();
+    let variables = create_variables(markdown, meta_values, 1).expect("to create variables");
+    assert_eq!(variables.get("title").unwrap(), "My *great* post");
+}
+
+#[test]
+fn can_escape_html() {
+    assert_eq!(escape_html("Tom & Jerry"), "Tom &amp; Jerry");
+    assert_eq!(escape_html("<script>alert(\"Hi\")</script>"), "&lt;script&gt;alert(&quot;Hi&quot;)&lt;/script&gt;");
+    // Existing entities are escaped again, as the input is treated as text.
+    assert_eq!(escape_html("&amp;"), "&amp;amp;");
+    assert_eq!(escape_html("£ and €"), "£ and €");
+}
+
+#[test]
+fn can_strip_markdown() {
+    assert_eq!(strip_markdown("**Bold**, _italic_, and ~~struck~~"), "Bold, italic, and struck");
+    assert_eq!(strip_markdown("A [link](https://example.com) and ![an image](cat.png)"), "A link and an image");
+    assert_eq!(strip_markdown("- Not a list\n> Not a quote"), "- Not a list\n> Not a quote");
+    assert_eq!(strip_markdown(""), "");
+}
+
+#[test]
+fn meta_values_can_reference_variables() {
+    let markdown = Span::new(":meta\nsite_url = https://example.com\ncanonical = {site_url}/posts\n:meta\n# Title");
+    let (markdown, meta_values) = parse_meta_section(markdown).unwrap();
+    let variables = create_variables(markdown, meta_values, 1).expect("to create variables");
+    assert_eq!(variables.get("canonical").unwrap(), "https://example.com/posts");
+}
+
+#[test]
+fn meta_values_can_forward_reference_variables() {
+    let markdown = Span::new(":meta\nsocial = {canonical}?share\ncanonical = {site_url}/{slug}\nsite_url = https://example.com\nslug = {title}\n:meta\n# Title");
+    let (markdown, meta_values) = parse_meta_section(markdown).unwrap();
+    let variables = create_variables(markdown, meta_values, 1).expect("to create variables");
+    assert_eq!(variables.get("social").unwrap(), "https://example.com/Title?share");
+    assert_eq!(variables.get("canonical").unwrap(), "https://example.com/Title");
+}
+
+#[test]
+fn meta_values_keep_unknown_references() {
+    let markdown = Span::new(":meta\njson = {\"a\": 1} {missing}\n:meta\n# {title}\nSome {content}");
+    let (markdown, meta_values) = parse_meta_section(markdown).unwrap();
+    let variables = create_variables(markdown, meta_values, 1).expect("to create variables");
+    assert_eq!(variables.get("json").unwrap(), "{\"a\": 1} {missing}");
+    assert_eq!(variables.get("title").unwrap(), "{title}");
+    assert_eq!(variables.get("content").unwrap(), "# {title}\nSome {content}");
+}
+
+#[test]
+fn meta_value_cycles_are_errors() {
+    let markdown = Span::new(":meta\na = {b}\nb = {c}\nc = {a}\n:meta\n# Title");
+    let (markdown, meta_values) = parse_meta_section(markdown).unwrap();
+    let error = create_variables(markdown, meta_values, 1).expect_err("to find a cycle");
+    assert_eq!(error.to_string(), "Circular meta reference: a -> b -> c -> a");
+
+    // Only the keys in the cycle are reported, in the order they are
+    // referenced.
+    let markdown = Span::new(":meta
+start = {z}
+z = {y}
+y = {z}
+:meta
+# Title");
+    let (markdown, meta_values) = parse_meta_section(markdown).unwrap();
+    let error = create_variables(markdown, meta_values, 1).expect_err("to find a cycle");
+    assert_eq!(error.to_string(), "Circular meta reference: z -> y -> z");
+
+    let markdown = Span::new(":meta\nself = {self}!\n:meta\n# Title");
+    let (markdown, meta_values) = parse_meta_section(markdown).unwrap();
+    let error = create_variables(markdown, meta_values, 1).expect_err("to find a cycle");
+    assert_eq!(error.to_string(), "Circular meta reference: self -> self");
+}
+
+#[test]
+fn can_parse_conditional_locations() {
+    let input = Span::new("<p>{{#if £a}}\n{{ #unless £b }}B{{ /unless }}\n{{/if}}</p>\n{{ #unless £c }}C{{ /unless }}");
+    let conditionals = parse_conditional_locations(input).unwrap();
+    assert_eq!(conditionals.len(), 2);
+
+    assert_eq!(conditionals[0].name, "c");
+    assert!(conditionals[0].negated);
+    assert_eq!(conditionals[0].selection.start.line, 4);
+
+    assert_eq!(conditionals[1].name, "a");
+    assert!(!conditionals[1].negated);
+    let body = &input.fragment()[conditionals[1].body.start.offset..conditionals[1].body.end.offset];
+    assert_eq!(body, "\n{{ #unless £b }}B{{ /unless }}\n");
+}
+
+#[test]
+fn mismatched_conditional_tags_are_errors() {
+    let error = parse_conditional_locations(Span::new("{{ #if £a }}A{{ /unless }}")).unwrap_err();
+    assert_eq!(error.to_string(), "The conditional block on line 1 is not closed.");
+
+    let error = parse_conditional_locations(Span::new("A\n{{ /if }}")).unwrap_err();
+    assert_eq!(error.to_string(), "The conditional block closed on line 2 was never opened.");
+}
+
+#[test]
+fn can_parse_loop_locations() {
+    let input = Span::new("<ul>\n{{#for £tag in £tags|split=';'|join=', '}}\n<li>{{ £tag }}</li>\n{{/for}}\n</ul>");
+    let loops = parse_loop_locations(input).unwrap();
+    assert_eq!(loops.len(), 1);
+    assert_eq!(loops[0].item, "tag");
+    assert_eq!(loops[0].name, "tags");
+    assert_eq!(loops[0].filters, vec![Filter::Split { separator: ";".to_string() }, Filter::Join { separator: ", ".to_string() }]);
+    assert_eq!(loops[0].selection.start.line, 2);
+    assert_eq!(&input.fragment()[loops[0].body.start.offset..loops[0].body.end.offset], "\n<li>{{ £tag }}</li>\n");
+
+    let error = parse_loop_locations(Span::new("{{ /for }}")).unwrap_err();
+    assert_eq!(error.to_string(), "The loop block closed on line 1 was never opened.");
+}
+
+#[test]
+fn can_render_bool_labels() {
+    let filter = Filter::Bool { truthy: "Draft".to_string(), falsy: "Published".to_string() };
+    for value in ["true", "True", "1", "YES", "on", "  yes\n"] {
+        assert_eq!(render_filter(value.to_string(), &filter), "Draft", "{:?} is truthy", value);
+    }
+    for value in ["false", "0", "no", "off", "", "2", "y"] {
+        assert_eq!(render_filter(value.to_string(), &filter), "Published", "{:?} is falsy", value);
+    }
+
+    // The labels default to `true` and `false`, normalising the value.
+    let (_, filter) = parse_filter(Span::new("bool")).expect("to parse filter");
+    assert_eq!(render_filter("ON".to_string(), &filter), "true");
+    assert_eq!(render_filter("nope".to_string(), &filter), "false");
+}
+
+#[test]
+fn can_render_fallback_filter() {
+    let variables = HashMap::from([
+        ("title".to_string(), "My Title".to_string()),
+        ("subtitle".to_string(), "".to_string()),
+    ]);
+    let filter = Filter::Fallback { key: "title".to_string() };
+
+    assert_eq!(render_filter_with_variables("".to_string(), &filter, &variables), "My Title");
+    assert_eq!(render_filter_with_variables("Subtitle".to_string(), &filter, &variables), "Subtitle");
+    // Without the other variables, the value is left as it is.
+    assert_eq!(render_filter("".to_string(), &filter), "");
+
+    // Every other filter renders as it would without the variables.
+    let (_, placeholder) = parse_placeholder(Span::new("{{ £subtitle | fallback = title | truncate = 2 }}")).expect("to parse placeholder");
+    assert_eq!(placeholder.apply_with_variables("".to_string(), &variables), "My...");
+    assert_eq!(placeholder.apply("".to_string()), "");
+}
+
+#[test]
+fn can_render_format_filter() {
+    let filter = Filter::Format { template: "Hello, {}!".to_string() };
+    assert_eq!(render_filter("World".to_string(), &filter), "Hello, World!");
+
+    let filter = Filter::Format { template: "{{}} {} £{}".to_string() };
+    assert_eq!(render_filter("5".to_string(), &filter), "{} 5 £{}");
+
+    // Without a placeholder, the value is appended.
+    let filter = Filter::Format { template: "Tags: ".to_string() };
+    assert_eq!(render_filter("rust".to_string(), &filter), "Tags: rust");
+
+    // The format string can contain braces within a placeholder.
+    let (_, placeholder) = parse_placeholder(Span::new("{{ £name | fmt = \"{{{}}}\" }}")).expect("to parse placeholder");
+    assert_eq!(placeholder.apply("name".to_string()), "{name}");
+}
+
+#[test]
+fn can_render_slice_filter() {
+    let hash = "d41d8cd98f00b204e9800998ecf8427e".to_string();
+    assert_eq!(render_filter(hash.clone(), &Filter::Slice { start: 0, end: Some(8) }), "d41d8cd9");
+    assert_eq!(render_filter(hash, &Filter::Slice { start: 28, end: None }), "427e");
+
+    // Indices are characters, not bytes.
+    assert_eq!(render_filter("£€¥$".to_string(), &Filter::Slice { start: 1, end: Some(3) }), "€¥");
+
+    // Out of range indices are clamped.
+    assert_eq!(render_filter("abc".to_string(), &Filter::Slice { start: 5, end: Some(10) }), "");
+    assert_eq!(render_filter("abc".to_string(), &Filter::Slice { start: 0, end: Some(10) }), "abc");
+    assert_eq!(render_filter("abc".to_string(), &Filter::Slice { start: 2, end: Some(1) }), "");
+
+    // Positional and named arguments can be mixed.
+    let (_, filter) = parse_filter(Span::new("slice = 1, end: 2")).expect("to parse filter");
+    assert_eq!(filter, Filter::Slice { start: 1, end: Some(2) });
+    let (_, filter) = parse_filter(Span::new("slice = 3")).expect("to parse filter");
+    assert_eq!(filter, Filter::Slice { start: 3, end: None });
+}
+
+#[test]
+#[cfg(feature = "graphemes")]
+fn reverse_keeps_graphemes_together() {
+    // An `e` followed by a combining acute accent.
+    let input = "Cafe\u{301}!".to_string();
+    assert_eq!(render_filter(input, &Filter::Reverse), "!e\u{301}faC");
+}
+
+#[test]
+#[cfg(not(feature = "graphemes"))]
+fn reverse_separates_combining_characters() {
+    let input = "Cafe\u{301}!".to_string();
+    assert_eq!(render_filter(input, &Filter::Reverse), "!\u{301}efaC");
+}
+
+#[test]
+fn filter_arguments_can_use_aliases() {
+    let (_, chars) = parse_filter(Span::new("truncate = chars: 7")).expect("to parse filter");
+    let (_, characters) = parse_filter(Span::new("truncate = characters: 7")).expect("to parse filter");
+    assert_eq!(chars, characters);
+    assert_eq!(chars, Filter::Truncate { characters: 7, trail: "...".to_string(), safe: false });
+    assert_eq!(render_filter("Hello, World!".to_string(), &chars), "Hello, ...");
+
+    let (_, filter) = parse_filter(Span::new("round = digits: 2")).expect("to parse filter");
+    assert_eq!(filter, Filter::Round { precision: 2, mode: RoundingMode::HalfUp });
+    let (_, filter) = parse_filter(Span::new("join = sep: ' | '")).expect("to parse filter");
+    assert_eq!(filter, Filter::Join { separator: " | ".to_string() });
+
+    // The unnamed argument is unaffected.
+    let (_, filter) = parse_filter(Span::new("truncate = 7")).expect("to parse filter");
+    assert_eq!(filter, characters);
+}
+
+#[test]
+fn placeholders_record_unknown_filter_arguments() {
+    let input = Span::new("{{ £title | truncate = charcters: 7 | wrap = before: '<', afterr: '>' }}");
+    let (_, (placeholder, unknown_arguments)) = parse_placeholder_with_unknown_arguments(input).expect("to parse placeholder");
+    assert_eq!(unknown_arguments, vec!["charcters".to_string(), "afterr".to_string()]);
+    // The misspelled argument is ignored, so the default is used.
+    assert_eq!(placeholder.filters[0], Filter::Truncate { characters: 100, trail: "...".to_string(), safe: false });
+
+    let input = Span::new("{{ £title | truncate = 7 | replace = find: a, regex: true }}");
+    let (_, (_, unknown_arguments)) = parse_placeholder_with_unknown_arguments(input).expect("to parse placeholder");
+    assert!(unknown_arguments.is_empty());
+}
+
+#[test]
+fn filter_trim_lines_works() {
+    let input = "One  \nTwo\t\n\n   \nThree ".to_string();
+    assert_eq!(render_filter(input, &Filter::TrimLines), "One\nTwo\n\n\nThree");
+
+    // Line endings are kept as they are, including a final one.
+    let input = "One \r\nTwo  \r\n".to_string();
+    assert_eq!(render_filter(input, &Filter::TrimLines), "One\r\nTwo\r\n");
+
+    // Leading whitespace is left alone, so indentation is unaffected.
+    let input = "    code();  \n".to_string();
+    assert_eq!(render_filter(input, &Filter::TrimLines), "    code();\n");
+
+    let (_, filter) = parse_filter(Span::new("trim_lines")).expect("to parse filter");
+    assert_eq!(filter, Filter::TrimLines);
+}
+
+#[test]
+fn filter_spaceless_works() {
+    let input = "<ul>\n    <li>One</li>\n    <li>Two words</li>\n</ul>\n".to_string();
+    assert_eq!(render_filter(input, &Filter::Spaceless), "<ul><li>One</li><li>Two words</li></ul>\n");
+
+    // Whitespace next to text, or a lone angle bracket, is left alone.
+    let input = "<b>Bold</b> <i>italic</i> and 1 > 0 < 2".to_string();
+    assert_eq!(render_filter(input, &Filter::Spaceless), "<b>Bold</b><i>italic</i> and 1 > 0 < 2");
+
+    let (_, filter) = parse_filter(Span::new("spaceless")).expect("to parse filter");
+    assert_eq!(filter, Filter::Spaceless);
+}
+
+#[test]
+fn filter_escape_xml_works() {
+    assert_eq!(render_filter("Q&A: \"Why?\" & 'How?'".to_string(), &Filter::EscapeXml), "Q&amp;A: &quot;Why?&quot; &amp; &apos;How?&apos;");
+    // Existing entities are escaped too, as the value is treated as text.
+    assert_eq!(escape_xml("&apos;"), "&amp;apos;");
+
+    let (_, filter) = parse_filter(Span::new("escape_xml")).expect("to parse filter");
+    assert_eq!(filter, Filter::EscapeXml);
+}
+
+#[test]
+fn filter_cdata_works() {
+    assert_eq!(render_filter("".to_string(), &Filter::Cdata), "<![CDATA[]]>");
+    assert_eq!(render_filter("a]]>b]]>".to_string(), &Filter::Cdata), "<![CDATA[a]]]]><![CDATA[>b]]]]><![CDATA[>]]>");
+    // Only the full terminator is split.
+    assert_eq!(render_filter("]] >".to_string(), &Filter::Cdata), "<![CDATA[]] >]]>");
+
+    let (_, filter) = parse_filter(Span::new("cdata")).expect("to parse filter");
+    assert_eq!(filter, Filter::Cdata);
+}
+
+#[test]
+fn can_parse_meta_after_title() {
+    let input = Span::new("\n# Markdown title\n\n<meta>\nauthor = John Doe\n</meta>\n\nThis is my content");
+    let (markdown, (title, meta_values)) = parse_meta_after_title(input, 1).expect("to parse meta after title");
+    assert_eq!(title.fragment(), &"# Markdown title");
+    assert_eq!(meta_values, vec![Meta::new("author", "John Doe")]);
+    assert_eq!(markdown.fragment(), &"This is my content");
+
+    // Only a title of the right level is skipped.
+    assert!(parse_meta_after_title(input, 2).is_err());
+
+    // Without a meta section, the title and content are still found when the
+    // meta block is the second element.
+    let variables = create_variables(input, vec![], 1).expect("to create variables");
+    assert_eq!(variables["title"], "Markdown title");
+    assert!(variables["content"].starts_with("# Markdown title"));
+}
+
+#[test]
+fn can_parse_empty_meta_section() {
+    for input in [":meta\n:meta\n# Markdown title", "<meta></meta>\n# Markdown title", "<?meta\n?>\n# Markdown title"] {
+        let (markdown, meta_values) = parse_meta_section(Span::new(input)).expect("to parse empty meta section");
+        assert!(meta_values.is_empty());
+        assert_eq!(markdown.fragment(), &"# Markdown title");
+
+        let variables = create_variables(markdown, meta_values, 1).expect("to create variables");
+        assert_eq!(variables.len(), 2);
+        assert_eq!(variables["title"], "Markdown title");
+        assert_eq!(variables["content"], "# Markdown title");
+    }
+
+    // A section of only comments is still valid too.
+    let input = Span::new(":meta\n// Nothing to see here.\n# Or here.\n:meta\n# Markdown title");
+    let (markdown, meta_values) = parse_meta_section(input).expect("to parse comment only meta section");
+    assert!(meta_values.is_empty());
+    assert_eq!(markdown.fragment(), &"# Markdown title");
+}
+
+#[test]
+fn filter_typography_works() {
+    let smarten = |input: &str| render_filter(input.to_string(), &Filter::Typography);
+
+    // Contractions and possessives are apostrophes.
+    assert_eq!(smarten("Don't touch James' or Jane's cat."), "Don’t touch James’ or Jane’s cat.");
+    assert_eq!(smarten("'Quoted,' he said."), "‘Quoted,’ he said.");
+    assert_eq!(smarten("\"Nested 'quotes'\""), "“Nested ‘quotes’”");
+    assert_eq!(smarten("[\"a\"]"), "[“a”]");
+
+    // The longest run of dashes or dots wins.
+    assert_eq!(smarten("a-b--c---d----e"), "a-b–c—d—-e");
+    assert_eq!(smarten(". .. ... ...."), ". .. … ….");
+
+    let (_, filter) = parse_filter(Span::new("smartypants")).expect("to parse filter");
+    assert_eq!(filter, Filter::Typography);
+}
+
+#[test]
+fn filter_typography_leaves_markup_alone() {
+    let smarten = |input: &str| render_filter(input.to_string(), &Filter::Typography);
+
+    // Attributes keep their straight quotes, while the text is smartened.
+    assert_eq!(
+        smarten("<a href=\"x\" title='y'>it's \"here\"</a>"),
+        "<a href=\"x\" title='y'>it’s “here”</a>"
+    );
+    assert_eq!(smarten("<!-- it's -->"), "<!-- it's -->");
+    // A `<` that doesn't start a tag is just text.
+    assert_eq!(smarten("1 < 2 -- \"true\""), "1 < 2 – “true”");
+
+    // Inline code, including code that contains backticks.
+    assert_eq!(smarten("Use `\"a\" -- b` or ``it's `x` ...`` here..."), "Use `\"a\" -- b` or ``it's `x` ...`` here…");
+    assert_eq!(smarten("An unclosed ` isn't code"), "An unclosed ` isn’t code");
+
+    // Fenced code blocks.
+    assert_eq!(
+        smarten("\"a\"\n```rust\nlet s = \"it's\";\n```\n~~~\n--\n~~~\n\"b\"\n"),
+        "“a”\n```rust\nlet s = \"it's\";\n```\n~~~\n--\n~~~\n“b”\n"
+    );
+
+    // Thematic breaks, setext underlines, and table separator rows.
+    assert_eq!(smarten("A\n\n---\n\nB"), "A\n\n---\n\nB");
+    assert_eq!(smarten("Title\n=====\nSub\n---"), "Title\n=====\nSub\n---");
+    assert_eq!(smarten("| a | b |\n|---|:--:|\n| \"c\" | d |"), "| a | b |\n|---|:--:|\n| “c” | d |");
+}
+
+#[test]
+fn can_find_placeholder_errors() {
+    let error = find_placeholder_error("{{ £x | }}").unwrap();
+    assert_eq!(error, "Expected a filter name on line 1, column 9:\n{{ £x | }}\n        ^");
+
+    let error = find_placeholder_error("<p>\n  {{ £x | not_a_filter }}\n</p>").unwrap();
+    assert_eq!(error, "Expected a filter name on line 2, column 11:\n  {{ £x | not_a_filter }}\n          ^");
+
+    let error = find_placeholder_error("{{ £1x }}").unwrap();
+    assert_eq!(error, "Expected a variable name on line 1, column 5:\n{{ £1x }}\n    ^");
+
+    let error = find_placeholder_error("{{ £x uppercase }}").unwrap();
+    assert_eq!(error, "Expected `|` or `}}` on line 1, column 7:\n{{ £x uppercase }}\n      ^");
+
+    assert!(find_placeholder_error("{{ £x | uppercase | truncate = 5 }}").is_none());
+}
+
+#[test]
+fn unknown_filter_is_an_error() {
+    assert!(parse_filter(Span::new("")).is_err());
+    assert!(parse_filter(Span::new("not_a_filter")).is_err());
+}
+
+#[test]
+fn filter_filesize_works() {
+    let decimal = |input: &str| render_filter(input.to_string(), &Filter::FileSize { binary: false });
+    let binary = |input: &str| render_filter(input.to_string(), &Filter::FileSize { binary: true });
+
+    assert_eq!(decimal("0"), "0 B");
+    assert_eq!(decimal("999"), "999 B");
+    assert_eq!(decimal("1536"), "1.5 KB");
+    assert_eq!(decimal("2500000"), "2.5 MB");
+    assert_eq!(decimal("5000000000000000"), "5000.0 TB");
+    assert_eq!(binary("1023"), "1023 B");
+    assert_eq!(binary("1536"), "1.5 KiB");
+    assert_eq!(binary("1073741824"), "1.0 GiB");
+    assert_eq!(decimal("-5"), "-5");
+    assert_eq!(decimal("1.5"), "1.5");
+}
+
+#[test]
+fn filter_timeago_works() {
+    let filter = parse_filter(Span::new("relative_time = from: \"2024-03-10T12:00:00Z\"")).expect("timeago").1;
+    let time_ago = |input: &str| render_filter(input.to_string(), &filter);
+
+    assert_eq!(time_ago("2024-03-10T12:00:00Z"), "just now");
+    assert_eq!(time_ago("2024-03-10T11:59:00Z"), "1 minute ago");
+    assert_eq!(time_ago("2024-03-10T10:00:00Z"), "2 hours ago");
+    assert_eq!(time_ago("2024-03-10T12:00:00+03:00"), "3 hours ago");
+    assert_eq!(time_ago("2024-03-09T12:00:00Z"), "1 day ago");
+    assert_eq!(time_ago("2024-01-10"), "2 months ago");
+    assert_eq!(time_ago("2022-03-10"), "2 years ago");
+    assert_eq!(time_ago("2024-03-12T12:00:00Z"), "in 2 days");
+    assert_eq!(time_ago("not a date"), "not a date");
+
+    // Any ISO 8601 date, with or without the seconds or an offset.
+    assert_eq!(time_ago("2024-03-10 10:00"), "2 hours ago");
+    assert_eq!(time_ago("2024-03-10T10:00:30.5"), "1 hour ago");
+    assert_eq!(time_ago("2024-03-10T09:00+01:00"), "4 hours ago");
+    assert_eq!(time_ago("2024-03-10T09:00:00-01:00"), "2 hours ago");
+    assert_eq!(time_ago("10/03/2024"), "10/03/2024");
+    assert_eq!(time_ago("2024-02-30"), "2024-02-30");
+    assert_eq!(time_ago("2024-03-10T25:00"), "2024-03-10T25:00");
+}
+
+#[test]
+fn filter_timeago_defaults_to_now() {
+    let output = render_filter("2000-01-01".to_string(), &Filter::TimeAgo { from: None });
+    assert!(output.ends_with("years ago"));
+}
+
+#[test]
+fn filter_min_and_max_clamp_numbers() {
+    let filters = parse_filters(Span::new("| min = 0 | max = 100")).expect("min and max").1;
+    let clamp = |input: &str| filters.iter().fold(input.to_string(), render_filter);
+
+    assert_eq!(clamp("-20"), "0");
+    assert_eq!(clamp("42.5"), "42.5");
+    assert_eq!(clamp("150"), "100");
+    assert_eq!(clamp("lots"), "lots");
+
+    // Without a value there is nothing to clamp to.
+    let max = parse_filter(Span::new("max")).expect("max").1;
+    assert_eq!(render_filter("1e6".to_string(), &max), "1000000");
+}
+
+#[test]
+fn maths_filters_ignore_grouping_separators() {
+    assert_eq!(render_filter("1,234.5".to_string(), &Filter::Ceil), "1235");
+    assert_eq!(render_filter("1,234.5".to_string(), &Filter::Floor), "1234");
+    assert_eq!(render_filter("1,234.56".to_string(), &Filter::Round { precision: 1, mode: RoundingMode::HalfUp }), "1234.6");
+    assert_eq!(render_filter("1,500".to_string(), &Filter::Max { value: 1000.0 }), "1000");
+
+    let unformat = parse_filter(Span::new("unformat")).expect("unformat").1;
+    assert_eq!(render_filter("1,234.5".to_string(), &unformat), "1234.5");
+    assert_eq!(render_filter("n/a".to_string(), &unformat), "n/a");
+}
+
+#[test]
+fn filter_highlight_works() {
+    let filter = parse_filter(Span::new("highlight = term: \"a.b\", tag: \"<script>\"")).expect("highlight").1;
+    assert_eq!(filter, Filter::Highlight { term: "a.b".to_string(), tag: "mark".to_string() });
+    assert_eq!(render_filter("a.b axb A.B".to_string(), &filter), "<mark>a.b</mark> axb <mark>A.B</mark>");
+
+    let filter = parse_filter(Span::new("highlight")).expect("highlight").1;
+    assert_eq!(render_filter("unchanged".to_string(), &filter), "unchanged");
+
+    let filter = parse_filter(Span::new("highlight = p")).expect("highlight").1;
+    assert_eq!(render_filter("<p class=\"p\">pop</p>".to_string(), &filter), "<p class=\"p\"><mark>p</mark>o<mark>p</mark></p>");
+
+    // A tag that isn't a name can't be closed, so falls back to `mark`.
+    let filter = parse_filter(Span::new("highlight = term: a, tag: 'mark class=x'")).expect("highlight").1;
+    assert_eq!(filter, Filter::Highlight { term: "a".to_string(), tag: "mark".to_string() });
+    let filter = Filter::Highlight { term: "a".to_string(), tag: "mark class=x".to_string() };
+    assert_eq!(render_filter("a".to_string(), &filter), "<mark>a</mark>");
+    let filter = Filter::Highlight { term: "a".to_string(), tag: "em".to_string() };
+    assert_eq!(render_filter("a".to_string(), &filter), "<em>a</em>");
+}
+
+#[test]
+fn filter_wrap_at_works() {
+    let wrap = |input: &str, width: u8, break_long_words: bool| render_filter(input.to_string(), &Filter::WrapAt { width, break_long_words });
+
+    // Existing lines and blank lines are kept.
+    assert_eq!(wrap("one two three\n\nfour five", 8, false), "one two\nthree\n\nfour\nfive");
+    assert_eq!(wrap("one two three\r\nfour", 8, false), "one two\r\nthree\r\nfour");
+    // Characters are counted, rather than bytes.
+    assert_eq!(wrap("café café café", 9, false), "café café\ncafé");
+    // Long words stay whole unless they may be broken.
+    assert_eq!(wrap("a supercalifragilistic b", 6, false), "a\nsupercalifragilistic\nb");
+    assert_eq!(wrap("a abcdefghijklm b", 5, true), "a\nabcde\nfghij\nklm b");
+    assert_eq!(wrap("unchanged", 0, true), "unchanged");
+}
+
+#[test]
+fn filter_dedent_works() {
+    let dedent = |input: &str| render_filter(input.to_string(), &Filter::Dedent);
+
+    assert_eq!(dedent("\t\tone\r\n\t\t\ttwo\r\n"), "one\r\n\ttwo\r\n");
+    // Only the whitespace that every line shares is removed.
+    assert_eq!(dedent("  \tone\n  two"), "\tone\ntwo");
+    assert_eq!(dedent("none\n    indented"), "none\n    indented");
+    assert_eq!(dedent("   \n\n"), "\n\n");
+    assert_eq!(dedent(""), "");
+}
+
+#[test]
+fn can_parse_filter_values_with_colons() {
+    let (_, filter) = parse_filter(Span::new("wrap = https://example.com/")).unwrap();
+    assert_eq!(filter, Filter::Wrap { before: "https://example.com/".to_string(), after: "https://example.com/".to_string() });
+
+    let (_, filter) = parse_filter(Span::new("wrap = before: https://example.com/, after: /")).unwrap();
+    assert_eq!(filter, Filter::Wrap { before: "https://example.com/".to_string(), after: "/".to_string() });
+
+    let (_, filter) = parse_filter(Span::new("replace = find: 12:30, with: noon")).unwrap();
+    assert_eq!(filter, Filter::Replace { find: "12:30".to_string(), replacement: "noon".to_string(), limit: None, ignore_case: false });
+
+    let (_, placeholder) = parse_placeholder(Span::new("{{ £website | remove = https:// | lowercase }}")).unwrap();
+    assert_eq!(placeholder.filters, vec![
+        Filter::Remove { text: "https://".to_string(), limit: None },
+        Filter::Text { case: TextCase::Lower },
+    ]);
+}
+
+#[test]
+fn can_parse_escaped_filter_values() {
+    let (_, filter) = parse_filter(Span::new(r"replace = find: ;, with: \,")).unwrap();
+    assert_eq!(filter, Filter::Replace { find: ";".to_string(), replacement: ",".to_string(), limit: None, ignore_case: false });
+    assert_eq!(render_filter("a;b;c".to_string(), &filter), "a,b,c");
+
+    let (_, filter) = parse_filter(Span::new(r"replace = find: \,, with: '\, and'")).unwrap();
+    assert_eq!(filter, Filter::Replace { find: ",".to_string(), replacement: ", and".to_string(), limit: None, ignore_case: false });
+
+    let (_, placeholder) = parse_placeholder(Span::new(r"{{ £title | truncate = 5, trail: \| | wrap = before: \{, after: \} }}")).unwrap();
+    assert_eq!(placeholder.filters, vec![
+        Filter::Truncate { characters: 5, trail: "|".to_string(), safe: false },
+        Filter::Wrap { before: "{".to_string(), after: "}".to_string() },
+    ]);
+    assert_eq!(placeholder.apply("Hello, World!".to_string()), "{Hello|}");
+
+    // Other backslashes are kept, such as in a regular expression.
+    let (_, filter) = parse_filter(Span::new(r"replace = find: \d+, with: #, regex: true")).unwrap();
+    assert_eq!(render_filter("Chapter 12".to_string(), &filter), "Chapter #");
+}
+
+#[test]
+fn trailing_pipe_is_an_error() {
+    assert!(parse_filters(Span::new("| uppercase |")).is_err());
+    assert!(parse_filters(Span::new("| uppercase | | lowercase")).is_err());
+    assert!(parse_placeholder(Span::new("{{ £x | uppercase | }}")).is_err());
+
+    let error = find_placeholder_error("<h1>{{ £x | uppercase | }}</h1>").unwrap();
+    assert_eq!(error, "Expected a filter name on line 1, column 25:\n<h1>{{ £x | uppercase | }}</h1>\n                        ^");
+}
+
+#[test]
+fn predicate_filters_compose_with_bool() {
+    let (_, placeholder) = parse_placeholder(Span::new(r#"{{ £summary | is_empty | bool = truthy: "No summary", falsy: "" }}"#)).unwrap();
+    assert_eq!(placeholder.apply("  ".to_string()), "No summary");
+    assert_eq!(placeholder.apply("A summary.".to_string()), "");
+
+    let (_, placeholder) = parse_placeholder(Span::new("{{ £rating | is_numeric | bool = truthy: rated, falsy: unrated }}")).unwrap();
+    assert_eq!(placeholder.apply("4.5".to_string()), "rated");
+    assert_eq!(placeholder.apply("n/a".to_string()), "unrated");
+}
+
+#[test]
+fn placeholders_are_available_in_both_orders() {
+    let input = Span::new("{{ £a }} {{ £b | uppercase }}\n{{ £c }}");
+
+    let reversed = parse_placeholder_locations(input).expect("to parse placeholders");
+    let names = reversed.iter().map(|placeholder| placeholder.name.as_str()).collect::<Vec<&str>>();
+    assert_eq!(names, vec!["c", "b", "a"]);
+    assert!(reversed.windows(2).all(|pair| pair[0].selection.start.offset > pair[1].selection.start.offset));
+
+    let in_order = parse_placeholder_locations_in_order(input).expect("to parse placeholders");
+    let names = in_order.iter().map(|placeholder| placeholder.name.as_str()).collect::<Vec<&str>>();
+    assert_eq!(names, vec!["a", "b", "c"]);
+    assert_eq!(in_order[2].selection.start.line, 2);
+    assert_eq!(template_placeholders(input.fragment()), in_order);
+}
+
+#[test]
+fn can_replace_placeholders_after_multibyte_characters() {
+    let template = "<p>Prix: 5€ — café — 日本語 🎉</p><h1>{{ £title }}</h1>";
+    let placeholders = parse_placeholder_locations(Span::new(template)).expect("to parse placeholders");
+    let placeholder = &placeholders[0];
+
+    let output = replace_substring(template, placeholder.selection.start.offset, placeholder.selection.end.offset, "Crème brûlée");
+    assert_eq!(output, "<p>Prix: 5€ — café — 日本語 🎉</p><h1>Crème brûlée</h1>");
+
+    // Offsets inside of a character are widened rather than panicking.
+    let emoji = template.find('🎉').unwrap();
+    assert_eq!(replace_substring("🎉!", 2, 3, "?"), "?!");
+    assert_eq!(replace_substring(template, emoji + 1, emoji + 2, "✨"), template.replace('🎉', "✨"));
+    assert_eq!(replace_substring(template, template.len() + 10, template.len() + 20, "!"), format!("{}!", template));
+}
+
+#[test]
+fn placeholder_selections_are_exact_with_multibyte_text() {
+    let template = "🎉 Café £{{ £price | round = 2 }}€ — naïve\n日本 {{ $title }}ß";
+    let placeholders = parse_placeholder_locations(Span::new(template)).expect("to parse placeholders");
+    assert_eq!(placeholders.len(), 2);
+
+    // Each selection covers exactly the `{{...}}`, and nothing else.
+    let selected = placeholders.iter()
+        .map(|placeholder| &template[placeholder.selection.start.offset..placeholder.selection.end.offset])
+        .collect::<Vec<&str>>();
+    assert_eq!(selected, vec!["{{ $title }}", "{{ £price | round = 2 }}"]);
+    assert_eq!(placeholders[0].selection.start.line, 2);
+
+    // Replacing every placeholder, from the end, leaves the rest untouched.
+    let output = placeholders.iter().fold(template.to_string(), |output, placeholder| {
+        let value = placeholder.apply(match placeholder.name.as_str() {
+            "price" => "4.999".to_string(),
+            _ => "Crème".to_string(),
+        });
+        replace_substring(&output, placeholder.selection.start.offset, placeholder.selection.end.offset, &value)
+    });
+    assert_eq!(output, "🎉 Café £5€ — naïve\n日本 Crèmeß");
+}
+
+#[test]
+fn filter_list_matches_the_parser() {
+    for info in FILTERS {
+        let (_, filter) = parse_filter(Span::new(info.name)).unwrap_or_else(|_| panic!("'{}' to parse", info.name));
+        for alias in info.aliases {
+            assert_eq!(parse_filter(Span::new(alias)).expect("alias to parse").1, filter, "alias '{}'", alias);
+        }
+
+        // These are shorthands for `text`, so they don't take its argument.
+        if ["lowercase", "uppercase"].contains(&info.name) {
+            continue;
+        }
+        let arguments = info.arguments.iter().map(|(name, _)| *name).collect::<Vec<&str>>();
+        assert_eq!(filter.arguments(), arguments.as_slice(), "arguments of '{}'", info.name);
+    }
+
+    // Passing each listed default is the same as passing no arguments.
+    for info in FILTERS {
+        let arguments = info.arguments.iter()
+            .filter(|(_, default)| !default.is_empty())
+            .map(|(name, default)| format!("{}: \"{}\"", name, default.replace("\\n", "\n")))
+            .collect::<Vec<String>>();
+        if arguments.is_empty() {
+            continue;
+        }
+        let input = format!("{} = {}", info.name, arguments.join(", "));
+        let (rest, with_defaults) = parse_filter(Span::new(&input)).unwrap_or_else(|_| panic!("'{}' to parse", input));
+        assert!(rest.is_empty(), "'{}' to parse entirely", input);
+        assert_eq!(parse_filter(Span::new(info.name)).expect("filter to parse").1, with_defaults, "defaults of '{}'", info.name);
+    }
+
+    // Sorted, with no duplicates.
+    assert!(FILTERS.windows(2).all(|pair| pair[0].name < pair[1].name));
+    let mut names = FILTERS.iter().flat_map(|info| std::iter::once(&info.name).chain(info.aliases)).collect::<Vec<&&str>>();
+    let count = names.len();
+    names.sort();
+    names.dedup();
+    assert_eq!(names.len(), count, "a name or alias is listed twice");
+
+    // A name that isn't listed can't be parsed.
+    for name in ["squash", "lower", "trim", "url"] {
+        assert!(parse_filter(Span::new(name)).is_err(), "'{}' is not a filter", name);
+    }
+}
+
+#[test]
+fn can_render_an_excerpt_of_content() {
+    let (_, placeholder) = parse_placeholder(Span::new("{{ £content | excerpt }}")).unwrap();
+    let content = "A *short* lede.\r\n\r\n## Heading\r\n\r\nMore text.".to_string();
+    assert_eq!(placeholder.apply(content), "<p>A <em>short</em> lede.</p>");
+
+    let excerpt = |input: &str| render_filter(input.to_string(), &Filter::FirstParagraph);
+    assert_eq!(excerpt("One\n \t\nTwo"), "One");
+    assert_eq!(excerpt(""), "");
+}
+
+#[test]
+fn can_split_content_at_the_more_marker() {
+    let content = "The *teaser*.\n\n<!-- more -->\n\nThe rest.".to_string();
+
+    let (_, teaser) = parse_placeholder(Span::new("{{ £content | before_more }}")).unwrap();
+    assert_eq!(teaser.apply(content.clone()), "<p>The <em>teaser</em>.</p>");
+
+    let (_, rest) = parse_placeholder(Span::new("{{ £content | after_more }}")).unwrap();
+    assert_eq!(rest.apply(content.clone()), "<p>The rest.</p>");
+
+    // Only the first marker splits the content.
+    let filter = Filter::AfterMore { marker: "<!-- more -->".to_string() };
+    assert_eq!(render_filter("a<!-- more -->b<!-- more -->c".to_string(), &filter), "b<!-- more -->c");
+}
+
+#[test]
+fn reading_time_is_rounded_up_to_minutes() {
+    // 400 words of body and a two word title.
+    let markdown = "# Markdown title\n\n".to_string() + &"word ".repeat(399) + "\n\n---\n";
+    assert_eq!(reading_time(&markdown, 200), 3);
+    assert_eq!(reading_time(&markdown, 401), 1);
+    assert_eq!(reading_time("# \n\n---", 200), 0);
+}
+
+#[test]
+fn truncate_safe_does_not_cut_entities_or_tags() {
+    let filter = |characters: u8| Filter::Truncate { characters, trail: "...".to_string(), safe: true };
+    let input = "Fish &amp; Chips".to_string();
+
+    // Cutting just before and just after the entity is unaffected.
+    assert_eq!(render_filter(input.clone(), &filter(5)), "Fish ...");
+    assert_eq!(render_filter(input.clone(), &filter(10)), "Fish &amp;...");
+    // Cutting inside the entity cuts before it instead.
+    assert_eq!(render_filter(input.clone(), &filter(7)), "Fish ...");
+    assert_eq!(render_filter(input.clone(), &filter(9)), "Fish ...");
+    // Without `safe`, the entity is cut.
+    let unsafe_filter = Filter::Truncate { characters: 7, trail: "...".to_string(), safe: false };
+    assert_eq!(render_filter(input, &unsafe_filter), "Fish &a...");
+
+    // Cutting inside a tag cuts before it.
+    let input = "Hello <strong>World</strong>".to_string();
+    assert_eq!(render_filter(input.clone(), &filter(10)), "Hello ...");
+    assert_eq!(render_filter(input, &filter(16)), "Hello <strong>Wo...");
+
+    // An `&` that isn't an entity is cut as usual.
+    assert_eq!(render_filter("Salt & Pepper".to_string(), &filter(8)), "Salt & P...");
+
+    // A short value is unchanged.
+    assert_eq!(render_filter("&amp;".to_string(), &filter(10)), "&amp;");
+}
+
+#[test]
+fn filter_url_join_works() {
+    let (_, placeholder) = parse_placeholder(Span::new("{{ £path | url_join = base: https://example.com/ }}")).unwrap();
+    let output = render_filter("/posts/one.html".to_string(), &placeholder.filters[0]);
+    assert_eq!(output, "https://example.com/posts/one.html");
+
+    // Without a base, the value is unchanged.
+    let output = render_filter("/posts/one.html".to_string(), &Filter::UrlJoin { base: String::new() });
+    assert_eq!(output, "/posts/one.html");
+
+    // Only a scheme at the start makes the value absolute.
+    let filter = &placeholder.filters[0];
+    assert_eq!(render_filter("tel:+441234".to_string(), filter), "tel:+441234");
+    assert_eq!(render_filter("svn+ssh://host/repo".to_string(), filter), "svn+ssh://host/repo");
+    assert_eq!(render_filter("/login?next=https://x".to_string(), filter), "https://example.com/login?next=https://x");
+    assert_eq!(render_filter("posts/a:b".to_string(), filter), "https://example.com/posts/a:b");
+    assert_eq!(render_filter("1st:post".to_string(), filter), "https://example.com/1st:post");
+}
+
+#[test]
+fn filter_truncate_middle_counts_characters() {
+    let filter = Filter::TruncateMiddle { characters: 7, trail: "…".to_string() };
+    assert_eq!(render_filter("£££££££££££".to_string(), &filter), "£££…£££");
+
+    // A short value is unchanged.
+    assert_eq!(render_filter("£££££££".to_string(), &filter), "£££££££");
+
+    // A trail longer than the length only keeps the trail.
+    let filter = Filter::TruncateMiddle { characters: 2, trail: "...".to_string() };
+    assert_eq!(render_filter("abcdef".to_string(), &filter), "...");
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // Integration tests
 
@@ -845,7 +2354,7 @@ fn can_replace_placeholder_from_meta() {
     let template = Span::new("<html>\n<head>\n<title>{{ £title }}</title>\n</head>\n<body>\n<h1>{{ £title }}</h1>\n<small>By {{ £author }}</small>\n<section>{{ £content }}</section>\n</body>\n</html>");
 
     let mut placeholders = parse_placeholder_locations(template).expect("to parse placeholders");
-    placeholders.sort_by(|a, b| b.selection.start.offset.cmp(&a.selection.start.offset));
+    placeholders.sort_by_key(|placeholder| std::cmp::Reverse(placeholder.selection.start.offset));
 
     let mut placeholder_title_iter = placeholders.iter().filter(|p| &p.name == "title");
     assert!(placeholder_title_iter.clone().count() == 2);
@@ -877,7 +2386,7 @@ fn can_replace_placeholder_from_meta() {
         Meta::new("title", "Meta title"),
         Meta::new("author", "John Doe"),
     ]);
-    let variables: HashMap<String, String> = create_variables(markdown, meta_values).expect("to create variables");
+    let variables: HashMap<String, String> = create_variables(markdown, meta_values, 1).expect("to create variables");
 
     let mut html_doc = template.to_string();
     for placeholder in &placeholders {
@@ -897,3 +2406,89 @@ fn can_replace_placeholder_from_meta() {
 
     assert_eq!(html_doc, "<html>\n<head>\n<title>Meta title</title>\n</head>\n<body>\n<h1>Meta title</h1>\n<small>By John Doe</small>\n<section><h1>Markdown title</h1>\n<p>This is my content</p></section>\n</body>\n</html>");
 }
+
+#[test]
+fn verbose_prints_each_rendered_file() {
+    let dir = tempfile::Builder::new().prefix("blogs-md-easy-").tempdir().expect("to create directory");
+    let dir = dir.path();
+    fs::write(dir.join("template.html"), "<h1>{{ £title }}</h1>{{ £content }}").expect("to write template");
+    fs::write(dir.join("one.md"), "# One
+
+Some content.").expect("to write markdown");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_blogs-md-easy"))
+        .args(["-t", "template.html", "-m", "one.md", "--verbose"])
+        .current_dir(dir)
+        .output()
+        .expect("to run the binary");
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).expect("stdout to be UTF-8");
+    let lines = stdout.lines().collect::<Vec<&str>>();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("one.md -> one.html (2 variables, "), "{}", lines[0]);
+    assert!(lines[1].starts_with("Rendered 1 file with 0 warnings in "), "{}", lines[1]);
+    assert!(dir.join("one.html").exists());
+
+    // Without it, nothing is printed.
+    let output = Command::new(env!("CARGO_BIN_EXE_blogs-md-easy"))
+        .args(["-t", "template.html", "-m", "one.md"])
+        .current_dir(dir)
+        .output()
+        .expect("to run the binary");
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Minification
+
+#[test]
+fn can_minify_html() {
+    let html = "<main>\n    <h1>Title</h1>\n\n    <p>Some   text\nover two lines.</p>\n</main>\n";
+    assert_eq!(minify_html(html), "<main> <h1>Title</h1> <p>Some text over two lines.</p> </main>");
+}
+
+#[test]
+fn minify_preserves_pre_blocks() {
+    let html = "<div>\n  <pre class=\"rust\">fn main() {\n    let  x = 1;\n}</pre>\n  <p>After   pre.</p>\n</div>";
+    assert_eq!(minify_html(html), "<div> <pre class=\"rust\">fn main() {\n    let  x = 1;\n}</pre> <p>After pre.</p> </div>");
+}
+
+#[test]
+fn minify_preserves_nested_code_in_pre() {
+    let html = "<PRE><code>a  <b>  c\n</b></code>\n\n  </PRE>  <p> x </p>";
+    assert_eq!(minify_html(html), "<PRE><code>a  <b>  c\n</b></code>\n\n  </PRE> <p> x </p>");
+}
+
+#[test]
+fn minify_preserves_unclosed_pre() {
+    let html = "<p>a   b</p>\n<pre>  never\n  closed";
+    assert_eq!(minify_html(html), "<p>a b</p> <pre>  never\n  closed");
+}
+
+#[test]
+fn minify_does_not_preserve_similar_tags() {
+    let html = "<premium>  a  </premium>";
+    assert_eq!(minify_html(html), "<premium> a </premium>");
+}
+
+#[test]
+fn can_render_rss_item() {
+    let input = Span::new(":meta\nlink = https://example.com/?a=1&b=2\n:meta\n# Fish & Chips <Updated>\nIt's the best.");
+    let template = Span::new("<item>\n<title>{{ £title | escape_xml }}</title>\n<link>{{ £link | escape_xml }}</link>\n</item>");
+
+    let (markdown, meta_values) = parse_meta_section(input).expect("to parse meta");
+    let variables = create_variables(markdown, meta_values, 1).expect("to create variables");
+
+    let mut placeholders = parse_placeholder_locations(template).expect("to parse placeholders");
+    placeholders.sort_by_key(|placeholder| std::cmp::Reverse(placeholder.selection.start.offset));
+
+    let mut rss = template.to_string();
+    for placeholder in &placeholders {
+        let variable = placeholder.apply(variables[&placeholder.name].to_owned());
+        rss = replace_substring(&rss, placeholder.selection.start.offset, placeholder.selection.end.offset, &variable);
+    }
+
+    assert_eq!(rss, "<item>\n<title>Fish &amp; Chips &lt;Updated&gt;</title>\n<link>https://example.com/?a=1&amp;b=2</link>\n</item>");
+}